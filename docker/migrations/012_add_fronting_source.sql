@@ -0,0 +1,3 @@
+CREATE TYPE fronting_source_enum AS ENUM ('SimplyPlural');
+
+ALTER TABLE users ADD COLUMN fronting_source fronting_source_enum NOT NULL DEFAULT 'SimplyPlural';
@@ -0,0 +1 @@
+ALTER TYPE fronting_source_enum ADD VALUE 'PluralKit';
@@ -0,0 +1,4 @@
+ALTER TABLE users ADD COLUMN website_show_custom_status BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN discord_show_custom_status BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN discord_status_message_show_custom_status BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN vrchat_show_custom_status BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,3 @@
+CREATE TYPE group_collapsing_enum AS ENUM ('Never', 'WhenTooLong', 'Always');
+
+ALTER TABLE users ADD COLUMN status_group_collapsing group_collapsing_enum NOT NULL DEFAULT 'WhenTooLong';
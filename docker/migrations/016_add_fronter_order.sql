@@ -0,0 +1,4 @@
+CREATE TYPE fronter_order_enum AS ENUM ('SourceOrder', 'StartTimeNewestFirst', 'StartTimeOldestFirst', 'Alphabetical', 'Manual');
+
+ALTER TABLE users ADD COLUMN fronter_order fronter_order_enum NOT NULL DEFAULT 'SourceOrder';
+ALTER TABLE users ADD COLUMN fronter_order_manual TEXT[];
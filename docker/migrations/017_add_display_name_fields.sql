@@ -0,0 +1,4 @@
+ALTER TABLE users ADD COLUMN vrchat_display_name_field TEXT;
+ALTER TABLE users ADD COLUMN discord_display_name_field TEXT;
+ALTER TABLE users ADD COLUMN discord_status_message_display_name_field TEXT;
+ALTER TABLE users ADD COLUMN website_display_name_field TEXT;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS front_history (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    fronter_ids TEXT[] NOT NULL,
+    fronter_names TEXT[] NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS front_history_user_id_changed_at_idx ON front_history (user_id, changed_at DESC);
+
+ALTER TABLE users ADD COLUMN front_history_retention_days INTEGER CHECK (front_history_retention_days >= 0);
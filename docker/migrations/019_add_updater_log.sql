@@ -0,0 +1,18 @@
+CREATE TYPE updater_platform_enum AS ENUM ('VRChat', 'Discord', 'DiscordStatusMessage', 'ToPluralKit');
+
+CREATE TYPE update_error_category_enum AS ENUM ('Authentication', 'RateLimited', 'PlatformUnavailable', 'RequestRejected', 'Network', 'InvalidResponse', 'Other');
+
+CREATE TABLE IF NOT EXISTS updater_log (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    platform updater_platform_enum NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    payload TEXT,
+    success BOOLEAN NOT NULL,
+    error TEXT,
+    error_category update_error_category_enum,
+    duration_ms INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS updater_log_user_id_updated_at_idx ON updater_log (user_id, updated_at DESC);
@@ -0,0 +1,4 @@
+ALTER TABLE users ADD COLUMN vrchat_status_templates TEXT[];
+ALTER TABLE users ADD COLUMN discord_status_templates TEXT[];
+ALTER TABLE users ADD COLUMN discord_status_message_status_templates TEXT[];
+ALTER TABLE users ADD COLUMN website_status_templates TEXT[];
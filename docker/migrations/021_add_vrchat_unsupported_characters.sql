@@ -0,0 +1,3 @@
+CREATE TYPE unsupported_characters_enum AS ENUM ('Drop', 'Transliterate', 'Placeholder');
+
+ALTER TABLE users ADD COLUMN vrchat_unsupported_characters unsupported_characters_enum NOT NULL DEFAULT 'Drop';
@@ -0,0 +1,19 @@
+ALTER TABLE users ADD COLUMN vrchat_status_prefix TEXT;
+ALTER TABLE users ADD COLUMN discord_status_prefix TEXT;
+ALTER TABLE users ADD COLUMN discord_status_message_status_prefix TEXT;
+ALTER TABLE users ADD COLUMN website_status_prefix TEXT;
+
+ALTER TABLE users ADD COLUMN vrchat_status_no_fronts TEXT;
+ALTER TABLE users ADD COLUMN discord_status_no_fronts TEXT;
+ALTER TABLE users ADD COLUMN discord_status_message_status_no_fronts TEXT;
+ALTER TABLE users ADD COLUMN website_status_no_fronts TEXT;
+
+ALTER TABLE users ADD COLUMN vrchat_status_truncate_names_to INTEGER CHECK (vrchat_status_truncate_names_to > 0);
+ALTER TABLE users ADD COLUMN discord_status_truncate_names_to INTEGER CHECK (discord_status_truncate_names_to > 0);
+ALTER TABLE users ADD COLUMN discord_status_message_status_truncate_names_to INTEGER CHECK (discord_status_message_status_truncate_names_to > 0);
+ALTER TABLE users ADD COLUMN website_status_truncate_names_to INTEGER CHECK (website_status_truncate_names_to > 0);
+
+ALTER TABLE users ADD COLUMN vrchat_status_group_collapsing group_collapsing_enum;
+ALTER TABLE users ADD COLUMN discord_status_group_collapsing group_collapsing_enum;
+ALTER TABLE users ADD COLUMN discord_status_message_status_group_collapsing group_collapsing_enum;
+ALTER TABLE users ADD COLUMN website_status_group_collapsing group_collapsing_enum;
@@ -0,0 +1,5 @@
+ALTER TYPE updater_platform_enum ADD VALUE 'Webhook';
+
+ALTER TABLE users ADD COLUMN enable_webhook BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN webhook_url TEXT;
+ALTER TABLE users ADD COLUMN enc__webhook_signing_secret bytea;
@@ -0,0 +1,6 @@
+ALTER TYPE updater_platform_enum ADD VALUE 'DiscordWebhook';
+
+ALTER TABLE users ADD COLUMN enable_discord_webhook BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN discord_webhook_edit_message BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN discord_webhook_message_id TEXT;
+ALTER TABLE users ADD COLUMN enc__discord_webhook_url bytea;
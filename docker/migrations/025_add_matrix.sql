@@ -0,0 +1,9 @@
+ALTER TYPE updater_platform_enum ADD VALUE 'Matrix';
+
+CREATE TYPE matrix_displayname_scope_enum AS ENUM ('Unchanged', 'Global', 'Rooms');
+
+ALTER TABLE users ADD COLUMN enable_matrix BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN matrix_homeserver_url TEXT;
+ALTER TABLE users ADD COLUMN matrix_displayname_scope matrix_displayname_scope_enum NOT NULL DEFAULT 'Unchanged';
+ALTER TABLE users ADD COLUMN matrix_displayname_rooms TEXT[];
+ALTER TABLE users ADD COLUMN enc__matrix_access_token bytea;
@@ -0,0 +1,7 @@
+ALTER TYPE updater_platform_enum ADD VALUE 'Mastodon';
+
+ALTER TABLE users ADD COLUMN enable_mastodon BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN mastodon_instance_url TEXT;
+ALTER TABLE users ADD COLUMN mastodon_field_name TEXT;
+ALTER TABLE users ADD COLUMN mastodon_display_name TEXT;
+ALTER TABLE users ADD COLUMN enc__mastodon_token bytea;
@@ -0,0 +1,7 @@
+ALTER TYPE updater_platform_enum ADD VALUE 'Bluesky';
+
+ALTER TABLE users ADD COLUMN enable_bluesky BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN bluesky_pds_url TEXT;
+ALTER TABLE users ADD COLUMN bluesky_identifier TEXT;
+ALTER TABLE users ADD COLUMN bluesky_display_name TEXT;
+ALTER TABLE users ADD COLUMN enc__bluesky_app_password bytea;
@@ -0,0 +1,11 @@
+ALTER TYPE updater_platform_enum ADD VALUE 'Mqtt';
+
+ALTER TABLE users ADD COLUMN enable_mqtt BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN mqtt_broker_url TEXT;
+ALTER TABLE users ADD COLUMN mqtt_username TEXT;
+ALTER TABLE users ADD COLUMN mqtt_fronters_topic TEXT;
+ALTER TABLE users ADD COLUMN mqtt_status_topic TEXT;
+ALTER TABLE users ADD COLUMN mqtt_fronter_count_topic TEXT;
+ALTER TABLE users ADD COLUMN mqtt_home_assistant_discovery BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE users ADD COLUMN mqtt_home_assistant_discovery_prefix TEXT;
+ALTER TABLE users ADD COLUMN enc__mqtt_password bytea;
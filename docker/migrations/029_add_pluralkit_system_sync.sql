@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN enable_pluralkit_system_sync BOOLEAN NOT NULL DEFAULT false;
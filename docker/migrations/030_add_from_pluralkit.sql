@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN enable_from_pluralkit BOOLEAN NOT NULL DEFAULT false;
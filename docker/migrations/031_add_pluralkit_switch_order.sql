@@ -0,0 +1,7 @@
+CREATE TYPE pluralkit_switch_order_enum AS ENUM ('PreservePluralKitOrder', 'SimplyPluralOrder', 'StartTimeNewestFirst', 'StartTimeOldestFirst', 'PrimaryMemberFirst');
+
+ALTER TABLE users ADD COLUMN pluralkit_switch_order pluralkit_switch_order_enum NOT NULL DEFAULT 'PreservePluralKitOrder';
+ALTER TABLE users ADD COLUMN pluralkit_switch_primary_member TEXT;
+
+-- the order of the existing switch used to be preserved only, if no specific fronter order was chosen
+UPDATE users SET pluralkit_switch_order = 'SimplyPluralOrder' WHERE fronter_order <> 'SourceOrder';
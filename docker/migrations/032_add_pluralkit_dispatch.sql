@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN enc__pluralkit_dispatch_signing_token bytea;
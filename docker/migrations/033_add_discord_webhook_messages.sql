@@ -0,0 +1,10 @@
+-- the announcement is edited in the background. a separate table keeps config saves from overwriting its id.
+CREATE TABLE IF NOT EXISTS discord_webhook_messages (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    message_id TEXT NOT NULL
+);
+
+INSERT INTO discord_webhook_messages (user_id, message_id)
+SELECT id, discord_webhook_message_id FROM users WHERE discord_webhook_message_id IS NOT NULL;
+
+ALTER TABLE users DROP COLUMN discord_webhook_message_id;
@@ -0,0 +1,3 @@
+-- keyed hash of the dispatch signing token. it's checked before anything of the user is decrypted.
+-- it's filled in on the next config save. until then, the encrypted token is used.
+ALTER TABLE users ADD COLUMN pluralkit_dispatch_token_hash TEXT;
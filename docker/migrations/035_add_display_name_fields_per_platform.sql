@@ -0,0 +1,15 @@
+-- one mapping instead of a column per platform. a missing platform uses the default field, an empty one disables it.
+ALTER TABLE users ADD COLUMN display_name_fields JSONB NOT NULL DEFAULT '{}';
+
+UPDATE users SET display_name_fields = jsonb_strip_nulls(jsonb_build_object(
+    'VRChat', vrchat_display_name_field,
+    'Discord', discord_display_name_field,
+    'DiscordStatusMessage', discord_status_message_display_name_field,
+    'Website', website_display_name_field
+));
+
+ALTER TABLE users
+    DROP COLUMN vrchat_display_name_field,
+    DROP COLUMN discord_display_name_field,
+    DROP COLUMN discord_status_message_display_name_field,
+    DROP COLUMN website_display_name_field;
@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN updater_log_retention_days INTEGER CHECK (updater_log_retention_days >= 0);
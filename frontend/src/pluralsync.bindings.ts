@@ -13,27 +13,132 @@ export type UserConfigDbEntries = {
     status_truncate_names_to?: number;
     privacy_fine_grained?: PrivacyFineGrained;
     privacy_fine_grained_buckets?: string[];
+    fronting_source?: FrontingSourceKind;
     show_members_non_archived?: boolean;
     show_members_archived?: boolean;
     show_custom_fronts?: boolean;
     respect_front_notifications_disabled?: boolean;
+    website_show_custom_status?: boolean;
+    discord_show_custom_status?: boolean;
+    discord_status_message_show_custom_status?: boolean;
+    vrchat_show_custom_status?: boolean;
+    status_group_collapsing?: GroupCollapsing;
+    fronter_order?: FronterOrder;
+    fronter_order_manual?: string[];
+    display_name_fields?: DisplayNameFields;
+    front_history_retention_days?: number;
+    updater_log_retention_days?: number;
+    vrchat_status_templates?: string[];
+    discord_status_templates?: string[];
+    discord_status_message_status_templates?: string[];
+    website_status_templates?: string[];
+    vrchat_unsupported_characters?: UnsupportedCharacters;
+    vrchat_status_prefix?: string;
+    discord_status_prefix?: string;
+    discord_status_message_status_prefix?: string;
+    website_status_prefix?: string;
+    vrchat_status_no_fronts?: string;
+    discord_status_no_fronts?: string;
+    discord_status_message_status_no_fronts?: string;
+    website_status_no_fronts?: string;
+    vrchat_status_truncate_names_to?: number;
+    discord_status_truncate_names_to?: number;
+    discord_status_message_status_truncate_names_to?: number;
+    website_status_truncate_names_to?: number;
+    vrchat_status_group_collapsing?: GroupCollapsing;
+    discord_status_group_collapsing?: GroupCollapsing;
+    discord_status_message_status_group_collapsing?: GroupCollapsing;
+    website_status_group_collapsing?: GroupCollapsing;
     enable_website?: boolean;
     enable_discord?: boolean;
     enable_discord_status_message?: boolean;
     enable_vrchat?: boolean;
     enable_to_pluralkit?: boolean;
+    enable_webhook?: boolean;
+    webhook_url?: string;
+    enable_discord_webhook?: boolean;
+    discord_webhook_edit_message?: boolean;
+    enable_matrix?: boolean;
+    matrix_homeserver_url?: string;
+    matrix_displayname_scope?: MatrixDisplayNameScope;
+    matrix_displayname_rooms?: string[];
+    enable_mastodon?: boolean;
+    mastodon_instance_url?: string;
+    mastodon_field_name?: string;
+    mastodon_display_name?: string;
+    enable_bluesky?: boolean;
+    bluesky_pds_url?: string;
+    bluesky_identifier?: string;
+    bluesky_display_name?: string;
+    enable_mqtt?: boolean;
+    mqtt_broker_url?: string;
+    mqtt_username?: string;
+    mqtt_fronters_topic?: string;
+    mqtt_status_topic?: string;
+    mqtt_fronter_count_topic?: string;
+    mqtt_home_assistant_discovery?: boolean;
+    mqtt_home_assistant_discovery_prefix?: string;
+    enable_pluralkit_system_sync?: boolean;
+    enable_from_pluralkit?: boolean;
+    pluralkit_switch_order?: PluralKitSwitchOrder;
+    pluralkit_switch_primary_member?: string;
     simply_plural_token?: Decrypted;
     discord_status_message_token?: Decrypted;
     vrchat_username?: Decrypted;
     vrchat_password?: Decrypted;
     vrchat_cookie?: Decrypted;
     pluralkit_token?: Decrypted;
+    webhook_signing_secret?: Decrypted;
+    discord_webhook_url?: Decrypted;
+    matrix_access_token?: Decrypted;
+    mastodon_token?: Decrypted;
+    bluesky_app_password?: Decrypted;
+    mqtt_password?: Decrypted;
+    pluralkit_dispatch_signing_token?: Decrypted;
 };
 export type PrivacyFineGrained = "NoFineGrained" | "ViaFriend" | "ViaPrivacyBuckets";
+/**
+ * The system manager which is the source of truth for the fronters of a user.
+ */
+export type FrontingSourceKind = "SimplyPlural" | "PluralKit";
+/**
+ * When to show the name of a group (e.g. "Littles ×3") instead of its fronting members.
+ */
+export type GroupCollapsing = "Never" | "WhenTooLong" | "Always";
+/**
+ * What to do with characters outside of the encoding of the status, e.g. "さくら", "Сергей" or emoji.
+ */
+export type UnsupportedCharacters = "Drop" | "Transliterate" | "Placeholder";
+/**
+ * Where the displayname on Matrix is set to the fronting status.
+ */
+export type MatrixDisplayNameScope = "Unchanged" | "Global" | "Rooms";
+/**
+ * The order of the members in the switches created in `PluralKit`.
+ */
+export type PluralKitSwitchOrder = "PreservePluralKitOrder" | "SimplyPluralOrder" | "StartTimeNewestFirst" | "StartTimeOldestFirst" | "PrimaryMemberFirst";
+/**
+ * The order in which the fronters are shown on all platforms.
+ */
+export type FronterOrder = "SourceOrder" | "StartTimeNewestFirst" | "StartTimeOldestFirst" | "Alphabetical" | "Manual";
+/**
+ * The platforms for which a custom field can be configured as the display name of members.
+ */
+export type DisplayNamePlatform = "VRChat" | "Discord" | "DiscordStatusMessage" | "Website" | "Webhook" | "DiscordWebhook" | "Matrix" | "Mastodon" | "Bluesky" | "Mqtt" | "Generic";
+export type DisplayNameFields = { [p in DisplayNamePlatform]?: string };
 export type JwtString = { inner: string };
-export type Platform = "VRChat" | "Discord" | "DiscordStatusMessage" | "ToPluralKit";
+export type UserInfoUI = { id: { inner: string }; email: Email; created_at: string };
+export type Platform = "VRChat" | "Discord" | "DiscordStatusMessage" | "ToPluralKit" | "Webhook" | "DiscordWebhook" | "Matrix" | "Mastodon" | "Bluesky" | "Mqtt";
+/**
+ * Coarse classification of failed updates, such that users can tell apart e.g. an expired login from an outage.
+ */
+export type UpdateErrorCategory = "Authentication" | "RateLimited" | "PlatformUnavailable" | "RequestRejected" | "Network" | "InvalidResponse" | "Other";
 export type UpdaterStatus = "Disabled" | "Running" | { "Error": string } | "Starting";
 export type UserUpdatersStatuses = { [p in Platform]?: UpdaterStatus };
+export type FrontingSourceStatus = { source: FrontingSourceKind; status: UpdaterStatus };
+export type UpdaterLogEntry = { platform: Platform; updated_at: string; payload: string | null; success: boolean; error: string | null; error_category: UpdateErrorCategory | null; duration_ms: number };
+export type FrontHistoryEntry = { changed_at: string; fronter_ids: string[]; fronter_names: string[] };
+export type FrontHistoryPage = { entries: FrontHistoryEntry[]; next_page: number | null };
 export type GenericFrontingStatus = { inner: string };
 export type VRChatCredentials = { username: string; password: string };
 export type VRChatCredentialsWithCookie = { creds: VRChatCredentials; cookie: string };
@@ -41,4 +146,8 @@ export type TwoFactorAuthMethod = "TwoFactorAuthMethodEmail" | "TwoFactorAuthMet
 export type TwoFactorCodeRequiredResponse = { method: TwoFactorAuthMethod; tmp_cookie: string };
 export type TwoFactorAuthCode = { inner: string };
 export type VRChatCredentialsWithTwoFactorAuth = { creds: VRChatCredentials; method: TwoFactorAuthMethod; code: TwoFactorAuthCode; tmp_cookie: string };
-export type VRChatAuthResponse = { Left: VRChatCredentialsWithCookie } | { Right: TwoFactorCodeRequiredResponse };
\ No newline at end of file
+export type VRChatAuthResponse = { Left: VRChatCredentialsWithCookie } | { Right: TwoFactorCodeRequiredResponse };
+export type PluralKitMemberField = "name" | "avatar_url" | "pronouns" | "color" | "description";
+export type PluralKitMemberFieldChange = { field: PluralKitMemberField; old_value: string | null; new_value: string };
+export type PluralKitMemberSyncChange = { simply_plural_member_id: string; name: string; action: "Create" | "Update"; pluralkit_id: string | null; link_pluralkit_id: boolean; field_changes: PluralKitMemberFieldChange[] };
+export type PluralKitSystemSyncPlan = { changes: PluralKitMemberSyncChange[]; unchanged_members_count: number; version: string };
\ No newline at end of file
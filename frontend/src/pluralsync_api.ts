@@ -9,7 +9,12 @@ import type {
   VRChatCredentialsWithCookie,
   VRChatCredentialsWithTwoFactorAuth,
   VRChatAuthResponse,
+  PluralKitSystemSyncPlan,
   GenericFrontingStatus,
+  FrontingSourceStatus,
+  FrontHistoryPage,
+  UpdaterLogEntry,
+  UserInfoUI,
 } from './pluralsync.bindings'
 import { getJwt, logoutAndBackToStart, setJwt } from './jwt'
 import router from './router'
@@ -45,6 +50,13 @@ export const pluralsync_api = {
   register: async function (creds: UserLoginCredentials): Promise<void> {
     await http.post('/api/user/register', creds)
   },
+  get_user_info: async function (): Promise<UserInfoUI> {
+    const jwtString = await getJwt()
+    const response = await http.get<UserInfoUI>('/api/user/info', {
+      headers: { Authorization: `Bearer ${jwtString.inner}` },
+    })
+    return response.data
+  },
   get_updater_status: async function (): Promise<UserUpdatersStatuses> {
     const jwtString = await getJwt()
     const response = await http.get<UserUpdatersStatuses>('/api/updaters/status', {
@@ -52,6 +64,21 @@ export const pluralsync_api = {
     })
     return response.data
   },
+  get_updaters_log: async function (limit: number): Promise<UpdaterLogEntry[]> {
+    const jwtString = await getJwt()
+    const response = await http.get<UpdaterLogEntry[]>('/api/updaters/log', {
+      params: { limit },
+      headers: { Authorization: `Bearer ${jwtString.inner}` },
+    })
+    return response.data
+  },
+  get_fronting_source_status: async function (): Promise<FrontingSourceStatus> {
+    const jwtString = await getJwt()
+    const response = await http.get<FrontingSourceStatus>('/api/fronting-source/status', {
+      headers: { Authorization: `Bearer ${jwtString.inner}` },
+    })
+    return response.data
+  },
   get_config: async function (): Promise<UserConfigDbEntries> {
     const jwtString = await getJwt()
     const response = await http.get<UserConfigDbEntries>('/api/user/config', {
@@ -76,6 +103,14 @@ export const pluralsync_api = {
     })
     return response.data
   },
+  get_front_history: async function (page: number, page_size: number): Promise<FrontHistoryPage> {
+    const jwtString = await getJwt()
+    const response = await http.get<FrontHistoryPage>('/api/user/front-history', {
+      params: { page, page_size },
+      headers: { Authorization: `Bearer ${jwtString.inner}` },
+    })
+    return response.data
+  },
   vrchat_request_2fa: async function (creds: VRChatCredentials): Promise<VRChatAuthResponse> {
     const jwtString = await getJwt()
     const response = await http.post<VRChatAuthResponse>(
@@ -96,6 +131,28 @@ export const pluralsync_api = {
     )
     return response.data
   },
+  pluralkit_system_sync_preview: async function (): Promise<PluralKitSystemSyncPlan> {
+    const jwtString = await getJwt()
+    const response = await http.get<PluralKitSystemSyncPlan>(
+      '/api/user/platform/pluralkit/system_sync/preview',
+      { headers: { Authorization: `Bearer ${jwtString.inner}` } },
+    )
+    return response.data
+  },
+  pluralkit_system_sync_apply: async function (
+    plan_version: string,
+  ): Promise<PluralKitSystemSyncPlan> {
+    const jwtString = await getJwt()
+    const response = await http.post<PluralKitSystemSyncPlan>(
+      '/api/user/platform/pluralkit/system_sync/apply',
+      {},
+      {
+        headers: { Authorization: `Bearer ${jwtString.inner}` },
+        params: { plan_version },
+      },
+    )
+    return response.data
+  },
   get_variant_info: async function (): Promise<PluralSyncVariantInfo> {
     const response = await http.get<PluralSyncVariantInfo>('/api/meta/pluralsync-variant-info')
     return response.data
@@ -0,0 +1,25 @@
+use anyhow::{Result, anyhow};
+use pluralsync::plurality::mock_simply_plural;
+use pluralsync::setup;
+use std::env;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    setup::logging_init();
+
+    let system_file = env::var("MOCK_SIMPLY_PLURAL_SYSTEM_FILE")
+        .unwrap_or_else(|_| "test/mock-simply-plural-system.json".to_owned());
+    let token = env::var("MOCK_SIMPLY_PLURAL_TOKEN")?;
+
+    log::info!("# | mock-simply-plural | loading system from {system_file}");
+
+    let system: mock_simply_plural::MockSystemFile =
+        serde_json::from_str(&std::fs::read_to_string(&system_file)?)?;
+
+    let _ = mock_simply_plural::build(token, system)
+        .launch()
+        .await
+        .map_err(|e| anyhow!(e))?;
+
+    Ok(())
+}
@@ -33,6 +33,7 @@ async fn main() -> Result<()> {
 
     plurality::auto_reconnecting_websocket_client_to_simply_plural(
         "global-mgr",
+        &setup::simply_plural_websocket_url_from_env(),
         &token,
         |ev| process_event(&token, ev),
         || accept_all_friend_requests(&token),
@@ -66,9 +67,11 @@ async fn accept_all_friend_requests(token: &str) -> Result<()> {
     log::info!("Fetching all friend requests and accepting them...");
     let client = setup::make_client()?;
 
-    let incoming_requests_url = "https://api.apparyllis.com/v1/friends/requests/incoming";
+    let base_url = setup::simply_plural_base_url_from_env();
+
+    let incoming_requests_url = format!("{base_url}/friends/requests/incoming");
     let response = client
-        .get(incoming_requests_url)
+        .get(&incoming_requests_url)
         .header("Authorization", token)
         .send()
         .await?
@@ -81,7 +84,7 @@ async fn accept_all_friend_requests(token: &str) -> Result<()> {
 
     for friend_request in &friend_requests {
         let url = format!(
-            "https://api.apparyllis.com/v1/friends/request/respond/{}?accepted=true",
+            "{base_url}/friends/request/respond/{}?accepted=true",
             friend_request.from_user_id
         );
         let _ = client
@@ -2,11 +2,12 @@ use anyhow::Result;
 use pluralsync::{
     database::Decrypted,
     platforms::{
-        TwoFactorAuthCode, TwoFactorAuthMethod, TwoFactorCodeRequiredResponse, VRChatCredentials,
+        MatrixDisplayNameScope, PluralKitSwitchOrder, TwoFactorAuthCode, TwoFactorAuthMethod, TwoFactorCodeRequiredResponse, VRChatCredentials,
         VRChatCredentialsWithCookie, VRChatCredentialsWithTwoFactorAuth,
         webview_api::GenericFrontingStatus,
     },
-    updater::Platform,
+    plurality::{DisplayNamePlatform, FronterOrder, FrontingSourceKind, GroupCollapsing, UnsupportedCharacters},
+    updater::{Platform, UpdateErrorCategory},
     users::PrivacyFineGrained,
 };
 use pluralsync_base::{
@@ -37,27 +38,108 @@ fn main() -> Result<()> {
     status_truncate_names_to?: number;
     privacy_fine_grained?: PrivacyFineGrained;
     privacy_fine_grained_buckets?: string[];
+    fronting_source?: FrontingSourceKind;
     show_members_non_archived?: boolean;
     show_members_archived?: boolean;
     show_custom_fronts?: boolean;
     respect_front_notifications_disabled?: boolean;
+    website_show_custom_status?: boolean;
+    discord_show_custom_status?: boolean;
+    discord_status_message_show_custom_status?: boolean;
+    vrchat_show_custom_status?: boolean;
+    status_group_collapsing?: GroupCollapsing;
+    fronter_order?: FronterOrder;
+    fronter_order_manual?: string[];
+    display_name_fields?: DisplayNameFields;
+    front_history_retention_days?: number;
+    updater_log_retention_days?: number;
+    vrchat_status_templates?: string[];
+    discord_status_templates?: string[];
+    discord_status_message_status_templates?: string[];
+    website_status_templates?: string[];
+    vrchat_unsupported_characters?: UnsupportedCharacters;
+    vrchat_status_prefix?: string;
+    discord_status_prefix?: string;
+    discord_status_message_status_prefix?: string;
+    website_status_prefix?: string;
+    vrchat_status_no_fronts?: string;
+    discord_status_no_fronts?: string;
+    discord_status_message_status_no_fronts?: string;
+    website_status_no_fronts?: string;
+    vrchat_status_truncate_names_to?: number;
+    discord_status_truncate_names_to?: number;
+    discord_status_message_status_truncate_names_to?: number;
+    website_status_truncate_names_to?: number;
+    vrchat_status_group_collapsing?: GroupCollapsing;
+    discord_status_group_collapsing?: GroupCollapsing;
+    discord_status_message_status_group_collapsing?: GroupCollapsing;
+    website_status_group_collapsing?: GroupCollapsing;
     enable_website?: boolean;
     enable_discord?: boolean;
     enable_discord_status_message?: boolean;
     enable_vrchat?: boolean;
     enable_to_pluralkit?: boolean;
+    enable_webhook?: boolean;
+    webhook_url?: string;
+    enable_discord_webhook?: boolean;
+    discord_webhook_edit_message?: boolean;
+    enable_matrix?: boolean;
+    matrix_homeserver_url?: string;
+    matrix_displayname_scope?: MatrixDisplayNameScope;
+    matrix_displayname_rooms?: string[];
+    enable_mastodon?: boolean;
+    mastodon_instance_url?: string;
+    mastodon_field_name?: string;
+    mastodon_display_name?: string;
+    enable_bluesky?: boolean;
+    bluesky_pds_url?: string;
+    bluesky_identifier?: string;
+    bluesky_display_name?: string;
+    enable_mqtt?: boolean;
+    mqtt_broker_url?: string;
+    mqtt_username?: string;
+    mqtt_fronters_topic?: string;
+    mqtt_status_topic?: string;
+    mqtt_fronter_count_topic?: string;
+    mqtt_home_assistant_discovery?: boolean;
+    mqtt_home_assistant_discovery_prefix?: string;
+    enable_pluralkit_system_sync?: boolean;
+    enable_from_pluralkit?: boolean;
+    pluralkit_switch_order?: PluralKitSwitchOrder;
+    pluralkit_switch_primary_member?: string;
     simply_plural_token?: Decrypted;
     discord_status_message_token?: Decrypted;
     vrchat_username?: Decrypted;
     vrchat_password?: Decrypted;
     vrchat_cookie?: Decrypted;
     pluralkit_token?: Decrypted;
+    webhook_signing_secret?: Decrypted;
+    discord_webhook_url?: Decrypted;
+    matrix_access_token?: Decrypted;
+    mastodon_token?: Decrypted;
+    bluesky_app_password?: Decrypted;
+    mqtt_password?: Decrypted;
+    pluralkit_dispatch_signing_token?: Decrypted;
 }".to_owned(),
         export::<PrivacyFineGrained>(conf)?,
+        export::<FrontingSourceKind>(conf)?,
+        export::<GroupCollapsing>(conf)?,
+        export::<UnsupportedCharacters>(conf)?,
+        export::<MatrixDisplayNameScope>(conf)?,
+        export::<PluralKitSwitchOrder>(conf)?,
+        export::<FronterOrder>(conf)?,
+        export::<DisplayNamePlatform>(conf)?,
+        "export type DisplayNameFields = { [p in DisplayNamePlatform]?: string }".to_owned(),
         export::<JwtString>(conf)?,
+        "export type UserInfoUI = { id: { inner: string }; email: Email; created_at: string }".to_owned(),
         export::<Platform>(conf)?,
+        export::<UpdateErrorCategory>(conf)?,
         "export type UpdaterStatus = \"Disabled\" | \"Running\" | { \"Error\": string } | \"Starting\"".to_owned(),
         "export type UserUpdatersStatuses = { [p in Platform]?: UpdaterStatus }".to_owned(),
+        "export type FrontingSourceStatus = { source: FrontingSourceKind; status: UpdaterStatus }".to_owned(),
+        "export type UpdaterLogEntry = { platform: Platform; updated_at: string; payload: string | null; success: boolean; error: string | null; error_category: UpdateErrorCategory | null; duration_ms: number }".to_owned(),
+        "export type FrontHistoryEntry = { changed_at: string; fronter_ids: string[]; fronter_names: string[] }".to_owned(),
+        "export type FrontHistoryPage = { entries: FrontHistoryEntry[]; next_page: number | null }".to_owned(),
         export::<GenericFrontingStatus>(conf)?,
         export::<VRChatCredentials>(conf)?,
         export::<VRChatCredentialsWithCookie>(conf)?,
@@ -66,6 +148,10 @@ fn main() -> Result<()> {
         export::<TwoFactorAuthCode>(conf)?,
         export::<VRChatCredentialsWithTwoFactorAuth>(conf)?,
         "export type VRChatAuthResponse = { Left: VRChatCredentialsWithCookie } | { Right: TwoFactorCodeRequiredResponse }".to_owned(),
+        "export type PluralKitMemberField = \"name\" | \"avatar_url\" | \"pronouns\" | \"color\" | \"description\"".to_owned(),
+        "export type PluralKitMemberFieldChange = { field: PluralKitMemberField; old_value: string | null; new_value: string }".to_owned(),
+        "export type PluralKitMemberSyncChange = { simply_plural_member_id: string; name: string; action: \"Create\" | \"Update\"; pluralkit_id: string | null; link_pluralkit_id: boolean; field_changes: PluralKitMemberFieldChange[] }".to_owned(),
+        "export type PluralKitSystemSyncPlan = { changes: PluralKitMemberSyncChange[]; unchanged_members_count: number; version: string }".to_owned(),
     ];
     fs::write(DESTINATION, defs.map(|s| s + ";").join("\n"))?;
     println!("Done.");
@@ -82,6 +82,72 @@ pub fn downgrade<Secret: secrets::SecretType, C: ConstraintsType>(
         respect_front_notifications_disabled: value.respect_front_notifications_disabled,
         privacy_fine_grained: value.privacy_fine_grained,
         privacy_fine_grained_buckets: value.privacy_fine_grained_buckets.clone(),
+        fronting_source: value.fronting_source,
+        website_show_custom_status: value.website_show_custom_status,
+        discord_show_custom_status: value.discord_show_custom_status,
+        discord_status_message_show_custom_status: value.discord_status_message_show_custom_status,
+        vrchat_show_custom_status: value.vrchat_show_custom_status,
+        status_group_collapsing: value.status_group_collapsing,
+        fronter_order: value.fronter_order,
+        fronter_order_manual: value.fronter_order_manual.clone(),
+        display_name_fields: value.display_name_fields.clone(),
+        front_history_retention_days: value.front_history_retention_days,
+        updater_log_retention_days: value.updater_log_retention_days,
+        vrchat_status_templates: value.vrchat_status_templates.clone(),
+        discord_status_templates: value.discord_status_templates.clone(),
+        discord_status_message_status_templates: value
+            .discord_status_message_status_templates
+            .clone(),
+        website_status_templates: value.website_status_templates.clone(),
+        vrchat_unsupported_characters: value.vrchat_unsupported_characters,
+        vrchat_status_prefix: value.vrchat_status_prefix.clone(),
+        discord_status_prefix: value.discord_status_prefix.clone(),
+        discord_status_message_status_prefix: value.discord_status_message_status_prefix.clone(),
+        website_status_prefix: value.website_status_prefix.clone(),
+        vrchat_status_no_fronts: value.vrchat_status_no_fronts.clone(),
+        discord_status_no_fronts: value.discord_status_no_fronts.clone(),
+        discord_status_message_status_no_fronts: value
+            .discord_status_message_status_no_fronts
+            .clone(),
+        website_status_no_fronts: value.website_status_no_fronts.clone(),
+        vrchat_status_truncate_names_to: value.vrchat_status_truncate_names_to,
+        discord_status_truncate_names_to: value.discord_status_truncate_names_to,
+        discord_status_message_status_truncate_names_to: value
+            .discord_status_message_status_truncate_names_to,
+        website_status_truncate_names_to: value.website_status_truncate_names_to,
+        vrchat_status_group_collapsing: value.vrchat_status_group_collapsing,
+        discord_status_group_collapsing: value.discord_status_group_collapsing,
+        discord_status_message_status_group_collapsing: value
+            .discord_status_message_status_group_collapsing,
+        website_status_group_collapsing: value.website_status_group_collapsing,
+        enable_webhook: value.enable_webhook,
+        webhook_url: value.webhook_url.clone(),
+        enable_discord_webhook: value.enable_discord_webhook,
+        discord_webhook_edit_message: value.discord_webhook_edit_message,
+        enable_matrix: value.enable_matrix,
+        matrix_homeserver_url: value.matrix_homeserver_url.clone(),
+        matrix_displayname_scope: value.matrix_displayname_scope,
+        matrix_displayname_rooms: value.matrix_displayname_rooms.clone(),
+        enable_mastodon: value.enable_mastodon,
+        mastodon_instance_url: value.mastodon_instance_url.clone(),
+        mastodon_field_name: value.mastodon_field_name.clone(),
+        mastodon_display_name: value.mastodon_display_name.clone(),
+        enable_bluesky: value.enable_bluesky,
+        bluesky_pds_url: value.bluesky_pds_url.clone(),
+        bluesky_identifier: value.bluesky_identifier.clone(),
+        bluesky_display_name: value.bluesky_display_name.clone(),
+        enable_mqtt: value.enable_mqtt,
+        mqtt_broker_url: value.mqtt_broker_url.clone(),
+        mqtt_username: value.mqtt_username.clone(),
+        mqtt_fronters_topic: value.mqtt_fronters_topic.clone(),
+        mqtt_status_topic: value.mqtt_status_topic.clone(),
+        mqtt_fronter_count_topic: value.mqtt_fronter_count_topic.clone(),
+        mqtt_home_assistant_discovery: value.mqtt_home_assistant_discovery,
+        mqtt_home_assistant_discovery_prefix: value.mqtt_home_assistant_discovery_prefix.clone(),
+        enable_pluralkit_system_sync: value.enable_pluralkit_system_sync,
+        enable_from_pluralkit: value.enable_from_pluralkit,
+        pluralkit_switch_order: value.pluralkit_switch_order,
+        pluralkit_switch_primary_member: value.pluralkit_switch_primary_member.clone(),
         enable_website: value.enable_website,
         enable_discord: value.enable_discord,
         enable_discord_status_message: value.enable_discord_status_message,
@@ -93,6 +159,13 @@ pub fn downgrade<Secret: secrets::SecretType, C: ConstraintsType>(
         vrchat_password: value.vrchat_password.clone(),
         vrchat_cookie: value.vrchat_cookie.clone(),
         pluralkit_token: value.pluralkit_token.clone(),
+        webhook_signing_secret: value.webhook_signing_secret.clone(),
+        discord_webhook_url: value.discord_webhook_url.clone(),
+        matrix_access_token: value.matrix_access_token.clone(),
+        mastodon_token: value.mastodon_token.clone(),
+        bluesky_app_password: value.bluesky_app_password.clone(),
+        mqtt_password: value.mqtt_password.clone(),
+        pluralkit_dispatch_signing_token: value.pluralkit_dispatch_signing_token.clone(),
     }
 }
 
@@ -114,6 +187,72 @@ pub fn only_use_this_function_to_mark_validation_after_you_have_actually_validat
         respect_front_notifications_disabled: value.respect_front_notifications_disabled,
         privacy_fine_grained: value.privacy_fine_grained,
         privacy_fine_grained_buckets: value.privacy_fine_grained_buckets.clone(),
+        fronting_source: value.fronting_source,
+        website_show_custom_status: value.website_show_custom_status,
+        discord_show_custom_status: value.discord_show_custom_status,
+        discord_status_message_show_custom_status: value.discord_status_message_show_custom_status,
+        vrchat_show_custom_status: value.vrchat_show_custom_status,
+        status_group_collapsing: value.status_group_collapsing,
+        fronter_order: value.fronter_order,
+        fronter_order_manual: value.fronter_order_manual.clone(),
+        display_name_fields: value.display_name_fields.clone(),
+        front_history_retention_days: value.front_history_retention_days,
+        updater_log_retention_days: value.updater_log_retention_days,
+        vrchat_status_templates: value.vrchat_status_templates.clone(),
+        discord_status_templates: value.discord_status_templates.clone(),
+        discord_status_message_status_templates: value
+            .discord_status_message_status_templates
+            .clone(),
+        website_status_templates: value.website_status_templates.clone(),
+        vrchat_unsupported_characters: value.vrchat_unsupported_characters,
+        vrchat_status_prefix: value.vrchat_status_prefix.clone(),
+        discord_status_prefix: value.discord_status_prefix.clone(),
+        discord_status_message_status_prefix: value.discord_status_message_status_prefix.clone(),
+        website_status_prefix: value.website_status_prefix.clone(),
+        vrchat_status_no_fronts: value.vrchat_status_no_fronts.clone(),
+        discord_status_no_fronts: value.discord_status_no_fronts.clone(),
+        discord_status_message_status_no_fronts: value
+            .discord_status_message_status_no_fronts
+            .clone(),
+        website_status_no_fronts: value.website_status_no_fronts.clone(),
+        vrchat_status_truncate_names_to: value.vrchat_status_truncate_names_to,
+        discord_status_truncate_names_to: value.discord_status_truncate_names_to,
+        discord_status_message_status_truncate_names_to: value
+            .discord_status_message_status_truncate_names_to,
+        website_status_truncate_names_to: value.website_status_truncate_names_to,
+        vrchat_status_group_collapsing: value.vrchat_status_group_collapsing,
+        discord_status_group_collapsing: value.discord_status_group_collapsing,
+        discord_status_message_status_group_collapsing: value
+            .discord_status_message_status_group_collapsing,
+        website_status_group_collapsing: value.website_status_group_collapsing,
+        enable_webhook: value.enable_webhook,
+        webhook_url: value.webhook_url.clone(),
+        enable_discord_webhook: value.enable_discord_webhook,
+        discord_webhook_edit_message: value.discord_webhook_edit_message,
+        enable_matrix: value.enable_matrix,
+        matrix_homeserver_url: value.matrix_homeserver_url.clone(),
+        matrix_displayname_scope: value.matrix_displayname_scope,
+        matrix_displayname_rooms: value.matrix_displayname_rooms.clone(),
+        enable_mastodon: value.enable_mastodon,
+        mastodon_instance_url: value.mastodon_instance_url.clone(),
+        mastodon_field_name: value.mastodon_field_name.clone(),
+        mastodon_display_name: value.mastodon_display_name.clone(),
+        enable_bluesky: value.enable_bluesky,
+        bluesky_pds_url: value.bluesky_pds_url.clone(),
+        bluesky_identifier: value.bluesky_identifier.clone(),
+        bluesky_display_name: value.bluesky_display_name.clone(),
+        enable_mqtt: value.enable_mqtt,
+        mqtt_broker_url: value.mqtt_broker_url.clone(),
+        mqtt_username: value.mqtt_username.clone(),
+        mqtt_fronters_topic: value.mqtt_fronters_topic.clone(),
+        mqtt_status_topic: value.mqtt_status_topic.clone(),
+        mqtt_fronter_count_topic: value.mqtt_fronter_count_topic.clone(),
+        mqtt_home_assistant_discovery: value.mqtt_home_assistant_discovery,
+        mqtt_home_assistant_discovery_prefix: value.mqtt_home_assistant_discovery_prefix.clone(),
+        enable_pluralkit_system_sync: value.enable_pluralkit_system_sync,
+        enable_from_pluralkit: value.enable_from_pluralkit,
+        pluralkit_switch_order: value.pluralkit_switch_order,
+        pluralkit_switch_primary_member: value.pluralkit_switch_primary_member.clone(),
         enable_website: value.enable_website,
         enable_discord: value.enable_discord,
         enable_discord_status_message: value.enable_discord_status_message,
@@ -125,5 +264,12 @@ pub fn only_use_this_function_to_mark_validation_after_you_have_actually_validat
         vrchat_password: value.vrchat_password.clone(),
         vrchat_cookie: value.vrchat_cookie.clone(),
         pluralkit_token: value.pluralkit_token.clone(),
+        webhook_signing_secret: value.webhook_signing_secret.clone(),
+        discord_webhook_url: value.discord_webhook_url.clone(),
+        matrix_access_token: value.matrix_access_token.clone(),
+        mastodon_token: value.mastodon_token.clone(),
+        bluesky_app_password: value.bluesky_app_password.clone(),
+        mqtt_password: value.mqtt_password.clone(),
+        pluralkit_dispatch_signing_token: value.pluralkit_dispatch_signing_token.clone(),
     }
 }
@@ -1,11 +1,12 @@
 use anyhow::{Result, anyhow};
 use pluralsync_base::users::Email;
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 use sqlx::{FromRow, PgPool};
 
 use crate::{
     database::{Decrypted, ValidConstraints, constraints, secrets},
-    setup,
+    plurality, setup, updater,
     users::{self, UserConfigDbEntries, UserId},
 };
 
@@ -63,12 +64,79 @@ pub async fn get_user(
             enable_to_pluralkit,
             privacy_fine_grained,
             privacy_fine_grained_buckets,
+            fronting_source,
+            website_show_custom_status,
+            discord_show_custom_status,
+            discord_status_message_show_custom_status,
+            vrchat_show_custom_status,
+            status_group_collapsing,
+            fronter_order,
+            fronter_order_manual,
+            display_name_fields::text AS display_name_fields,
+            front_history_retention_days,
+            updater_log_retention_days,
+            vrchat_status_templates,
+            discord_status_templates,
+            discord_status_message_status_templates,
+            website_status_templates,
+            vrchat_unsupported_characters,
+            vrchat_status_prefix,
+            discord_status_prefix,
+            discord_status_message_status_prefix,
+            website_status_prefix,
+            vrchat_status_no_fronts,
+            discord_status_no_fronts,
+            discord_status_message_status_no_fronts,
+            website_status_no_fronts,
+            vrchat_status_truncate_names_to,
+            discord_status_truncate_names_to,
+            discord_status_message_status_truncate_names_to,
+            website_status_truncate_names_to,
+            vrchat_status_group_collapsing,
+            discord_status_group_collapsing,
+            discord_status_message_status_group_collapsing,
+            website_status_group_collapsing,
+            enable_webhook,
+            webhook_url,
+            enable_discord_webhook,
+            discord_webhook_edit_message,
+            enable_matrix,
+            matrix_homeserver_url,
+            matrix_displayname_scope,
+            matrix_displayname_rooms,
+            enable_mastodon,
+            mastodon_instance_url,
+            mastodon_field_name,
+            mastodon_display_name,
+            enable_bluesky,
+            bluesky_pds_url,
+            bluesky_identifier,
+            bluesky_display_name,
+            enable_mqtt,
+            mqtt_broker_url,
+            mqtt_username,
+            mqtt_fronters_topic,
+            mqtt_status_topic,
+            mqtt_fronter_count_topic,
+            mqtt_home_assistant_discovery,
+            mqtt_home_assistant_discovery_prefix,
+            enable_pluralkit_system_sync,
+            enable_from_pluralkit,
+            pluralkit_switch_order,
+            pluralkit_switch_primary_member,
             '' AS simply_plural_token,
             '' AS discord_status_message_token,
             '' AS vrchat_username,
             '' AS vrchat_password,
             '' AS vrchat_cookie,
             '' AS pluralkit_token,
+            '' AS webhook_signing_secret,
+            '' AS discord_webhook_url,
+            '' AS matrix_access_token,
+            '' AS mastodon_token,
+            '' AS bluesky_app_password,
+            '' AS mqtt_password,
+            '' AS pluralkit_dispatch_signing_token,
             false AS valid_constraints
             FROM users WHERE id = $1",
     )
@@ -78,6 +146,7 @@ pub async fn get_user(
     .map_err(|e| anyhow!(e))
 }
 
+#[allow(clippy::too_many_lines)]
 pub async fn set_user_config_secrets(
     db_pool: &PgPool,
     user_id: &UserId,
@@ -88,6 +157,24 @@ pub async fn set_user_config_secrets(
 
     let secrets_key = compute_user_secrets_key(user_id, application_user_secret);
 
+    // bound as one parameter, such that new secrets only need a line here and in the SET list
+    let secrets = serde_json::json!({
+        "simply_plural_token": config.simply_plural_token.map(|s| s.secret),
+        "discord_status_message_token": config.discord_status_message_token.map(|s| s.secret),
+        "vrchat_username": config.vrchat_username.map(|s| s.secret),
+        "vrchat_password": config.vrchat_password.map(|s| s.secret),
+        "vrchat_cookie": config.vrchat_cookie.map(|s| s.secret),
+        "pluralkit_token": config.pluralkit_token.map(|s| s.secret),
+        "webhook_signing_secret": config.webhook_signing_secret.map(|s| s.secret),
+        "discord_webhook_url": config.discord_webhook_url.map(|s| s.secret),
+        "matrix_access_token": config.matrix_access_token.map(|s| s.secret),
+        "mastodon_token": config.mastodon_token.map(|s| s.secret),
+        "bluesky_app_password": config.bluesky_app_password.map(|s| s.secret),
+        "mqtt_password": config.mqtt_password.map(|s| s.secret),
+        "pluralkit_dispatch_signing_token": config.pluralkit_dispatch_signing_token.map(|s| s.secret),
+    })
+    .to_string();
+
     let _: Option<UserConfigDbEntries<secrets::Decrypted>> = sqlx::query_as(
         "UPDATE users
         SET
@@ -97,22 +184,90 @@ pub async fn set_user_config_secrets(
             status_truncate_names_to = $6,
             enable_discord_status_message = $7,
             enable_vrchat = $8,
-            enc__simply_plural_token = pgp_sym_encrypt($10, $9),
-            enc__discord_status_message_token = pgp_sym_encrypt($11, $9),
-            enc__vrchat_username = pgp_sym_encrypt($12, $9),
-            enc__vrchat_password = pgp_sym_encrypt($13, $9),
-            enc__vrchat_cookie = pgp_sym_encrypt($14, $9),
-            enable_discord = $15,
-            enable_website = $16,
-            website_url_name = $17,
-            show_members_non_archived = $18,
-            show_members_archived = $19,
-            show_custom_fronts = $20,
-            respect_front_notifications_disabled = $21,
-            privacy_fine_grained = $22,
-            privacy_fine_grained_buckets = $23,
-            enable_to_pluralkit = $24,
-            enc__pluralkit_token = pgp_sym_encrypt($25, $9)
+            enc__simply_plural_token = pgp_sym_encrypt($10::jsonb ->> 'simply_plural_token', $9),
+            enc__discord_status_message_token = pgp_sym_encrypt($10::jsonb ->> 'discord_status_message_token', $9),
+            enc__vrchat_username = pgp_sym_encrypt($10::jsonb ->> 'vrchat_username', $9),
+            enc__vrchat_password = pgp_sym_encrypt($10::jsonb ->> 'vrchat_password', $9),
+            enc__vrchat_cookie = pgp_sym_encrypt($10::jsonb ->> 'vrchat_cookie', $9),
+            enable_discord = $11,
+            enable_website = $12,
+            website_url_name = $13,
+            show_members_non_archived = $14,
+            show_members_archived = $15,
+            show_custom_fronts = $16,
+            respect_front_notifications_disabled = $17,
+            privacy_fine_grained = $18,
+            privacy_fine_grained_buckets = $19,
+            enable_to_pluralkit = $20,
+            enc__pluralkit_token = pgp_sym_encrypt($10::jsonb ->> 'pluralkit_token', $9),
+            fronting_source = $21,
+            website_show_custom_status = $22,
+            discord_show_custom_status = $23,
+            discord_status_message_show_custom_status = $24,
+            vrchat_show_custom_status = $25,
+            status_group_collapsing = $26,
+            fronter_order = $27,
+            fronter_order_manual = $28,
+            display_name_fields = $29::jsonb,
+            front_history_retention_days = $30,
+            updater_log_retention_days = $31,
+            vrchat_status_templates = $32,
+            discord_status_templates = $33,
+            discord_status_message_status_templates = $34,
+            website_status_templates = $35,
+            vrchat_unsupported_characters = $36,
+            vrchat_status_prefix = $37,
+            discord_status_prefix = $38,
+            discord_status_message_status_prefix = $39,
+            website_status_prefix = $40,
+            vrchat_status_no_fronts = $41,
+            discord_status_no_fronts = $42,
+            discord_status_message_status_no_fronts = $43,
+            website_status_no_fronts = $44,
+            vrchat_status_truncate_names_to = $45,
+            discord_status_truncate_names_to = $46,
+            discord_status_message_status_truncate_names_to = $47,
+            website_status_truncate_names_to = $48,
+            vrchat_status_group_collapsing = $49,
+            discord_status_group_collapsing = $50,
+            discord_status_message_status_group_collapsing = $51,
+            website_status_group_collapsing = $52,
+            enable_webhook = $53,
+            webhook_url = $54,
+            enc__webhook_signing_secret = pgp_sym_encrypt($10::jsonb ->> 'webhook_signing_secret', $9),
+            enable_discord_webhook = $55,
+            discord_webhook_edit_message = $56,
+            enc__discord_webhook_url = pgp_sym_encrypt($10::jsonb ->> 'discord_webhook_url', $9),
+            enable_matrix = $57,
+            matrix_homeserver_url = $58,
+            matrix_displayname_scope = $59,
+            matrix_displayname_rooms = $60,
+            enc__matrix_access_token = pgp_sym_encrypt($10::jsonb ->> 'matrix_access_token', $9),
+            enable_mastodon = $61,
+            mastodon_instance_url = $62,
+            mastodon_field_name = $63,
+            mastodon_display_name = $64,
+            enc__mastodon_token = pgp_sym_encrypt($10::jsonb ->> 'mastodon_token', $9),
+            enable_bluesky = $65,
+            bluesky_pds_url = $66,
+            bluesky_identifier = $67,
+            bluesky_display_name = $68,
+            enc__bluesky_app_password = pgp_sym_encrypt($10::jsonb ->> 'bluesky_app_password', $9),
+            enable_mqtt = $69,
+            mqtt_broker_url = $70,
+            mqtt_username = $71,
+            mqtt_fronters_topic = $72,
+            mqtt_status_topic = $73,
+            mqtt_fronter_count_topic = $74,
+            mqtt_home_assistant_discovery = $75,
+            mqtt_home_assistant_discovery_prefix = $76,
+            enc__mqtt_password = pgp_sym_encrypt($10::jsonb ->> 'mqtt_password', $9),
+            enable_pluralkit_system_sync = $77,
+            enable_from_pluralkit = $78,
+            pluralkit_switch_order = $79,
+            pluralkit_switch_primary_member = $80,
+            enc__pluralkit_dispatch_signing_token = pgp_sym_encrypt($10::jsonb ->> 'pluralkit_dispatch_signing_token', $9),
+            pluralkit_dispatch_token_hash = encode(hmac($10::jsonb ->> 'pluralkit_dispatch_signing_token', $9, 'sha256'), 'hex')
         WHERE id = $1",
     )
     .bind(user_id.inner)
@@ -124,11 +279,7 @@ pub async fn set_user_config_secrets(
     .bind(config.enable_discord_status_message)
     .bind(config.enable_vrchat)
     .bind(&secrets_key.inner)
-    .bind(config.simply_plural_token.map(|s| s.secret))
-    .bind(config.discord_status_message_token.map(|s| s.secret))
-    .bind(config.vrchat_username.map(|s| s.secret))
-    .bind(config.vrchat_password.map(|s| s.secret))
-    .bind(config.vrchat_cookie.map(|s| s.secret))
+    .bind(secrets)
     .bind(config.enable_discord)
     .bind(config.enable_website)
     .bind(config.website_url_name)
@@ -139,7 +290,66 @@ pub async fn set_user_config_secrets(
     .bind(config.privacy_fine_grained)
     .bind(config.privacy_fine_grained_buckets)
     .bind(config.enable_to_pluralkit)
-    .bind(config.pluralkit_token.map(|s| s.secret))
+    .bind(config.fronting_source)
+    .bind(config.website_show_custom_status)
+    .bind(config.discord_show_custom_status)
+    .bind(config.discord_status_message_show_custom_status)
+    .bind(config.vrchat_show_custom_status)
+    .bind(config.status_group_collapsing)
+    .bind(config.fronter_order)
+    .bind(&config.fronter_order_manual)
+    .bind(serde_json::to_string(&config.display_name_fields)?)
+    .bind(config.front_history_retention_days)
+    .bind(config.updater_log_retention_days)
+    .bind(&config.vrchat_status_templates)
+    .bind(&config.discord_status_templates)
+    .bind(&config.discord_status_message_status_templates)
+    .bind(&config.website_status_templates)
+    .bind(config.vrchat_unsupported_characters)
+    .bind(&config.vrchat_status_prefix)
+    .bind(&config.discord_status_prefix)
+    .bind(&config.discord_status_message_status_prefix)
+    .bind(&config.website_status_prefix)
+    .bind(&config.vrchat_status_no_fronts)
+    .bind(&config.discord_status_no_fronts)
+    .bind(&config.discord_status_message_status_no_fronts)
+    .bind(&config.website_status_no_fronts)
+    .bind(config.vrchat_status_truncate_names_to)
+    .bind(config.discord_status_truncate_names_to)
+    .bind(config.discord_status_message_status_truncate_names_to)
+    .bind(config.website_status_truncate_names_to)
+    .bind(config.vrchat_status_group_collapsing)
+    .bind(config.discord_status_group_collapsing)
+    .bind(config.discord_status_message_status_group_collapsing)
+    .bind(config.website_status_group_collapsing)
+    .bind(config.enable_webhook)
+    .bind(&config.webhook_url)
+    .bind(config.enable_discord_webhook)
+    .bind(config.discord_webhook_edit_message)
+    .bind(config.enable_matrix)
+    .bind(&config.matrix_homeserver_url)
+    .bind(config.matrix_displayname_scope)
+    .bind(&config.matrix_displayname_rooms)
+    .bind(config.enable_mastodon)
+    .bind(&config.mastodon_instance_url)
+    .bind(&config.mastodon_field_name)
+    .bind(&config.mastodon_display_name)
+    .bind(config.enable_bluesky)
+    .bind(&config.bluesky_pds_url)
+    .bind(&config.bluesky_identifier)
+    .bind(&config.bluesky_display_name)
+    .bind(config.enable_mqtt)
+    .bind(&config.mqtt_broker_url)
+    .bind(&config.mqtt_username)
+    .bind(&config.mqtt_fronters_topic)
+    .bind(&config.mqtt_status_topic)
+    .bind(&config.mqtt_fronter_count_topic)
+    .bind(config.mqtt_home_assistant_discovery)
+    .bind(&config.mqtt_home_assistant_discovery_prefix)
+    .bind(config.enable_pluralkit_system_sync)
+    .bind(config.enable_from_pluralkit)
+    .bind(config.pluralkit_switch_order)
+    .bind(&config.pluralkit_switch_primary_member)
     .fetch_optional(db_pool)
     .await
     .map_err(|e| anyhow!(e))?;
@@ -151,13 +361,19 @@ pub async fn get_user_config_with_secrets(
     db_pool: &PgPool,
     user_id: &UserId,
     client: &reqwest::Client,
+    simply_plural_endpoints: &plurality::SimplyPluralEndpoints,
     application_user_secret: &secrets::ApplicationUserSecrets,
 ) -> Result<users::UserConfigForUpdater> {
     log::debug!("# | db::get_user_config_with_secrets | {user_id}");
 
     let config = get_user_secrets(db_pool, user_id, application_user_secret).await?;
 
-    let (config, _) = users::create_config_with_strong_constraints(user_id, client, &config)?;
+    let (config, _) = users::create_config_with_strong_constraints(
+        user_id,
+        client,
+        simply_plural_endpoints,
+        &config,
+    )?;
 
     Ok(config)
 }
@@ -189,12 +405,79 @@ pub async fn get_user_secrets(
             enable_to_pluralkit,
             privacy_fine_grained,
             privacy_fine_grained_buckets,
+            fronting_source,
+            website_show_custom_status,
+            discord_show_custom_status,
+            discord_status_message_show_custom_status,
+            vrchat_show_custom_status,
+            status_group_collapsing,
+            fronter_order,
+            fronter_order_manual,
+            display_name_fields::text AS display_name_fields,
+            front_history_retention_days,
+            updater_log_retention_days,
+            vrchat_status_templates,
+            discord_status_templates,
+            discord_status_message_status_templates,
+            website_status_templates,
+            vrchat_unsupported_characters,
+            vrchat_status_prefix,
+            discord_status_prefix,
+            discord_status_message_status_prefix,
+            website_status_prefix,
+            vrchat_status_no_fronts,
+            discord_status_no_fronts,
+            discord_status_message_status_no_fronts,
+            website_status_no_fronts,
+            vrchat_status_truncate_names_to,
+            discord_status_truncate_names_to,
+            discord_status_message_status_truncate_names_to,
+            website_status_truncate_names_to,
+            vrchat_status_group_collapsing,
+            discord_status_group_collapsing,
+            discord_status_message_status_group_collapsing,
+            website_status_group_collapsing,
+            enable_webhook,
+            webhook_url,
+            enable_discord_webhook,
+            discord_webhook_edit_message,
+            enable_matrix,
+            matrix_homeserver_url,
+            matrix_displayname_scope,
+            matrix_displayname_rooms,
+            enable_mastodon,
+            mastodon_instance_url,
+            mastodon_field_name,
+            mastodon_display_name,
+            enable_bluesky,
+            bluesky_pds_url,
+            bluesky_identifier,
+            bluesky_display_name,
+            enable_mqtt,
+            mqtt_broker_url,
+            mqtt_username,
+            mqtt_fronters_topic,
+            mqtt_status_topic,
+            mqtt_fronter_count_topic,
+            mqtt_home_assistant_discovery,
+            mqtt_home_assistant_discovery_prefix,
+            enable_pluralkit_system_sync,
+            enable_from_pluralkit,
+            pluralkit_switch_order,
+            pluralkit_switch_primary_member,
             pgp_sym_decrypt(enc__simply_plural_token, $2) AS simply_plural_token,
             pgp_sym_decrypt(enc__discord_status_message_token, $2) AS discord_status_message_token,
             pgp_sym_decrypt(enc__vrchat_username, $2) AS vrchat_username,
             pgp_sym_decrypt(enc__vrchat_password, $2) AS vrchat_password,
             pgp_sym_decrypt(enc__vrchat_cookie, $2) AS vrchat_cookie,
             pgp_sym_decrypt(enc__pluralkit_token, $2) AS pluralkit_token,
+            pgp_sym_decrypt(enc__webhook_signing_secret, $2) AS webhook_signing_secret,
+            pgp_sym_decrypt(enc__discord_webhook_url, $2) AS discord_webhook_url,
+            pgp_sym_decrypt(enc__matrix_access_token, $2) AS matrix_access_token,
+            pgp_sym_decrypt(enc__mastodon_token, $2) AS mastodon_token,
+            pgp_sym_decrypt(enc__bluesky_app_password, $2) AS bluesky_app_password,
+            pgp_sym_decrypt(enc__mqtt_password, $2) AS mqtt_password,
+            pgp_sym_decrypt(enc__pluralkit_dispatch_signing_token, $2) AS pluralkit_dispatch_signing_token,
             true AS valid_constraints
             FROM users WHERE id = $1",
     )
@@ -220,8 +503,13 @@ pub async fn modify_user_secrets(
 
     let unused_client = setup::make_client()?;
 
-    let (_, new_config) =
-        users::create_config_with_strong_constraints(user_id, &unused_client, &user_with_secrets)?;
+    // only the validated db entries are used. hence, the endpoints don't matter.
+    let (_, new_config) = users::create_config_with_strong_constraints(
+        user_id,
+        &unused_client,
+        &plurality::SimplyPluralEndpoints::default(),
+        &user_with_secrets,
+    )?;
 
     let () =
         set_user_config_secrets(db_pool, user_id, new_config, application_user_secrets).await?;
@@ -286,6 +574,193 @@ pub async fn find_user_by_website_url_name(
     .map_err(|e| anyhow!(e))
 }
 
+pub async fn get_front_history(
+    db_pool: &PgPool,
+    user_id: &UserId,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<FrontHistoryEntry>> {
+    log::debug!("# | db::get_front_history | {user_id} | limit={limit} offset={offset}");
+    sqlx::query_as(
+        "SELECT
+            changed_at,
+            fronter_ids,
+            fronter_names
+        FROM front_history
+        WHERE user_id = $1
+        ORDER BY changed_at DESC, id DESC
+        LIMIT $2 OFFSET $3",
+    )
+    .bind(user_id.inner)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db_pool)
+    .await
+    .map_err(|e| anyhow!(e))
+}
+
+pub async fn insert_front_history_entry(
+    db_pool: &PgPool,
+    user_id: &UserId,
+    entry: &FrontHistoryEntry,
+) -> Result<()> {
+    log::debug!("# | db::insert_front_history_entry | {user_id}");
+    sqlx::query(
+        "INSERT INTO front_history (user_id, changed_at, fronter_ids, fronter_names)
+        VALUES ($1, $2, $3, $4)",
+    )
+    .bind(user_id.inner)
+    .bind(entry.changed_at)
+    .bind(&entry.fronter_ids)
+    .bind(&entry.fronter_names)
+    .execute(db_pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| anyhow!(e))
+}
+
+pub async fn delete_front_history_before(
+    db_pool: &PgPool,
+    user_id: &UserId,
+    before: chrono::DateTime<chrono::Utc>,
+) -> Result<u64> {
+    log::debug!("# | db::delete_front_history_before | {user_id} | {before}");
+    sqlx::query("DELETE FROM front_history WHERE user_id = $1 AND changed_at < $2")
+        .bind(user_id.inner)
+        .bind(before)
+        .execute(db_pool)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| anyhow!(e))
+}
+
+pub async fn get_updater_log(
+    db_pool: &PgPool,
+    user_id: &UserId,
+    limit: i64,
+) -> Result<Vec<UpdaterLogEntry>> {
+    log::debug!("# | db::get_updater_log | {user_id} | limit={limit}");
+    sqlx::query_as(
+        "SELECT
+            platform,
+            updated_at,
+            payload,
+            success,
+            error,
+            error_category,
+            duration_ms
+        FROM updater_log
+        WHERE user_id = $1
+        ORDER BY updated_at DESC, id DESC
+        LIMIT $2",
+    )
+    .bind(user_id.inner)
+    .bind(limit)
+    .fetch_all(db_pool)
+    .await
+    .map_err(|e| anyhow!(e))
+}
+
+pub async fn insert_updater_log_entry(
+    db_pool: &PgPool,
+    user_id: &UserId,
+    entry: &UpdaterLogEntry,
+) -> Result<()> {
+    log::debug!("# | db::insert_updater_log_entry | {user_id}");
+    sqlx::query(
+        "INSERT INTO updater_log
+            (user_id, platform, updated_at, payload, success, error, error_category, duration_ms)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+    )
+    .bind(user_id.inner)
+    .bind(entry.platform)
+    .bind(entry.updated_at)
+    .bind(&entry.payload)
+    .bind(entry.success)
+    .bind(&entry.error)
+    .bind(entry.error_category)
+    .bind(entry.duration_ms)
+    .execute(db_pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| anyhow!(e))
+}
+
+pub async fn delete_updater_log_before(
+    db_pool: &PgPool,
+    user_id: &UserId,
+    before: chrono::DateTime<chrono::Utc>,
+) -> Result<u64> {
+    log::debug!("# | db::delete_updater_log_before | {user_id} | {before}");
+    sqlx::query("DELETE FROM updater_log WHERE user_id = $1 AND updated_at < $2")
+        .bind(user_id.inner)
+        .bind(before)
+        .execute(db_pool)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| anyhow!(e))
+}
+
+/// Compares keyed hashes of the tokens, such that only the dispatch signing token of not yet migrated users is decrypted.
+pub async fn is_pluralkit_dispatch_signing_token(
+    db_pool: &PgPool,
+    user_id: &UserId,
+    application_user_secrets: &secrets::ApplicationUserSecrets,
+    signing_token: &str,
+) -> Result<bool> {
+    log::debug!("# | db::is_pluralkit_dispatch_signing_token | {user_id}");
+
+    let secrets_key = compute_user_secrets_key(user_id, application_user_secrets);
+
+    let is_signed: Option<Option<bool>> = sqlx::query_scalar(
+        "SELECT
+            COALESCE(
+                pluralkit_dispatch_token_hash,
+                encode(hmac(pgp_sym_decrypt(enc__pluralkit_dispatch_signing_token, $2), $2, 'sha256'), 'hex')
+            ) = encode(hmac($3, $2, 'sha256'), 'hex')
+        FROM users WHERE id = $1",
+    )
+    .bind(user_id.inner)
+    .bind(secrets_key.inner)
+    .bind(signing_token)
+    .fetch_optional(db_pool)
+    .await
+    .map_err(|e| anyhow!(e))?;
+
+    Ok(is_signed.flatten().unwrap_or(false))
+}
+
+pub async fn get_discord_webhook_message_id(
+    db_pool: &PgPool,
+    user_id: &UserId,
+) -> Result<Option<String>> {
+    log::debug!("# | db::get_discord_webhook_message_id | {user_id}");
+    sqlx::query_scalar("SELECT message_id FROM discord_webhook_messages WHERE user_id = $1")
+        .bind(user_id.inner)
+        .fetch_optional(db_pool)
+        .await
+        .map_err(|e| anyhow!(e))
+}
+
+pub async fn set_discord_webhook_message_id(
+    db_pool: &PgPool,
+    user_id: &UserId,
+    message_id: &str,
+) -> Result<()> {
+    log::debug!("# | db::set_discord_webhook_message_id | {user_id}");
+    sqlx::query(
+        "INSERT INTO discord_webhook_messages (user_id, message_id)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET message_id = EXCLUDED.message_id",
+    )
+    .bind(user_id.inner)
+    .bind(message_id)
+    .execute(db_pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| anyhow!(e))
+}
+
 fn compute_user_secrets_key(
     user_id: &UserId,
     application_user_secret: &secrets::ApplicationUserSecrets,
@@ -312,3 +787,24 @@ pub struct UserInfo {
     pub password_hash: users::PasswordHashString,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
+
+/// One change of the fronters. The IDs and names are in the order of the fronting status.
+#[derive(FromRow, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FrontHistoryEntry {
+    pub changed_at: chrono::DateTime<chrono::Utc>,
+    pub fronter_ids: Vec<String>,
+    pub fronter_names: Vec<String>,
+}
+
+/// The outcome of one update of a platform.
+#[derive(FromRow, Serialize, Debug, Clone)]
+pub struct UpdaterLogEntry {
+    pub platform: updater::Platform,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// The status string or payload which was pushed to the platform
+    pub payload: Option<String>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub error_category: Option<updater::UpdateErrorCategory>,
+    pub duration_ms: i32,
+}
@@ -9,3 +9,7 @@ pub mod plurality;
 pub mod setup;
 pub mod updater;
 pub mod users;
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test_support;
@@ -48,6 +48,16 @@ async fn main() -> Result<()> {
     )
     .await?;
 
+    let () = setup::start_cron_job(
+        &app_setup.db_pool,
+        &app_setup.shared_updaters,
+        &app_setup.application_user_secrets,
+        "prune-expired-entries",
+        setup::EVERY_HOUR,
+        updater::prune_expired_entries,
+    )
+    .await?;
+
     log::debug!(
         "# | app_setup | configured | updaters_restarted | cron_jobs_started | webserver_starting"
     );
@@ -73,6 +83,7 @@ async fn run_webserver(setup: setup::ApplicationSetup) -> Result<()> {
         .manage(setup.client)
         .manage(setup.shared_updaters)
         .manage(setup.pluralsync_variant_info)
+        .manage(platforms::pluralkit_api::PluralKitDispatchRateLimit::default())
         .attach(metrics::PROM_METRICS.clone())
         .attach(setup.cors_policy)
         .mount(
@@ -85,11 +96,17 @@ async fn run_webserver(setup: setup::ApplicationSetup) -> Result<()> {
                 users::config_api::post_api_user_config,
                 users::config_api::get_api_config_defaults,
                 updater::api::get_api_updaters_status,
+                updater::api::get_api_updaters_log,
+                updater::api::get_api_fronting_source_status,
+                updater::api::get_api_user_front_history,
                 platforms::webview_api::get_api_fronting_status,
                 platforms::webview_api::get_api_fronting_by_user_id,
                 platforms::vrchat_api::post_api_user_platform_vrchat_auth_2fa_request,
                 platforms::vrchat_api::post_api_user_platform_vrchat_auth_2fa_resolve,
                 platforms::discord_api::get_api_user_platform_discord_bridge_events,
+                platforms::pluralkit_api::get_api_user_platform_pluralkit_system_sync_preview,
+                platforms::pluralkit_api::post_api_user_platform_pluralkit_system_sync_apply,
+                platforms::pluralkit_api::post_api_pluralkit_dispatch,
                 meta_api::get_api_meta_pluralsync_variant,
             ],
         )
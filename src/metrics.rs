@@ -74,19 +74,26 @@ pub static PROM_METRICS: sync::LazyLock<rocket_prometheus::PrometheusMetrics> =
             plurality::SIMPLY_PLURAL_FETCH_FRONTS_ACTIVE_MEMBERS_COUNT,
             plurality::SIMPLY_PLURAL_FETCH_FRONTS_ARCHIVED_MEMBERS_COUNT,
             plurality::SIMPLY_PLURAL_FETCH_FRONTS_CUSTOM_FRONTS_COUNT,
+            plurality::SIMPLY_PLURAL_FETCH_FRONTS_GROUPS_COUNT,
             plurality::SIMPLY_PLURAL_WEBSOCKET_CONNECTION_ATTEMPTS_TOTAL,
             plurality::SIMPLY_PLURAL_WEBSOCKET_CONNECTION_ENDED_ERROR_GENERAL_TOTAL,
             plurality::SIMPLY_PLURAL_WEBSOCKET_CONNECTION_ENDED_ERROR_AUTH_TOTAL,
             plurality::SIMPLY_PLURAL_WEBSOCKET_MESSAGES_RECEIVED_TOTAL,
             plurality::SIMPLY_PLURAL_WEBSOCKET_SEMANTIC_MESSAGES_RECEIVED_TOTAL,
+            plurality::SIMPLY_PLURAL_CACHE_HITS_TOTAL,
+            plurality::SIMPLY_PLURAL_CACHE_MISSES_TOTAL,
+            plurality::PLURALKIT_FETCH_FRONTS_TOTAL_COUNTER,
+            plurality::PLURALKIT_FETCH_FRONTS_FRONTERS_COUNT,
+            plurality::PLURALKIT_POLL_SWITCH_CHANGED_TOTAL_COUNTER,
             plurality::FRONTING_STATUS_STRING,
             updater::UPDATER_MANAGER_RESTART_TOTAL_COUNT,
             updater::UPDATER_MANAGER_RESTART_SUCCESS_COUNT,
-            updater::UPDATER_MANAGER_SIMPLY_PLURAL_WEBSOCKET_RELEVANT_CHANGE_MESSAGE_COUNT,
+            plurality::UPDATER_MANAGER_SIMPLY_PLURAL_WEBSOCKET_RELEVANT_CHANGE_MESSAGE_COUNT,
             updater::UPDATER_PLATFORM_STATUS,
             updater::UPDATER_PROCESS_START_TOTAL,
             updater::UPDATER_PROCESS_SUCCESS_TOTAL,
             updater::UPDATER_PROCESS_UNEXPECTED_STOP_TOTAL,
+            updater::FRONT_HISTORY_ENTRIES_RECORDED_TOTAL,
             platforms::PLURALKIT_API_REQUESTS_TOTAL,
             platforms::PLURALKIT_API_RATELIMIT_REMAINING,
             PLURALSYNC_USER_CONFIG_FEATURE,
@@ -0,0 +1,598 @@
+use crate::{platforms, plurality, record_if_error, users};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+/// Encloses the section of the profile description which shows the fronting status, e.g. "⟦front⟧ F: Annalea ⟦front⟧".
+pub const BLUESKY_DESCRIPTION_MARKER: &str = "⟦front⟧";
+
+/// Limits of the `app.bsky.actor.profile` lexicon, in grapheme clusters
+const BLUESKY_DESCRIPTION_MAX_LENGTH: usize = 256;
+const BLUESKY_DISPLAY_NAME_MAX_LENGTH: usize = 64;
+
+const PROFILE_COLLECTION: &str = "app.bsky.actor.profile";
+const PROFILE_RECORD_KEY: &str = "self";
+
+#[derive(Serialize, Debug)]
+struct CreateSession<'a> {
+    identifier: &'a str,
+    password: &'a str,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Session {
+    access_jwt: String,
+    refresh_jwt: String,
+    did: String,
+    handle: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ProfileRecord {
+    cid: Option<String>,
+    value: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PutRecord<'a> {
+    repo: &'a str,
+    collection: &'a str,
+    rkey: &'a str,
+    record: &'a serde_json::Map<String, serde_json::Value>,
+    /// Fails the update, if the profile was changed in the meantime
+    #[serde(skip_serializing_if = "Option::is_none")]
+    swap_record: Option<&'a str>,
+}
+
+/// XRPC errors have a name besides the HTTP status, e.g. `{"error": "ExpiredToken", "message": "Token has expired"}`
+#[derive(Deserialize, Debug)]
+struct XrpcError {
+    error: String,
+    #[serde(default)]
+    message: String,
+}
+
+impl std::fmt::Display for XrpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.error, self.message)
+    }
+}
+
+pub struct BlueskyUpdater {
+    pub last_operation_error: Option<String>,
+    pub last_payload: Option<String>,
+    session: Option<Session>,
+}
+
+impl Default for BlueskyUpdater {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlueskyUpdater {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            last_operation_error: None,
+            last_payload: None,
+            session: None,
+        }
+    }
+
+    pub async fn setup(&mut self, config: &users::UserConfigForUpdater) -> Result<()> {
+        let client = record_if_error!(
+            self,
+            platforms::public_url_client(config, &config.bluesky_pds_url).await
+        )?;
+        let session = record_if_error!(self, create_session(&client, config).await)?;
+        log::info!(
+            "# | bluesky setup | {} | authenticated as {} ({})",
+            config.user_id,
+            session.handle,
+            session.did
+        );
+        self.session = Some(session);
+        Ok(())
+    }
+
+    pub async fn update_fronting_status(
+        &mut self,
+        config: &users::UserConfigForUpdater,
+        fronts: &[plurality::Fronter],
+    ) -> Result<()> {
+        let session = record_if_error!(
+            self,
+            self.session
+                .clone()
+                .ok_or_else(|| anyhow!("update_fronting_status: Updater not initalized!"))
+        )?;
+        let client = record_if_error!(
+            self,
+            platforms::public_url_client(config, &config.bluesky_pds_url).await
+        )?;
+
+        let mut result = update_bluesky(&client, config, &session, fronts).await;
+
+        // access tokens expire after a few hours. the refresh token is valid for months.
+        if result.as_ref().is_err_and(is_expired_token) {
+            log::info!(
+                "# | bluesky update_fronting_status | {} | session expired. refreshing.",
+                config.user_id
+            );
+            let refreshed_session = match refresh_session(&client, config, &session).await {
+                Ok(refreshed_session) => refreshed_session,
+                Err(_) => record_if_error!(self, create_session(&client, config).await)?,
+            };
+            result = update_bluesky(&client, config, &refreshed_session, fronts).await;
+            self.session = Some(refreshed_session);
+        }
+
+        let description_status = record_if_error!(self, result)?;
+        self.last_payload = Some(description_status);
+        Ok(())
+    }
+}
+
+/// Returns the fronting status which was written into the description.
+async fn update_bluesky(
+    client: &reqwest::Client,
+    config: &users::UserConfigForUpdater,
+    session: &Session,
+    fronts: &[plurality::Fronter],
+) -> Result<String> {
+    let mut profile = fetch_profile_record(client, config, session).await?;
+
+    let description = profile
+        .value
+        .get("description")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default();
+    let (before, after) = split_at_front_section(description)?;
+    let description_status = format_description_status(config, before, after, fronts)?;
+    let new_description = format!(
+        "{before}{BLUESKY_DESCRIPTION_MARKER} {description_status} {BLUESKY_DESCRIPTION_MARKER}{after}"
+    );
+
+    profile
+        .value
+        .insert("description".to_owned(), new_description.into());
+    if let Some(base_display_name) = &config.bluesky_display_name {
+        profile.value.insert(
+            "displayName".to_owned(),
+            format_bluesky_display_name(config, base_display_name, fronts).into(),
+        );
+    }
+
+    put_profile_record(client, config, session, &profile).await?;
+
+    log::info!(
+        "# | update_bluesky | {} | profile updated to '{description_status}'",
+        config.user_id
+    );
+
+    Ok(description_status)
+}
+
+/// The text before the first and after the second marker. The text in between is replaced by the fronting status.
+fn split_at_front_section(description: &str) -> Result<(&str, &str)> {
+    let missing_section = || {
+        anyhow!(
+            "The Bluesky profile description must contain a section '{BLUESKY_DESCRIPTION_MARKER} ... {BLUESKY_DESCRIPTION_MARKER}' for the fronting status"
+        )
+    };
+    let (before, rest) = description
+        .split_once(BLUESKY_DESCRIPTION_MARKER)
+        .ok_or_else(missing_section)?;
+    let (_, after) = rest
+        .split_once(BLUESKY_DESCRIPTION_MARKER)
+        .ok_or_else(missing_section)?;
+    Ok((before, after))
+}
+
+/// The status is shortened such that the whole description stays within the limit.
+fn format_description_status(
+    config: &users::UserConfigForUpdater,
+    before: &str,
+    after: &str,
+    fronts: &[plurality::Fronter],
+) -> Result<String> {
+    let length_metric = plurality::LengthMetric::GraphemeClusters;
+    let surrounding_length = length_metric.length(before)
+        + length_metric.length(after)
+        + 2 * length_metric.length(BLUESKY_DESCRIPTION_MARKER)
+        + 2;
+    let remaining_length = BLUESKY_DESCRIPTION_MAX_LENGTH.saturating_sub(surrounding_length);
+    let no_room = || {
+        anyhow!(
+            "The Bluesky profile description leaves no room for the fronting status. Shorten the text around the '{BLUESKY_DESCRIPTION_MARKER}' section."
+        )
+    };
+    if remaining_length == 0 {
+        return Err(no_room());
+    }
+
+    let mut fronting_format =
+        plurality::generic_fronting_format(config, plurality::DisplayNamePlatform::Bluesky);
+    fronting_format.max_length = Some(remaining_length);
+    fronting_format.length_metric = length_metric;
+    let status_string = plurality::format_fronting_status(&fronting_format, fronts);
+
+    // the status is empty, if not even the shortest shape fits
+    if status_string.is_empty() || length_metric.length(&status_string) > remaining_length {
+        return Err(no_room());
+    }
+    Ok(status_string)
+}
+
+/// The configured display name followed by the fronting status, e.g. "Our System F: Annalea".
+/// If the display name leaves no room for the status, then only the display name is used.
+fn format_bluesky_display_name(
+    config: &users::UserConfigForUpdater,
+    base_display_name: &str,
+    fronts: &[plurality::Fronter],
+) -> String {
+    let length_metric = plurality::LengthMetric::GraphemeClusters;
+    let remaining_length = BLUESKY_DISPLAY_NAME_MAX_LENGTH
+        .saturating_sub(length_metric.length(base_display_name))
+        .saturating_sub(1);
+    if remaining_length == 0 {
+        return base_display_name.to_owned();
+    }
+
+    let mut fronting_format =
+        plurality::generic_fronting_format(config, plurality::DisplayNamePlatform::Bluesky);
+    fronting_format.max_length = Some(remaining_length);
+    fronting_format.length_metric = length_metric;
+    let status_string = plurality::format_fronting_status(&fronting_format, fronts);
+
+    if status_string.is_empty() || length_metric.length(&status_string) > remaining_length {
+        base_display_name.to_owned()
+    } else {
+        format!("{base_display_name} {status_string}")
+    }
+}
+
+fn is_expired_token(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<XrpcError>()
+        .is_some_and(|xrpc_error| xrpc_error.error == "ExpiredToken")
+}
+
+fn xrpc_url(config: &users::UserConfigForUpdater, method: &str) -> Result<reqwest::Url> {
+    let mut url = reqwest::Url::parse(&config.bluesky_pds_url)?;
+    url.path_segments_mut()
+        .map_err(|()| anyhow!("xrpc_url: bluesky_pds_url cannot be a base"))?
+        .pop_if_empty()
+        .extend(["xrpc", method]);
+    Ok(url)
+}
+
+/// Like `error_for_status`, but keeps the XRPC error (e.g. "ExpiredToken") of the response body as context.
+async fn xrpc_error_for_status(response: reqwest::Response) -> Result<reqwest::Response> {
+    let Err(status_error) = response.error_for_status_ref() else {
+        return Ok(response);
+    };
+    match response.json::<XrpcError>().await {
+        Ok(xrpc_error) => Err(anyhow::Error::new(status_error).context(xrpc_error)),
+        Err(_) => Err(status_error.into()),
+    }
+}
+
+async fn create_session(
+    client: &reqwest::Client,
+    config: &users::UserConfigForUpdater,
+) -> Result<Session> {
+    let response = client
+        .post(xrpc_url(config, "com.atproto.server.createSession")?)
+        .json(&CreateSession {
+            identifier: &config.bluesky_identifier,
+            password: &config.bluesky_app_password.secret,
+        })
+        .send()
+        .await?;
+    Ok(xrpc_error_for_status(response).await?.json().await?)
+}
+
+async fn refresh_session(
+    client: &reqwest::Client,
+    config: &users::UserConfigForUpdater,
+    session: &Session,
+) -> Result<Session> {
+    let response = client
+        .post(xrpc_url(config, "com.atproto.server.refreshSession")?)
+        .bearer_auth(&session.refresh_jwt)
+        .send()
+        .await?;
+    Ok(xrpc_error_for_status(response).await?.json().await?)
+}
+
+async fn fetch_profile_record(
+    client: &reqwest::Client,
+    config: &users::UserConfigForUpdater,
+    session: &Session,
+) -> Result<ProfileRecord> {
+    let mut url = xrpc_url(config, "com.atproto.repo.getRecord")?;
+    url.query_pairs_mut()
+        .append_pair("repo", &session.did)
+        .append_pair("collection", PROFILE_COLLECTION)
+        .append_pair("rkey", PROFILE_RECORD_KEY);
+
+    let response = client
+        .get(url)
+        .bearer_auth(&session.access_jwt)
+        .send()
+        .await?;
+    Ok(xrpc_error_for_status(response).await?.json().await?)
+}
+
+async fn put_profile_record(
+    client: &reqwest::Client,
+    config: &users::UserConfigForUpdater,
+    session: &Session,
+    profile: &ProfileRecord,
+) -> Result<()> {
+    let response = client
+        .post(xrpc_url(config, "com.atproto.repo.putRecord")?)
+        .bearer_auth(&session.access_jwt)
+        .json(&PutRecord {
+            repo: &session.did,
+            collection: PROFILE_COLLECTION,
+            rkey: PROFILE_RECORD_KEY,
+            record: &profile.value,
+            swap_record: profile.cid.as_deref(),
+        })
+        .send()
+        .await?;
+    xrpc_error_for_status(response).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::database::Decrypted;
+    use crate::test_support::{self, ReceivedRequests, fronter};
+    use crate::users::UserConfigDbEntries;
+
+    const MOCK_IDENTIFIER: &str = "oursystem.bsky.social";
+    const MOCK_APP_PASSWORD: &str = "abcd-efgh-ijkl-mnop";
+    const MOCK_DID: &str = "did:plc:oursystem";
+
+    /// Minimal stand-in for a personal data server. Records the requests and answers the known XRPC methods.
+    /// If `session_expired`, the access token of the created session is rejected as expired, but it can be refreshed.
+    async fn start_mock_pds(session_expired: bool) -> (String, ReceivedRequests) {
+        test_support::start_mock_http_server(move |request| {
+            let bearer_token = request
+                .header("authorization")
+                .unwrap_or_default()
+                .trim_start_matches("Bearer ");
+            let initial_access_token = if session_expired {
+                "access-expired"
+            } else {
+                "access-1"
+            };
+            let session = |access_token: &str| {
+                format!(
+                    r#"{{"accessJwt": "{access_token}", "refreshJwt": "refresh-1", "did": "{MOCK_DID}", "handle": "{MOCK_IDENTIFIER}", "active": true}}"#
+                )
+            };
+            let token_valid = bearer_token == "access-1" || bearer_token == "access-2";
+
+            let xrpc_method = request.path.split('?').next().unwrap_or_default();
+            match (request.method.as_str(), xrpc_method) {
+                ("POST", "/xrpc/com.atproto.server.createSession") => {
+                    if request.body.contains(MOCK_APP_PASSWORD)
+                        && request.body.contains(MOCK_IDENTIFIER)
+                    {
+                        ("200 OK", session(initial_access_token))
+                    } else {
+                        (
+                            "401 Unauthorized",
+                            r#"{"error": "AuthenticationRequired", "message": "Invalid identifier or password"}"#.to_owned(),
+                        )
+                    }
+                }
+                ("POST", "/xrpc/com.atproto.server.refreshSession")
+                    if bearer_token == "refresh-1" =>
+                {
+                    ("200 OK", session("access-2"))
+                }
+                _ if bearer_token == "access-expired" => (
+                    "400 Bad Request",
+                    r#"{"error": "ExpiredToken", "message": "Token has expired"}"#.to_owned(),
+                ),
+                _ if !token_valid => (
+                    "401 Unauthorized",
+                    r#"{"error": "AuthenticationRequired", "message": "Invalid token"}"#.to_owned(),
+                ),
+                ("GET", "/xrpc/com.atproto.repo.getRecord") => (
+                    "200 OK",
+                    format!(
+                        r#"{{"uri": "at://{MOCK_DID}/app.bsky.actor.profile/self", "cid": "bafyprofile", "value": {{"$type": "app.bsky.actor.profile", "displayName": "Our System", "description": "Hi, we are a system!\n⟦front⟧ old ⟦front⟧\nAvatar by a friend", "avatar": {{"$type": "blob", "ref": {{"$link": "bafyavatar"}}, "mimeType": "image/png", "size": 1234}}}}}}"#
+                    ),
+                ),
+                ("POST", "/xrpc/com.atproto.repo.putRecord") => (
+                    "200 OK",
+                    format!(
+                        r#"{{"uri": "at://{MOCK_DID}/app.bsky.actor.profile/self", "cid": "bafyprofile2"}}"#
+                    ),
+                ),
+                _ => (
+                    "501 Not Implemented",
+                    r#"{"error": "MethodNotImplemented", "message": "Method Not Implemented"}"#
+                        .to_owned(),
+                ),
+            }
+        })
+        .await
+    }
+
+    fn create_test_config(
+        pds_url: &str,
+        app_password: &str,
+        display_name: Option<&str>,
+    ) -> users::UserConfigForUpdater {
+        test_support::create_test_config(&UserConfigDbEntries {
+            enable_bluesky: true,
+            bluesky_pds_url: Some(pds_url.to_owned()),
+            bluesky_identifier: Some(MOCK_IDENTIFIER.to_owned()),
+            bluesky_display_name: display_name.map(str::to_owned),
+            bluesky_app_password: Some(Decrypted {
+                secret: app_password.to_owned(),
+            }),
+            ..test_support::db_config()
+        })
+    }
+
+    fn sent_put_records(received: &ReceivedRequests) -> Vec<serde_json::Value> {
+        received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.request_line() == "POST /xrpc/com.atproto.repo.putRecord")
+            .map(test_support::MockRequest::json)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_bluesky_rewrites_front_section_and_keeps_rest_of_profile() {
+        let (url, received) = start_mock_pds(false).await;
+        let config = create_test_config(&url, MOCK_APP_PASSWORD, None);
+        let mut updater = BlueskyUpdater::new();
+
+        updater.setup(&config).await.unwrap();
+        updater
+            .update_fronting_status(&config, &[fronter("Annalea"), fronter("Borgn")])
+            .await
+            .unwrap();
+
+        let put_records = sent_put_records(&received);
+        assert_eq!(put_records.len(), 1);
+        let put_record = &put_records[0];
+        assert_eq!(put_record["repo"], MOCK_DID);
+        assert_eq!(put_record["collection"], "app.bsky.actor.profile");
+        assert_eq!(put_record["rkey"], "self");
+        assert_eq!(put_record["swapRecord"], "bafyprofile");
+        assert_eq!(
+            put_record["record"]["description"],
+            "Hi, we are a system!\n⟦front⟧ F: Annalea, Borgn ⟦front⟧\nAvatar by a friend"
+        );
+        assert_eq!(put_record["record"]["displayName"], "Our System");
+        assert_eq!(put_record["record"]["$type"], "app.bsky.actor.profile");
+        assert_eq!(put_record["record"]["avatar"]["ref"]["$link"], "bafyavatar");
+
+        assert_eq!(updater.last_payload.as_deref(), Some("F: Annalea, Borgn"));
+        assert_eq!(updater.last_operation_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_bluesky_sets_display_name() {
+        let (url, received) = start_mock_pds(false).await;
+        let config = create_test_config(&url, MOCK_APP_PASSWORD, Some("Our System"));
+        let mut updater = BlueskyUpdater::new();
+
+        updater.setup(&config).await.unwrap();
+        updater
+            .update_fronting_status(&config, &[fronter("Annalea")])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            sent_put_records(&received)[0]["record"]["displayName"],
+            "Our System F: Annalea"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bluesky_refreshes_expired_session() {
+        let (url, received) = start_mock_pds(true).await;
+        let config = create_test_config(&url, MOCK_APP_PASSWORD, None);
+        let mut updater = BlueskyUpdater::new();
+
+        updater.setup(&config).await.unwrap();
+        updater
+            .update_fronting_status(&config, &[fronter("Annalea")])
+            .await
+            .unwrap();
+
+        assert!(received.lock().unwrap().iter().any(
+            |request| request.request_line() == "POST /xrpc/com.atproto.server.refreshSession"
+        ));
+        assert_eq!(sent_put_records(&received).len(), 1);
+        assert_eq!(updater.last_operation_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_bluesky_setup_fails_for_private_pds() {
+        let (url, received) = start_mock_pds(false).await;
+        let mut config = create_test_config(&url, MOCK_APP_PASSWORD, None);
+        config.allow_private_network_urls = false;
+        let mut updater = BlueskyUpdater::new();
+
+        assert!(updater.setup(&config).await.is_err());
+        assert!(
+            updater
+                .last_operation_error
+                .unwrap()
+                .contains("is not a public address")
+        );
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bluesky_setup_fails_with_invalid_app_password() {
+        let (url, _) = start_mock_pds(false).await;
+        let config = create_test_config(&url, "wrong-password", None);
+        let mut updater = BlueskyUpdater::new();
+
+        assert!(updater.setup(&config).await.is_err());
+        assert!(
+            updater
+                .last_operation_error
+                .as_ref()
+                .unwrap()
+                .contains("AuthenticationRequired")
+        );
+
+        assert!(
+            updater
+                .update_fronting_status(&config, &[fronter("Annalea")])
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_split_at_front_section() {
+        assert_eq!(
+            split_at_front_section("Hi!\n⟦front⟧ old ⟦front⟧ bye").unwrap(),
+            ("Hi!\n", " bye")
+        );
+        assert_eq!(split_at_front_section("⟦front⟧⟦front⟧").unwrap(), ("", ""));
+        assert!(split_at_front_section("Hi, no section here").is_err());
+        assert!(split_at_front_section("Hi! ⟦front⟧ only one marker").is_err());
+    }
+
+    #[test]
+    fn test_description_status_fits_into_description() {
+        let config = create_test_config("https://bsky.social", MOCK_APP_PASSWORD, None);
+        let fronts: Vec<_> = (0..40)
+            .map(|i| fronter(&format!("Fronter number {i}")))
+            .collect();
+
+        let before = "a".repeat(150);
+        let status = format_description_status(&config, &before, "", &fronts).unwrap();
+        assert!(!status.is_empty());
+        let description =
+            format!("{before}{BLUESKY_DESCRIPTION_MARKER} {status} {BLUESKY_DESCRIPTION_MARKER}");
+        assert!(
+            plurality::LengthMetric::GraphemeClusters.length(&description)
+                <= BLUESKY_DESCRIPTION_MAX_LENGTH
+        );
+
+        let before = "a".repeat(250);
+        assert!(format_description_status(&config, &before, "", &fronts).is_err());
+    }
+}
@@ -46,17 +46,25 @@ pub fn render_fronts_to_discord_rich_presence(
     fronters: Vec<plurality::Fronter>,
     config: &users::UserConfigForUpdater,
 ) -> Result<DiscordRichPresence> {
-    let short_format = plurality::FrontingFormat {
-        max_length: Some(30), // seems to fit often enough without '...' truncation
-        cleaning: plurality::CleanForPlatform::NoClean,
-        prefix: config.status_prefix.clone(),
-        status_if_no_fronters: config.status_no_fronts.clone(),
-        truncate_names_to_length_if_status_too_long: config.status_truncate_names_to,
-    };
+    let short_format = plurality::fronting_format_with_overrides(
+        config,
+        plurality::PlatformOverrides {
+            max_length: Some(30), // seems to fit often enough without '...' truncation
+            length_metric: Some(plurality::LengthMetric::Utf16CodeUnits),
+            prefix: config.discord_status_prefix.clone(),
+            status_if_no_fronters: config.discord_status_no_fronts.clone(),
+            truncate_names_to: config.discord_status_truncate_names_to,
+            group_collapsing: config.discord_status_group_collapsing,
+            display_name_platform: Some(plurality::DisplayNamePlatform::Discord),
+            status_templates: config.discord_status_templates.clone(),
+            ..Default::default()
+        },
+    );
     let short_fronters_string = plurality::format_fronting_status(&short_format, &fronters);
 
     let long_format = plurality::FrontingFormat {
         max_length: Some(50), // seems to fit often enough without '...' truncation
+        show_custom_status: config.discord_show_custom_status,
         ..short_format
     };
     let long_fronters_string = plurality::format_fronting_status(&long_format, &fronters);
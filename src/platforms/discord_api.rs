@@ -30,9 +30,14 @@ pub async fn get_api_user_platform_discord_bridge_events(
     let user_id_c = user_id.clone();
     log::info!("# | GET /api/user/platform/discord/bridge-events | {user_id}");
 
-    let config =
-        database::get_user_config_with_secrets(db_pool, &user_id, client, application_user_secrets)
-            .await?;
+    let config = database::get_user_config_with_secrets(
+        db_pool,
+        &user_id,
+        client,
+        &shared_updaters.simply_plural_endpoints,
+        application_user_secrets,
+    )
+    .await?;
 
     let initial_fronters = shared_updaters.fronter_channel_get_most_recent_sent_value(&user_id)?;
 
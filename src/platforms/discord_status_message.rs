@@ -14,6 +14,7 @@ struct Status {
 
 pub struct DiscordStatusMessageUpdater {
     pub last_operation_error: Option<String>,
+    pub last_payload: Option<String>,
 }
 impl Default for DiscordStatusMessageUpdater {
     fn default() -> Self {
@@ -26,6 +27,7 @@ impl DiscordStatusMessageUpdater {
     pub const fn new() -> Self {
         Self {
             last_operation_error: None,
+            last_payload: None,
         }
     }
 
@@ -39,27 +41,33 @@ impl DiscordStatusMessageUpdater {
         config: &users::UserConfigForUpdater,
         fronts: &[plurality::Fronter],
     ) -> Result<()> {
-        record_if_error!(self, update_to_discord(config, fronts).await)
+        let status_string = format_discord_status(config, fronts);
+        self.last_payload = Some(status_string.clone());
+        record_if_error!(self, set_discord_status(config, status_string).await)
     }
 }
 
-async fn update_to_discord(
+fn format_discord_status(
     config: &users::UserConfigForUpdater,
     fronts: &[plurality::Fronter],
-) -> Result<()> {
-    let fronting_format = plurality::FrontingFormat {
-        max_length: Some(plurality::DISCORD_STATUS_MAX_LENGTH),
-        cleaning: plurality::CleanForPlatform::NoClean,
-        prefix: config.status_prefix.clone(),
-        status_if_no_fronters: config.status_no_fronts.clone(),
-        truncate_names_to_length_if_status_too_long: config.status_truncate_names_to,
-    };
-
-    let status_string = plurality::format_fronting_status(&fronting_format, fronts);
-
-    set_discord_status(config, status_string).await?;
+) -> String {
+    let fronting_format = plurality::fronting_format_with_overrides(
+        config,
+        plurality::PlatformOverrides {
+            max_length: Some(plurality::DISCORD_STATUS_MAX_LENGTH),
+            length_metric: Some(plurality::LengthMetric::Utf16CodeUnits),
+            prefix: config.discord_status_message_status_prefix.clone(),
+            status_if_no_fronters: config.discord_status_message_status_no_fronts.clone(),
+            truncate_names_to: config.discord_status_message_status_truncate_names_to,
+            show_custom_status: config.discord_status_message_show_custom_status,
+            group_collapsing: config.discord_status_message_status_group_collapsing,
+            display_name_platform: Some(plurality::DisplayNamePlatform::DiscordStatusMessage),
+            status_templates: config.discord_status_message_status_templates.clone(),
+            ..Default::default()
+        },
+    );
 
-    Ok(())
+    plurality::format_fronting_status(&fronting_format, fronts)
 }
 
 async fn set_discord_status(
@@ -0,0 +1,503 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{database, platforms, plurality, users};
+use anyhow::{Result, anyhow};
+use pluralsync_base::communication::{self, FireAndForgetChannel, LatestReceiver};
+use serde::{Deserialize, Serialize};
+
+/// Discord shows at most 10 embeds per message.
+const DISCORD_WEBHOOK_MAX_EMBEDS: usize = 10;
+
+type AnnouncementChannel = FireAndForgetChannel<
+    Vec<plurality::Fronter>,
+    communication::RateLimitedMostRecentSend<Vec<plurality::Fronter>>,
+>;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct DiscordWebhookMessage {
+    content: String,
+    embeds: Vec<DiscordEmbed>,
+    allowed_mentions: DiscordAllowedMentions,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct DiscordEmbed {
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail: Option<DiscordEmbedImage>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct DiscordEmbedImage {
+    url: String,
+}
+
+/// Names in the announcement must never ping anyone.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct DiscordAllowedMentions {
+    parse: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DiscordWebhookMessageResponse {
+    id: String,
+}
+
+pub struct DiscordWebhookUpdater {
+    pub last_operation_error: Option<String>,
+    pub last_payload: Option<String>,
+    announcements: Option<AnnouncementChannel>,
+    /// Outcome of the most recent delivery, as the announcements are delivered in the background.
+    last_delivery_error: Arc<Mutex<Option<String>>>,
+}
+
+impl Default for DiscordWebhookUpdater {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiscordWebhookUpdater {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            last_operation_error: None,
+            last_payload: None,
+            announcements: None,
+            last_delivery_error: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    #[allow(clippy::unused_async)]
+    pub async fn setup(
+        &mut self,
+        config: &users::UserConfigForUpdater,
+        db_pool: &sqlx::PgPool,
+    ) -> Result<()> {
+        let announcements: AnnouncementChannel =
+            communication::fire_and_forget_channel_with(announcement_rate_limit(&config.user_id));
+
+        // the delivery task ends by itself, once the updater and with it the channel is dropped
+        tokio::spawn(deliver_announcements(
+            config.clone(),
+            announcements.subscribe(),
+            self.last_delivery_error.clone(),
+            db_pool.clone(),
+        ));
+
+        self.announcements = Some(announcements);
+        Ok(())
+    }
+
+    pub async fn update_fronting_status(
+        &mut self,
+        config: &users::UserConfigForUpdater,
+        fronts: &[plurality::Fronter],
+    ) -> Result<()> {
+        // errors of the background delivery show up here, such that they're part of the updater status
+        self.last_operation_error = self
+            .last_delivery_error
+            .lock()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .clone();
+
+        let message = build_announcement(config, fronts);
+        self.last_payload = Some(serde_json::to_string(&message)?);
+
+        let announcements = self.announcements.as_mut().ok_or_else(|| {
+            anyhow!("update_fronting_status: Discord webhook updater not initialized!")
+        })?;
+        announcements.send(fronts.to_vec());
+
+        Ok(())
+    }
+}
+
+/// Channel messages are more noticeable than a status. Hence, frequent switches are announced more sparingly than to the other platforms.
+fn announcement_rate_limit(
+    user_id: &users::UserId,
+) -> communication::RateLimitedMostRecentSend<Vec<plurality::Fronter>> {
+    if cfg!(debug_assertions) {
+        communication::RateLimitedMostRecentSend::new(
+            format!("discord_webhook {user_id}"),
+            chrono::Duration::milliseconds(100),
+            chrono::Duration::seconds(1),
+            chrono::Duration::seconds(5),
+        )
+    } else {
+        communication::RateLimitedMostRecentSend::new(
+            format!("discord_webhook {user_id}"),
+            chrono::Duration::seconds(2),
+            chrono::Duration::minutes(5),
+            chrono::Duration::minutes(15),
+        )
+    }
+}
+
+async fn deliver_announcements(
+    config: users::UserConfigForUpdater,
+    mut receiver: LatestReceiver<Vec<plurality::Fronter>>,
+    last_delivery_error: Arc<Mutex<Option<String>>>,
+    db_pool: sqlx::PgPool,
+) {
+    let user_id = &config.user_id;
+    let mut message_id = if config.discord_webhook_edit_message {
+        database::get_discord_webhook_message_id(&db_pool, user_id)
+            .await
+            .inspect_err(|e| {
+                log::warn!("# | deliver_announcements | {user_id} | loading message failed: {e}");
+            })
+            .unwrap_or_default() // then, a new message is posted
+    } else {
+        None
+    };
+
+    while let Some(fronters) = receiver.recv().await {
+        let result = announce_fronters(&config, &fronters, message_id.as_deref()).await;
+
+        match &result {
+            Ok(new_message_id) => {
+                log::info!("# | deliver_announcements | {user_id} | announced in {new_message_id}");
+                if config.discord_webhook_edit_message
+                    && message_id.as_ref() != Some(new_message_id)
+                {
+                    message_id = Some(new_message_id.clone());
+                    let _ =
+                        database::set_discord_webhook_message_id(&db_pool, user_id, new_message_id)
+                            .await
+                            .inspect_err(|e| {
+                                log::warn!(
+                                    "# | deliver_announcements | {user_id} | save failed: {e}"
+                                );
+                            }); // the message is posted anew after a restart, which is OK
+                }
+            }
+            Err(e) => log::warn!("# | deliver_announcements | {user_id} | failed: {e}"),
+        }
+
+        match last_delivery_error.lock() {
+            Ok(mut error) => *error = result.err().map(|e| e.to_string()),
+            Err(e) => log::error!("# | deliver_announcements | {user_id} | shouldn't happen: {e}"),
+        }
+    }
+
+    log::info!("# | deliver_announcements | {user_id} | end of announcement channel");
+}
+
+fn build_announcement(
+    config: &users::UserConfigForUpdater,
+    fronts: &[plurality::Fronter],
+) -> DiscordWebhookMessage {
+    let content = plurality::format_fronting_status(
+        &plurality::generic_fronting_format(config, plurality::DisplayNamePlatform::DiscordWebhook),
+        fronts,
+    );
+
+    let embeds = fronts
+        .iter()
+        .take(DISCORD_WEBHOOK_MAX_EMBEDS)
+        .map(|fronter| {
+            let description = [
+                fronter.pronouns.clone().filter(|p| !p.is_empty()),
+                fronter
+                    .start_time
+                    .map(|t| format!("Fronting since <t:{}:R>", t.timestamp())),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n");
+
+            DiscordEmbed {
+                title: fronter.name.clone(),
+                description: Some(description).filter(|d| !d.is_empty()),
+                thumbnail: Some(fronter.avatar_url.clone())
+                    .filter(|url| !url.is_empty())
+                    .map(|url| DiscordEmbedImage { url }),
+            }
+        })
+        .collect();
+
+    DiscordWebhookMessage {
+        content,
+        embeds,
+        allowed_mentions: DiscordAllowedMentions { parse: vec![] },
+    }
+}
+
+/// Posts the announcement or edits the existing one. Returns the id of the message showing the announcement.
+async fn announce_fronters(
+    config: &users::UserConfigForUpdater,
+    fronts: &[plurality::Fronter],
+    message_id: Option<&str>,
+) -> Result<String> {
+    let message = build_announcement(config, fronts);
+    let client = platforms::public_url_client(config, &config.discord_webhook_url.secret).await?;
+    let webhook_url = reqwest::Url::parse(&config.discord_webhook_url.secret)?;
+
+    if config.discord_webhook_edit_message
+        && let Some(message_id) = message_id
+    {
+        let mut edit_url = webhook_url.clone();
+        edit_url
+            .path_segments_mut()
+            .map_err(|()| anyhow!("announce_fronters: discord_webhook_url cannot be a base"))?
+            .push("messages")
+            .push(message_id);
+
+        let response = client.patch(edit_url).json(&message).send().await?;
+
+        // the message was deleted in the meantime. a new one is posted instead.
+        if response.status() != reqwest::StatusCode::NOT_FOUND {
+            response.error_for_status()?;
+            return Ok(message_id.to_owned());
+        }
+        log::info!(
+            "# | announce_fronters | {} | message {message_id} not found. posting a new one.",
+            config.user_id
+        );
+    }
+
+    let mut post_url = webhook_url;
+    post_url.query_pairs_mut().append_pair("wait", "true");
+
+    let response = client
+        .post(post_url)
+        .json(&message)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let posted: DiscordWebhookMessageResponse = serde_json::from_str(&response)?;
+
+    Ok(posted.id)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::database::Decrypted;
+    use crate::test_support::{self, ReceivedRequests};
+    use crate::users::UserConfigDbEntries;
+
+    const MOCK_WEBHOOK_PATH: &str = "/api/webhooks/123/mock_token";
+
+    /// Minimal stand-in for the Discord webhook API. Posting creates the message "posted".
+    /// Only the message "existing" can be edited, any other message is unknown.
+    async fn start_mock_discord_webhook_server() -> (String, ReceivedRequests) {
+        let (base_url, received) = test_support::start_mock_http_server(|request| {
+            let (status, body) = match (request.method.as_str(), request.path.as_str()) {
+                ("POST", p) if p == format!("{MOCK_WEBHOOK_PATH}?wait=true") => {
+                    ("200 OK", r#"{"id": "posted"}"#)
+                }
+                ("PATCH", p) if p == format!("{MOCK_WEBHOOK_PATH}/messages/existing") => {
+                    ("200 OK", r#"{"id": "existing"}"#)
+                }
+                _ => (
+                    "404 Not Found",
+                    r#"{"message": "Unknown Message", "code": 10008}"#,
+                ),
+            };
+            (status, body.to_owned())
+        })
+        .await;
+        (format!("{base_url}{MOCK_WEBHOOK_PATH}"), received)
+    }
+
+    fn create_test_config(webhook_url: &str, edit_message: bool) -> users::UserConfigForUpdater {
+        test_support::create_test_config(&UserConfigDbEntries {
+            enable_discord_webhook: true,
+            discord_webhook_edit_message: edit_message,
+            discord_webhook_url: Some(Decrypted {
+                secret: webhook_url.to_owned(),
+            }),
+            ..test_support::db_config()
+        })
+    }
+
+    fn fronter(id: &str, name: &str) -> plurality::Fronter {
+        plurality::Fronter {
+            fronter_id: id.to_owned(),
+            avatar_url: format!("https://example.com/{id}.png"),
+            pronouns: Some("they/them".to_owned()),
+            start_time: Some(chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap()),
+            ..test_support::fronter(name)
+        }
+    }
+
+    #[test]
+    fn test_build_announcement() {
+        let config = create_test_config("https://discord.com/api/webhooks/1/t", false);
+        let mut without_details = fronter("b", "Borgn");
+        without_details.avatar_url = String::new();
+        without_details.pronouns = None;
+        without_details.start_time = None;
+
+        let message = build_announcement(&config, &[fronter("a", "Annalea"), without_details]);
+
+        assert_eq!(message.content, "F: Annalea, Borgn");
+        assert_eq!(
+            message.embeds,
+            vec![
+                DiscordEmbed {
+                    title: "Annalea".to_owned(),
+                    description: Some("they/them\nFronting since <t:1700000000:R>".to_owned()),
+                    thumbnail: Some(DiscordEmbedImage {
+                        url: "https://example.com/a.png".to_owned()
+                    }),
+                },
+                DiscordEmbed {
+                    title: "Borgn".to_owned(),
+                    description: None,
+                    thumbnail: None,
+                },
+            ]
+        );
+        assert!(message.allowed_mentions.parse.is_empty());
+
+        let many_fronters: Vec<_> = (0..15).map(|i| fronter(&i.to_string(), "X")).collect();
+        let message = build_announcement(&config, &many_fronters);
+        assert_eq!(message.embeds.len(), DISCORD_WEBHOOK_MAX_EMBEDS);
+    }
+
+    #[tokio::test]
+    async fn test_announce_fronters_posts_new_message() {
+        let (url, received) = start_mock_discord_webhook_server().await;
+        let config = create_test_config(&url, false);
+
+        let message_id = announce_fronters(&config, &[fronter("a", "Annalea")], Some("existing"))
+            .await
+            .unwrap();
+
+        assert_eq!(message_id, "posted");
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        assert_eq!(
+            received[0].request_line(),
+            format!("POST {MOCK_WEBHOOK_PATH}?wait=true")
+        );
+        let body: serde_json::Value = received[0].json();
+        assert_eq!(body["embeds"][0]["title"], "Annalea");
+        assert_eq!(
+            body["embeds"][0]["thumbnail"]["url"],
+            "https://example.com/a.png"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_announce_fronters_is_not_sent_into_private_networks() {
+        let (url, received) = start_mock_discord_webhook_server().await;
+        let mut config = create_test_config(&url, false);
+        config.allow_private_network_urls = false;
+
+        let result = announce_fronters(&config, &[fronter("a", "Annalea")], None).await;
+
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("is not a public address")
+        );
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_announce_fronters_edits_existing_message() {
+        let (url, received) = start_mock_discord_webhook_server().await;
+        let config = create_test_config(&url, true);
+
+        let message_id = announce_fronters(&config, &[fronter("a", "Annalea")], Some("existing"))
+            .await
+            .unwrap();
+
+        assert_eq!(message_id, "existing");
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        assert_eq!(
+            received[0].request_line(),
+            format!("PATCH {MOCK_WEBHOOK_PATH}/messages/existing")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_announce_fronters_reposts_deleted_message() {
+        let (url, received) = start_mock_discord_webhook_server().await;
+        let config = create_test_config(&url, true);
+
+        let message_id = announce_fronters(&config, &[fronter("a", "Annalea")], Some("deleted"))
+            .await
+            .unwrap();
+
+        assert_eq!(message_id, "posted");
+        let requests: Vec<String> = received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(test_support::MockRequest::request_line)
+            .collect();
+        assert_eq!(
+            requests,
+            vec![
+                format!("PATCH {MOCK_WEBHOOK_PATH}/messages/deleted"),
+                format!("POST {MOCK_WEBHOOK_PATH}?wait=true"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_updater_announces_only_most_recent_of_quick_changes() {
+        let (url, received) = start_mock_discord_webhook_server().await;
+        let config = create_test_config(&url, false);
+        let db_pool = sqlx::PgPool::connect_lazy("postgres://unused@localhost/unused").unwrap();
+
+        let mut updater = DiscordWebhookUpdater::new();
+        updater.setup(&config, &db_pool).await.unwrap();
+
+        for name in ["Annalea", "Borgn", "Clementine"] {
+            updater
+                .update_fronting_status(&config, &[fronter("x", name)])
+                .await
+                .unwrap();
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        let body: serde_json::Value = received[0].json();
+        assert_eq!(body["embeds"][0]["title"], "Clementine");
+        assert_eq!(updater.last_operation_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_updater_reports_failed_delivery() {
+        let (url, _) = start_mock_discord_webhook_server().await;
+        let config = create_test_config(&format!("{url}/unknown"), false);
+        let db_pool = sqlx::PgPool::connect_lazy("postgres://unused@localhost/unused").unwrap();
+
+        let mut updater = DiscordWebhookUpdater::new();
+        updater.setup(&config, &db_pool).await.unwrap();
+
+        updater
+            .update_fronting_status(&config, &[fronter("a", "Annalea")])
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+        updater
+            .update_fronting_status(&config, &[fronter("b", "Borgn")])
+            .await
+            .unwrap();
+
+        assert!(
+            updater
+                .last_operation_error
+                .unwrap()
+                .contains("404 Not Found")
+        );
+    }
+}
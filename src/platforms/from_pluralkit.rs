@@ -0,0 +1,542 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, Weak};
+
+use crate::platforms::same_members;
+use crate::plurality::{self, FrontEntry, Member, PluralKitSwitchEntry};
+use crate::users;
+use anyhow::{Result, anyhow};
+use pluralsync_base::clock;
+
+/// Only the most recent switches created by `PluralSync` can show up as the latest switch when polling.
+const OWN_SWITCHES_REMEMBERED: usize = 16;
+
+/// The websocket events of the writes into Simply Plural arrive with some delay.
+const SIMPLY_PLURAL_SETTLE_TIME: chrono::Duration = chrono::Duration::seconds(60);
+
+/// Shared between the `ToPluralKitUpdater` and its background task, which syncs the switches from `PluralKit`.
+#[derive(Debug, Default)]
+pub struct FromPluralKitState {
+    /// The switches which `PluralSync` itself created in `PluralKit`. They must not be synced back.
+    own_switch_ids: VecDeque<String>,
+    /// The most recent switch synced into Simply Plural. It must not be pushed back into `PluralKit`.
+    synced_switch: Option<SyncedSwitch>,
+    /// Outcome of the most recent sync into Simply Plural, as it happens in the background.
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct SyncedSwitch {
+    /// pkIds of the member fronts in Simply Plural before and after the sync
+    previous_pluralkit_ids: Vec<String>,
+    pluralkit_ids: Vec<String>,
+    /// Until then, Simply Plural may still report the fronts in between, e.g. the old and new fronters together.
+    settles_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl FromPluralKitState {
+    pub fn record_own_switch(&mut self, switch_id: String) {
+        self.own_switch_ids.push_back(switch_id);
+        if self.own_switch_ids.len() > OWN_SWITCHES_REMEMBERED {
+            self.own_switch_ids.pop_front();
+        }
+    }
+
+    #[must_use]
+    pub fn is_own_switch(&self, switch_id: &str) -> bool {
+        self.own_switch_ids.iter().any(|id| id == switch_id)
+    }
+
+    /// Whether the fronters with these pkIds only reflect a switch from `PluralKit` and hence mustn't be pushed back.
+    ///
+    /// `PluralKit` already has the switch, possibly with members which Simply Plural doesn't know.
+    /// Once Simply Plural shows other fronters, they are pushed as usual again.
+    pub fn is_synced_from_pluralkit(&mut self, pluralkit_ids: &[String]) -> bool {
+        let Some(synced) = &self.synced_switch else {
+            return false;
+        };
+
+        let is_synced = same_members(pluralkit_ids, &synced.pluralkit_ids);
+        // the new fronts are started before the old ones are ended. hence, either all old or all new members front.
+        let contains_all = |ids: &[String]| ids.iter().all(|id| pluralkit_ids.contains(id));
+        let is_in_between = clock::now() < synced.settles_at
+            && !same_members(pluralkit_ids, &synced.previous_pluralkit_ids)
+            && (contains_all(&synced.previous_pluralkit_ids)
+                || contains_all(&synced.pluralkit_ids))
+            && pluralkit_ids.iter().all(|id| {
+                synced.pluralkit_ids.contains(id) || synced.previous_pluralkit_ids.contains(id)
+            });
+
+        if !is_synced && !is_in_between {
+            self.synced_switch = None;
+        }
+        is_synced || is_in_between
+    }
+}
+
+/// The writes which make the live member fronts in Simply Plural match a switch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SimplyPluralFrontChanges {
+    members_to_start: Vec<String>,
+    /// front entry IDs with their end times
+    fronts_to_end: Vec<(String, chrono::DateTime<chrono::Utc>)>,
+}
+
+/// Polls the latest switch in `PluralKit` and writes new switches into the Simply Plural front history.
+///
+/// The switch found at the start is only the baseline, because Simply Plural might have changed since
+/// and is synced to `PluralKit` anyways. The task ends by itself, once the updater and with it the state is dropped.
+pub async fn sync_switches_from_pluralkit(
+    config: users::UserConfigForUpdater,
+    state: Weak<Mutex<FromPluralKitState>>,
+) {
+    let user_id = &config.user_id;
+
+    // None means, that the baseline is yet to be seen.
+    // The members are compared as well, because a switch can be edited after its creation.
+    let mut last_seen_switch: Option<Option<(String, Vec<String>)>> = None;
+
+    loop {
+        let Some(shared_state) = state.upgrade() else {
+            log::info!("# | sync_switches_from_pluralkit | {user_id} | updater dropped. stopping.");
+            break;
+        };
+
+        match plurality::pluralkit_http_get_latest_switch(&config).await {
+            Ok(latest_switch) => {
+                let seen = latest_switch
+                    .as_ref()
+                    .map(|s| (s.id.clone(), s.members.clone()));
+                let changed = last_seen_switch.as_ref().is_some_and(|last| *last != seen);
+                last_seen_switch = Some(seen);
+
+                if let Some(switch) = latest_switch.filter(|_| changed) {
+                    let result =
+                        sync_switch_into_simply_plural(&config, &shared_state, &switch).await;
+                    if let Err(e) = &result {
+                        log::warn!("# | sync_switches_from_pluralkit | {user_id} | {e}");
+                    }
+                    if let Ok(mut shared_state) = shared_state.lock() {
+                        shared_state.last_error = result.err().map(|e| e.to_string());
+                    }
+                }
+            }
+            Err(e) => {
+                log::warn!(
+                    "# | sync_switches_from_pluralkit | {user_id} | polling latest switch failed: {e}"
+                );
+            }
+        }
+
+        drop(shared_state);
+        tokio::time::sleep(plurality::PLURALKIT_POLL_INTERVAL).await;
+    }
+}
+
+pub async fn sync_switch_into_simply_plural(
+    config: &users::UserConfigForUpdater,
+    state: &Mutex<FromPluralKitState>,
+    switch: &PluralKitSwitchEntry,
+) -> Result<()> {
+    let user_id = &config.user_id;
+
+    let own_switch = state
+        .lock()
+        .map_err(|e| anyhow!(e.to_string()))?
+        .is_own_switch(&switch.id);
+    if own_switch {
+        log::info!(
+            "# | sync_switch_into_simply_plural | {user_id} | {} was created by PluralSync. skipping.",
+            switch.id
+        );
+        return Ok(());
+    }
+
+    let system_id = plurality::simply_plural_http_get_system_id(config).await?;
+    let members = plurality::simply_plural_http_get_members(config, &system_id).await?;
+    let live_fronts = plurality::simply_plural_http_request_get_fronters(config).await?;
+
+    let changes = simply_plural_front_changes(switch, &members, &live_fronts);
+
+    let mut synced_switch = SyncedSwitch {
+        previous_pluralkit_ids: live_pluralkit_ids(&members, &live_fronts),
+        pluralkit_ids: switch_pluralkit_ids(switch, &members),
+        settles_at: clock::now() + SIMPLY_PLURAL_SETTLE_TIME,
+    };
+    state
+        .lock()
+        .map_err(|e| anyhow!(e.to_string()))?
+        .synced_switch = Some(synced_switch.clone());
+
+    let Some(changes) = changes else {
+        log::info!(
+            "# | sync_switch_into_simply_plural | {user_id} | {} has the same members as Simply Plural. skipping.",
+            switch.id
+        );
+        return Ok(());
+    };
+
+    let result = apply_simply_plural_front_changes(config, switch, &changes).await;
+
+    // after a failure, the fronts in Simply Plural are pushed to PluralKit as usual.
+    synced_switch.settles_at = clock::now() + SIMPLY_PLURAL_SETTLE_TIME;
+    state
+        .lock()
+        .map_err(|e| anyhow!(e.to_string()))?
+        .synced_switch = result.is_ok().then_some(synced_switch);
+    result?;
+
+    log::info!(
+        "# | sync_switch_into_simply_plural | {user_id} | {} | started {:?} | ended {:?}",
+        switch.id,
+        changes.members_to_start,
+        changes.fronts_to_end
+    );
+
+    Ok(())
+}
+
+async fn apply_simply_plural_front_changes(
+    config: &users::UserConfigForUpdater,
+    switch: &PluralKitSwitchEntry,
+    changes: &SimplyPluralFrontChanges,
+) -> Result<()> {
+    // new fronts are started first, such that nobody is shown as fronting in between
+    for member_id in &changes.members_to_start {
+        plurality::simply_plural_http_post_front_history(config, member_id, switch.timestamp)
+            .await?;
+    }
+    for (front_entry_id, end_time) in &changes.fronts_to_end {
+        plurality::simply_plural_http_patch_front_history_end(config, front_entry_id, *end_time)
+            .await?;
+    }
+    Ok(())
+}
+
+/// The pkIds of the switch members as shown in Simply Plural. Members unknown to Simply Plural are left out.
+fn switch_pluralkit_ids(switch: &PluralKitSwitchEntry, members: &[Member]) -> Vec<String> {
+    switch
+        .members
+        .iter()
+        .filter_map(|pluralkit_id| {
+            members
+                .iter()
+                .filter_map(|m| m.content.pluralkit_id.as_ref())
+                .find(|id| id.eq_ignore_ascii_case(pluralkit_id))
+                .cloned()
+        })
+        .collect()
+}
+
+/// The pkIds of the live member fronts in Simply Plural.
+fn live_pluralkit_ids(members: &[Member], live_fronts: &[FrontEntry]) -> Vec<String> {
+    live_fronts
+        .iter()
+        .filter_map(|fe| {
+            members
+                .iter()
+                .find(|m| m.member_id == fe.content.fronter_id)
+        })
+        .filter_map(|m| m.content.pluralkit_id.clone())
+        .collect()
+}
+
+/// Computes the changes to the live member fronts in Simply Plural. None, if they already match the switch.
+///
+/// Members are matched by their `pkId`. Custom fronts and members without `pkId` can't appear in `PluralKit`
+/// and are hence left untouched.
+fn simply_plural_front_changes(
+    switch: &PluralKitSwitchEntry,
+    members: &[Member],
+    live_fronts: &[FrontEntry],
+) -> Option<SimplyPluralFrontChanges> {
+    let switch_member_ids: Vec<String> = switch
+        .members
+        .iter()
+        .filter_map(|pluralkit_id| {
+            let member = members.iter().find(|m| {
+                m.content
+                    .pluralkit_id
+                    .as_deref()
+                    .is_some_and(|id| id.eq_ignore_ascii_case(pluralkit_id))
+            });
+            if member.is_none() {
+                log::info!(
+                    "# | simply_plural_front_changes | no Simply Plural member with pkId {pluralkit_id}"
+                );
+            }
+            member.map(|m| m.member_id.clone())
+        })
+        .collect();
+
+    let live_member_fronts: Vec<&FrontEntry> = live_fronts
+        .iter()
+        .filter(|fe| {
+            members
+                .iter()
+                .any(|m| m.member_id == fe.content.fronter_id && m.content.pluralkit_id.is_some())
+        })
+        .collect();
+    let live_member_ids: Vec<String> = live_member_fronts
+        .iter()
+        .map(|fe| fe.content.fronter_id.clone())
+        .collect();
+
+    if same_members(&switch_member_ids, &live_member_ids) {
+        return None;
+    }
+
+    Some(SimplyPluralFrontChanges {
+        members_to_start: switch_member_ids
+            .iter()
+            .filter(|id| !live_member_ids.contains(id))
+            .cloned()
+            .collect(),
+        fronts_to_end: live_member_fronts
+            .iter()
+            .filter(|fe| !switch_member_ids.contains(&fe.content.fronter_id))
+            // a switch may be logged retroactively. a front can't end before it started though.
+            .map(|fe| {
+                (
+                    fe.front_entry_id.clone(),
+                    switch.timestamp.max(fe.content.start_time),
+                )
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::database::Decrypted;
+    use crate::test_support;
+    use crate::users::UserConfigDbEntries;
+
+    fn timestamp(epoch_millis: i64) -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::from_timestamp_millis(epoch_millis).unwrap()
+    }
+
+    fn switch(id: &str, members: &[&str], epoch_millis: i64) -> PluralKitSwitchEntry {
+        PluralKitSwitchEntry {
+            id: id.to_owned(),
+            timestamp: timestamp(epoch_millis),
+            members: members.iter().map(|&m| m.to_owned()).collect(),
+        }
+    }
+
+    fn mock_members_json() -> serde_json::Value {
+        serde_json::json!([
+            {"id": "sp_a", "content": {"name": "Annalea", "pkId": "aaaaa"}},
+            {"id": "sp_b", "content": {"name": "Borgn", "pkId": "bbbbb"}},
+            {"id": "sp_c", "content": {"name": "Cassia"}},
+            {"id": "sp_d", "content": {"name": "Daro", "pkId": "ddddd"}},
+        ])
+    }
+
+    fn mock_fronters_json() -> serde_json::Value {
+        serde_json::json!([
+            {"id": "fe_a", "content": {"member": "sp_a", "uid": "system_123", "startTime": 1_000, "live": true}},
+            {"id": "fe_c", "content": {"member": "sp_c", "uid": "system_123", "startTime": 1_000, "live": true}},
+            {"id": "fe_d", "content": {"member": "sp_d", "uid": "system_123", "startTime": 5_000, "live": true}},
+            {"id": "fe_custom", "content": {"member": "custom_1", "uid": "system_123", "startTime": 1_000, "live": true}},
+        ])
+    }
+
+    async fn start_mock_simply_plural() -> String {
+        test_support::start_mock_simply_plural(serde_json::json!({
+            "system_id": "system_123",
+            "members": mock_members_json(),
+            "customFronts": [],
+            "customFields": [],
+            "friend": {},
+            "frontHistory": mock_fronters_json(),
+        }))
+        .await
+    }
+
+    fn create_test_config(simply_plural_base_url: &str) -> users::UserConfigForUpdater {
+        test_support::create_test_config_with_endpoints(
+            &UserConfigDbEntries {
+                pluralkit_token: Some(Decrypted {
+                    secret: "pk_token_123".to_owned(),
+                }),
+                enable_to_pluralkit: true,
+                enable_from_pluralkit: true,
+                ..test_support::db_config()
+            },
+            &plurality::SimplyPluralEndpoints {
+                base_url: simply_plural_base_url.to_owned(),
+                websocket_url: String::new(),
+            },
+        )
+    }
+
+    /// The fronters and start times of the live fronts in Simply Plural.
+    async fn live_fronts_in_simply_plural(
+        config: &users::UserConfigForUpdater,
+    ) -> Vec<(String, i64)> {
+        let mut live_fronts: Vec<(String, i64)> =
+            plurality::simply_plural_http_request_get_fronters(config)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|fe| {
+                    (
+                        fe.content.fronter_id,
+                        fe.content.start_time.timestamp_millis(),
+                    )
+                })
+                .collect();
+        live_fronts.sort();
+        live_fronts
+    }
+
+    fn members() -> Vec<Member> {
+        serde_json::from_value(mock_members_json()).unwrap()
+    }
+
+    fn live_fronts() -> Vec<FrontEntry> {
+        serde_json::from_value(mock_fronters_json()).unwrap()
+    }
+
+    #[test]
+    fn test_front_changes_start_new_and_end_old_member_fronts() {
+        let changes = simply_plural_front_changes(
+            &switch("sw1", &["bbbbb", "AAAAA"], 3_000),
+            &members(),
+            &live_fronts(),
+        );
+
+        // the custom front and cassia without pkId stay. the end time of daro's front isn't before its start.
+        assert_eq!(
+            changes,
+            Some(SimplyPluralFrontChanges {
+                members_to_start: vec!["sp_b".to_owned()],
+                fronts_to_end: vec![("fe_d".to_owned(), timestamp(5_000))],
+            })
+        );
+    }
+
+    #[test]
+    fn test_front_changes_none_for_same_members() {
+        // pluralkit members unknown to simply plural are ignored
+        let changes = simply_plural_front_changes(
+            &switch("sw1", &["unknown", "aaaaa"], 3_000),
+            &[members()[0].clone()],
+            &live_fronts(),
+        );
+
+        assert_eq!(changes, None);
+    }
+
+    #[test]
+    fn test_own_switches_are_remembered() {
+        let mut state = FromPluralKitState::default();
+        for i in 0..=OWN_SWITCHES_REMEMBERED {
+            state.record_own_switch(format!("sw{i}"));
+        }
+
+        assert!(!state.is_own_switch("sw0"));
+        assert!(state.is_own_switch("sw1"));
+        assert!(state.is_own_switch(&format!("sw{OWN_SWITCHES_REMEMBERED}")));
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|&id| id.to_owned()).collect()
+    }
+
+    #[test]
+    fn test_synced_switch_and_fronts_in_between_are_recognized() {
+        let mut state = FromPluralKitState {
+            synced_switch: Some(SyncedSwitch {
+                previous_pluralkit_ids: ids(&["aaaaa", "bbbbb"]),
+                pluralkit_ids: ids(&["bbbbb", "ddddd"]),
+                settles_at: clock::now() + SIMPLY_PLURAL_SETTLE_TIME,
+            }),
+            ..Default::default()
+        };
+
+        assert!(state.is_synced_from_pluralkit(&ids(&["ddddd", "bbbbb"])));
+        // new fronts started, old ones not yet ended
+        assert!(state.is_synced_from_pluralkit(&ids(&["aaaaa", "bbbbb", "ddddd"])));
+        // switching back to the old fronters is pushed. then, the synced switch is forgotten.
+        assert!(!state.is_synced_from_pluralkit(&ids(&["aaaaa", "bbbbb"])));
+        assert!(!state.is_synced_from_pluralkit(&ids(&["bbbbb", "ddddd"])));
+    }
+
+    #[test]
+    fn test_other_fronters_are_not_in_between() {
+        let mut state = FromPluralKitState {
+            synced_switch: Some(SyncedSwitch {
+                previous_pluralkit_ids: ids(&["aaaaa", "bbbbb"]),
+                pluralkit_ids: ids(&["bbbbb", "ddddd"]),
+                settles_at: clock::now() + SIMPLY_PLURAL_SETTLE_TIME,
+            }),
+            ..Default::default()
+        };
+
+        // a member staying in front never disappears in between
+        assert!(!state.is_synced_from_pluralkit(&ids(&["aaaaa", "ddddd"])));
+        assert!(state.synced_switch.is_none());
+    }
+
+    #[test]
+    fn test_fronts_in_between_are_pushed_after_settling() {
+        let mut state = FromPluralKitState {
+            synced_switch: Some(SyncedSwitch {
+                previous_pluralkit_ids: ids(&["aaaaa"]),
+                pluralkit_ids: ids(&["bbbbb"]),
+                settles_at: clock::now(),
+            }),
+            ..Default::default()
+        };
+
+        assert!(state.is_synced_from_pluralkit(&ids(&["bbbbb"])));
+        assert!(!state.is_synced_from_pluralkit(&ids(&["aaaaa", "bbbbb"])));
+    }
+
+    #[tokio::test]
+    async fn test_sync_switch_writes_front_history() {
+        let url = start_mock_simply_plural().await;
+        let config = create_test_config(&url);
+        let state = Mutex::new(FromPluralKitState::default());
+
+        sync_switch_into_simply_plural(&config, &state, &switch("sw1", &["aaaaa", "bbbbb"], 9_000))
+            .await
+            .unwrap();
+
+        // borgn started fronting and daro's front ended
+        assert_eq!(
+            live_fronts_in_simply_plural(&config).await,
+            vec![
+                ("custom_1".to_owned(), 1_000),
+                ("sp_a".to_owned(), 1_000),
+                ("sp_b".to_owned(), 9_000),
+                ("sp_c".to_owned(), 1_000),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_switch_skips_own_switches() {
+        let url = start_mock_simply_plural().await;
+        let config = create_test_config(&url);
+        let state = Mutex::new(FromPluralKitState::default());
+        state.lock().unwrap().record_own_switch("sw1".to_owned());
+
+        sync_switch_into_simply_plural(&config, &state, &switch("sw1", &["bbbbb"], 9_000))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            live_fronts_in_simply_plural(&config).await,
+            vec![
+                ("custom_1".to_owned(), 1_000),
+                ("sp_a".to_owned(), 1_000),
+                ("sp_c".to_owned(), 1_000),
+                ("sp_d".to_owned(), 5_000),
+            ]
+        );
+    }
+}
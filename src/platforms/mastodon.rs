@@ -0,0 +1,380 @@
+use crate::{platforms, plurality, record_if_error, users};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+/// Mastodon limits the values of the profile fields. Counted in characters like Ruby does.
+const MASTODON_FIELD_VALUE_MAX_LENGTH: usize = 255;
+
+const MASTODON_DISPLAY_NAME_MAX_LENGTH: usize = 30;
+
+#[derive(Deserialize, Debug)]
+struct CredentialAccount {
+    username: String,
+    source: CredentialAccountSource,
+}
+
+/// The profile as entered by the user, i.e. without the HTML of the rendered fields.
+#[derive(Deserialize, Debug)]
+struct CredentialAccountSource {
+    #[serde(default)]
+    fields: Vec<ProfileField>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct ProfileField {
+    name: String,
+    value: String,
+}
+
+#[derive(Serialize, Debug)]
+struct UpdateCredentials {
+    fields_attributes: Vec<ProfileField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+}
+
+pub struct MastodonUpdater {
+    pub last_operation_error: Option<String>,
+    pub last_payload: Option<String>,
+}
+
+impl Default for MastodonUpdater {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MastodonUpdater {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            last_operation_error: None,
+            last_payload: None,
+        }
+    }
+
+    pub async fn setup(&mut self, config: &users::UserConfigForUpdater) -> Result<()> {
+        let client = record_if_error!(
+            self,
+            platforms::public_url_client(config, &config.mastodon_instance_url).await
+        )?;
+        let account = record_if_error!(self, fetch_credential_account(&client, config).await)?;
+        log::info!(
+            "# | mastodon setup | {} | authenticated as {}",
+            config.user_id,
+            account.username
+        );
+        Ok(())
+    }
+
+    pub async fn update_fronting_status(
+        &mut self,
+        config: &users::UserConfigForUpdater,
+        fronts: &[plurality::Fronter],
+    ) -> Result<()> {
+        let field_value = plurality::format_fronting_status(&field_fronting_format(config), fronts);
+        self.last_payload = Some(field_value.clone());
+        record_if_error!(self, update_mastodon(config, &field_value, fronts).await)
+    }
+}
+
+/// The field already has a name (e.g. "Fronting"), hence the status prefix is left out.
+fn field_fronting_format(config: &users::UserConfigForUpdater) -> plurality::FrontingFormat {
+    let mut fronting_format =
+        plurality::generic_fronting_format(config, plurality::DisplayNamePlatform::Mastodon);
+    fronting_format.prefix = String::new();
+    fronting_format.max_length = Some(MASTODON_FIELD_VALUE_MAX_LENGTH);
+    fronting_format.length_metric = plurality::LengthMetric::UnicodeScalars;
+    fronting_format
+}
+
+/// The configured display name followed by the fronting status, e.g. "Our System F: Annalea".
+/// If the display name leaves no room for the status, then only the display name is used.
+fn format_mastodon_display_name(
+    config: &users::UserConfigForUpdater,
+    base_display_name: &str,
+    fronts: &[plurality::Fronter],
+) -> String {
+    let remaining_length = MASTODON_DISPLAY_NAME_MAX_LENGTH
+        .saturating_sub(base_display_name.chars().count())
+        .saturating_sub(1);
+    if remaining_length == 0 {
+        return base_display_name.to_owned();
+    }
+
+    let mut fronting_format =
+        plurality::generic_fronting_format(config, plurality::DisplayNamePlatform::Mastodon);
+    fronting_format.max_length = Some(remaining_length);
+    fronting_format.length_metric = plurality::LengthMetric::UnicodeScalars;
+    let status_string = plurality::format_fronting_status(&fronting_format, fronts);
+
+    if status_string.is_empty() || status_string.chars().count() > remaining_length {
+        base_display_name.to_owned()
+    } else {
+        format!("{base_display_name} {status_string}")
+    }
+}
+
+/// Sets the value of the field with the configured name or appends the field, if there is none yet.
+fn with_fronting_field(
+    mut fields: Vec<ProfileField>,
+    field_name: &str,
+    field_value: &str,
+) -> Vec<ProfileField> {
+    match fields.iter_mut().find(|field| field.name == field_name) {
+        Some(field) => field.value = field_value.to_owned(),
+        None => fields.push(ProfileField {
+            name: field_name.to_owned(),
+            value: field_value.to_owned(),
+        }),
+    }
+    fields
+}
+
+async fn update_mastodon(
+    config: &users::UserConfigForUpdater,
+    field_value: &str,
+    fronts: &[plurality::Fronter],
+) -> Result<()> {
+    let client = platforms::public_url_client(config, &config.mastodon_instance_url).await?;
+
+    // Mastodon replaces all fields on update. Hence, the other fields are sent unchanged.
+    let account = fetch_credential_account(&client, config).await?;
+
+    let update = UpdateCredentials {
+        fields_attributes: with_fronting_field(
+            account.source.fields,
+            &config.mastodon_field_name,
+            field_value,
+        ),
+        display_name: config
+            .mastodon_display_name
+            .as_ref()
+            .map(|base| format_mastodon_display_name(config, base, fronts)),
+    };
+
+    client
+        .patch(mastodon_api_url(
+            config,
+            &["api", "v1", "accounts", "update_credentials"],
+        )?)
+        .bearer_auth(&config.mastodon_token.secret)
+        .json(&update)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    log::info!(
+        "# | update_mastodon | {} | field '{}' updated to '{field_value}'",
+        config.user_id,
+        config.mastodon_field_name
+    );
+
+    Ok(())
+}
+
+fn mastodon_api_url(
+    config: &users::UserConfigForUpdater,
+    segments: &[&str],
+) -> Result<reqwest::Url> {
+    let mut url = reqwest::Url::parse(&config.mastodon_instance_url)?;
+    url.path_segments_mut()
+        .map_err(|()| anyhow!("mastodon_api_url: mastodon_instance_url cannot be a base"))?
+        .pop_if_empty()
+        .extend(segments);
+    Ok(url)
+}
+
+async fn fetch_credential_account(
+    client: &reqwest::Client,
+    config: &users::UserConfigForUpdater,
+) -> Result<CredentialAccount> {
+    let account = client
+        .get(mastodon_api_url(
+            config,
+            &["api", "v1", "accounts", "verify_credentials"],
+        )?)
+        .bearer_auth(&config.mastodon_token.secret)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(account)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::database::Decrypted;
+    use crate::test_support::{self, ReceivedRequests, fronter};
+    use crate::users::UserConfigDbEntries;
+
+    const MOCK_TOKEN: &str = "mastodon_mock_token";
+
+    /// Minimal stand-in for a Mastodon instance. Records the requests and answers the known paths.
+    async fn start_mock_mastodon_server() -> (String, ReceivedRequests) {
+        test_support::start_mock_http_server(|request| {
+            let authorized =
+                request.header("authorization") == Some(format!("Bearer {MOCK_TOKEN}").as_str());
+            let (status, body) = match (request.method.as_str(), request.path.as_str()) {
+                _ if !authorized => (
+                    "401 Unauthorized",
+                    r#"{"error": "The access token is invalid"}"#,
+                ),
+                ("GET", "/api/v1/accounts/verify_credentials") => (
+                    "200 OK",
+                    r#"{"username": "oursystem", "display_name": "Our System", "source": {"note": "", "fields": [{"name": "Pronouns", "value": "they/them", "verified_at": null}, {"name": "Fronting", "value": "old", "verified_at": null}]}}"#,
+                ),
+                ("PATCH", "/api/v1/accounts/update_credentials") => {
+                    ("200 OK", r#"{"username": "oursystem"}"#)
+                }
+                _ => ("404 Not Found", r#"{"error": "Record not found"}"#),
+            };
+            (status, body.to_owned())
+        })
+        .await
+    }
+
+    fn create_test_config(
+        instance_url: &str,
+        token: &str,
+        field_name: &str,
+        display_name: Option<&str>,
+    ) -> users::UserConfigForUpdater {
+        test_support::create_test_config(&UserConfigDbEntries {
+            enable_mastodon: true,
+            mastodon_instance_url: Some(instance_url.to_owned()),
+            mastodon_field_name: Some(field_name.to_owned()),
+            mastodon_display_name: display_name.map(str::to_owned),
+            mastodon_token: Some(Decrypted {
+                secret: token.to_owned(),
+            }),
+            ..test_support::db_config()
+        })
+    }
+
+    fn sent_update(received: &ReceivedRequests) -> serde_json::Value {
+        received
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|request| request.request_line() == "PATCH /api/v1/accounts/update_credentials")
+            .unwrap()
+            .json()
+    }
+
+    #[tokio::test]
+    async fn test_mastodon_replaces_field_and_keeps_other_fields() {
+        let (url, received) = start_mock_mastodon_server().await;
+        let config = create_test_config(&url, MOCK_TOKEN, "Fronting", None);
+        let mut updater = MastodonUpdater::new();
+
+        updater.setup(&config).await.unwrap();
+        updater
+            .update_fronting_status(&config, &[fronter("Annalea"), fronter("Borgn")])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            sent_update(&received),
+            serde_json::json!({
+                "fields_attributes": [
+                    {"name": "Pronouns", "value": "they/them"},
+                    {"name": "Fronting", "value": "Annalea, Borgn"},
+                ]
+            })
+        );
+        assert_eq!(updater.last_payload.as_deref(), Some("Annalea, Borgn"));
+        assert_eq!(updater.last_operation_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_mastodon_appends_missing_field_and_sets_display_name() {
+        let (url, received) = start_mock_mastodon_server().await;
+        let config = create_test_config(&url, MOCK_TOKEN, "Front", Some("Our System"));
+        let mut updater = MastodonUpdater::new();
+
+        updater.setup(&config).await.unwrap();
+        updater
+            .update_fronting_status(&config, &[fronter("Annalea")])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            sent_update(&received),
+            serde_json::json!({
+                "fields_attributes": [
+                    {"name": "Pronouns", "value": "they/them"},
+                    {"name": "Fronting", "value": "old"},
+                    {"name": "Front", "value": "Annalea"},
+                ],
+                "display_name": "Our System F: Annalea",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mastodon_setup_fails_with_invalid_token() {
+        let (url, _) = start_mock_mastodon_server().await;
+        let config = create_test_config(&url, "wrong_token", "Fronting", None);
+        let mut updater = MastodonUpdater::new();
+
+        assert!(updater.setup(&config).await.is_err());
+        assert!(
+            updater
+                .last_operation_error
+                .as_ref()
+                .unwrap()
+                .contains("401 Unauthorized")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mastodon_setup_fails_for_private_instance() {
+        let (url, received) = start_mock_mastodon_server().await;
+        let mut config = create_test_config(&url, MOCK_TOKEN, "Fronting", None);
+        config.allow_private_network_urls = false;
+        let mut updater = MastodonUpdater::new();
+
+        assert!(updater.setup(&config).await.is_err());
+        assert!(
+            updater
+                .last_operation_error
+                .unwrap()
+                .contains("is not a public address")
+        );
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mastodon_display_name_stays_within_limit() {
+        let config = create_test_config("https://mastodon.example", MOCK_TOKEN, "Fronting", None);
+        let fronts = [fronter("Annalea"), fronter("Borgn"), fronter("Cassia")];
+
+        let display_name = format_mastodon_display_name(&config, "Our System", &fronts);
+        // the names are truncated to fit the 19 characters after the base display name
+        assert_eq!(display_name, "Our System F:Annal,Borgn,Cassi");
+        assert!(display_name.chars().count() <= MASTODON_DISPLAY_NAME_MAX_LENGTH);
+
+        let long_base = "A very long system name of 29";
+        assert_eq!(
+            format_mastodon_display_name(&config, long_base, &fronts),
+            long_base
+        );
+    }
+
+    #[test]
+    fn test_mastodon_field_value_is_limited_to_255_characters() {
+        let config = create_test_config("https://mastodon.example", MOCK_TOKEN, "Fronting", None);
+        let fronts: Vec<_> = (0..40)
+            .map(|i| fronter(&format!("Fronter number {i}")))
+            .collect();
+
+        let field_value =
+            plurality::format_fronting_status(&field_fronting_format(&config), &fronts);
+        assert!(!field_value.is_empty());
+        assert!(field_value.chars().count() <= MASTODON_FIELD_VALUE_MAX_LENGTH);
+    }
+}
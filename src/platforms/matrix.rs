@@ -0,0 +1,467 @@
+use crate::{platforms, plurality, record_if_error, users};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+/// Synapse rejects longer displaynames.
+const MATRIX_DISPLAYNAME_MAX_LENGTH: usize = 256;
+
+/// Where the displayname on Matrix is set to the fronting status.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    sqlx::Type,
+    specta::Type,
+    strum_macros::Display,
+)]
+#[specta(export)]
+#[sqlx(type_name = "matrix_displayname_scope_enum")]
+pub enum MatrixDisplayNameScope {
+    /// Only the presence status message is set
+    #[default]
+    Unchanged,
+    /// The displayname of the profile. Matrix also updates it in all joined rooms.
+    Global,
+    /// Only the displayname in the configured rooms
+    Rooms,
+}
+
+#[derive(Deserialize, Debug)]
+struct WhoAmI {
+    user_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Presence {
+    presence: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status_msg: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct DisplayName<'a> {
+    displayname: &'a str,
+}
+
+pub struct MatrixUpdater {
+    pub last_operation_error: Option<String>,
+    pub last_payload: Option<String>,
+    matrix_user_id: Option<String>,
+}
+
+impl Default for MatrixUpdater {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MatrixUpdater {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            last_operation_error: None,
+            last_payload: None,
+            matrix_user_id: None,
+        }
+    }
+
+    pub async fn setup(&mut self, config: &users::UserConfigForUpdater) -> Result<()> {
+        let matrix_user_id = record_if_error!(self, fetch_matrix_user_id(config).await)?;
+        log::info!(
+            "# | matrix setup | {} | authenticated as {matrix_user_id}",
+            config.user_id
+        );
+        self.matrix_user_id = Some(matrix_user_id);
+        Ok(())
+    }
+
+    pub async fn update_fronting_status(
+        &mut self,
+        config: &users::UserConfigForUpdater,
+        fronts: &[plurality::Fronter],
+    ) -> Result<()> {
+        let matrix_user_id = record_if_error!(
+            self,
+            self.matrix_user_id
+                .clone()
+                .ok_or_else(|| anyhow!("update_fronting_status: Updater not initalized!"))
+        )?;
+        let status_string = plurality::format_fronting_status(
+            &plurality::generic_fronting_format(config, plurality::DisplayNamePlatform::Matrix),
+            fronts,
+        );
+        self.last_payload = Some(status_string.clone());
+        record_if_error!(
+            self,
+            update_matrix(config, &matrix_user_id, &status_string, fronts).await
+        )
+    }
+}
+
+async fn update_matrix(
+    config: &users::UserConfigForUpdater,
+    matrix_user_id: &str,
+    status_string: &str,
+    fronts: &[plurality::Fronter],
+) -> Result<()> {
+    let client = platforms::public_url_client(config, &config.matrix_homeserver_url).await?;
+
+    set_presence_status_msg(&client, config, matrix_user_id, status_string).await?;
+
+    let displayname = format_matrix_displayname(config, fronts);
+    match config.matrix_displayname_scope {
+        MatrixDisplayNameScope::Unchanged => (),
+        MatrixDisplayNameScope::Global => {
+            set_global_displayname(&client, config, matrix_user_id, &displayname).await?;
+        }
+        MatrixDisplayNameScope::Rooms => {
+            for room_id in &config.matrix_displayname_rooms {
+                set_room_displayname(&client, config, matrix_user_id, room_id, &displayname)
+                    .await?;
+            }
+        }
+    }
+
+    log::info!(
+        "# | update_matrix | {} | matrix updated to '{status_string}' ({})",
+        config.user_id,
+        config.matrix_displayname_scope
+    );
+
+    Ok(())
+}
+
+fn format_matrix_displayname(
+    config: &users::UserConfigForUpdater,
+    fronts: &[plurality::Fronter],
+) -> String {
+    let mut fronting_format =
+        plurality::generic_fronting_format(config, plurality::DisplayNamePlatform::Matrix);
+    fronting_format.max_length = Some(MATRIX_DISPLAYNAME_MAX_LENGTH);
+    plurality::format_fronting_status(&fronting_format, fronts)
+}
+
+/// URL of the client-server API endpoint with the given path segments. The segments are percent-encoded.
+fn matrix_api_url(config: &users::UserConfigForUpdater, segments: &[&str]) -> Result<reqwest::Url> {
+    let mut url = reqwest::Url::parse(&config.matrix_homeserver_url)?;
+    url.path_segments_mut()
+        .map_err(|()| anyhow!("matrix_api_url: matrix_homeserver_url cannot be a base"))?
+        .pop_if_empty()
+        .extend(["_matrix", "client", "v3"])
+        .extend(segments);
+    Ok(url)
+}
+
+async fn fetch_matrix_user_id(config: &users::UserConfigForUpdater) -> Result<String> {
+    let client = platforms::public_url_client(config, &config.matrix_homeserver_url).await?;
+
+    let whoami: WhoAmI = client
+        .get(matrix_api_url(config, &["account", "whoami"])?)
+        .bearer_auth(&config.matrix_access_token.secret)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(whoami.user_id)
+}
+
+/// The presence itself (online, unavailable, ...) is kept as it is. Only the message is replaced.
+async fn set_presence_status_msg(
+    client: &reqwest::Client,
+    config: &users::UserConfigForUpdater,
+    matrix_user_id: &str,
+    status_string: &str,
+) -> Result<()> {
+    let presence_url = matrix_api_url(config, &["presence", matrix_user_id, "status"])?;
+
+    let current: Presence = client
+        .get(presence_url.clone())
+        .bearer_auth(&config.matrix_access_token.secret)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    client
+        .put(presence_url)
+        .bearer_auth(&config.matrix_access_token.secret)
+        .json(&Presence {
+            presence: current.presence,
+            status_msg: Some(status_string.to_owned()),
+        })
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+async fn set_global_displayname(
+    client: &reqwest::Client,
+    config: &users::UserConfigForUpdater,
+    matrix_user_id: &str,
+    displayname: &str,
+) -> Result<()> {
+    client
+        .put(matrix_api_url(
+            config,
+            &["profile", matrix_user_id, "displayname"],
+        )?)
+        .bearer_auth(&config.matrix_access_token.secret)
+        .json(&DisplayName { displayname })
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// The per-room displayname is part of the membership event. Its other fields (membership, avatar, ...) are kept.
+async fn set_room_displayname(
+    client: &reqwest::Client,
+    config: &users::UserConfigForUpdater,
+    matrix_user_id: &str,
+    room_id: &str,
+    displayname: &str,
+) -> Result<()> {
+    let member_url = matrix_api_url(
+        config,
+        &["rooms", room_id, "state", "m.room.member", matrix_user_id],
+    )?;
+
+    let mut member_event: serde_json::Map<String, serde_json::Value> = client
+        .get(member_url.clone())
+        .bearer_auth(&config.matrix_access_token.secret)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    member_event.insert(
+        "displayname".to_owned(),
+        serde_json::Value::String(displayname.to_owned()),
+    );
+
+    client
+        .put(member_url)
+        .bearer_auth(&config.matrix_access_token.secret)
+        .json(&member_event)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::database::Decrypted;
+    use crate::test_support::{self, ReceivedRequests, fronter};
+    use crate::users::UserConfigDbEntries;
+
+    const MOCK_TOKEN: &str = "syt_mock_token";
+    const MOCK_USER: &str = "@alice:example.org";
+    const MOCK_ROOM: &str = "!room:example.org";
+
+    /// Minimal stand-in for a Matrix homeserver. Records the requests and answers the known paths.
+    async fn start_mock_matrix_server() -> (String, ReceivedRequests) {
+        test_support::start_mock_http_server(|request| {
+            let presence_path = format!("/_matrix/client/v3/presence/{MOCK_USER}/status");
+            let profile_path = format!("/_matrix/client/v3/profile/{MOCK_USER}/displayname");
+            let member_path =
+                format!("/_matrix/client/v3/rooms/{MOCK_ROOM}/state/m.room.member/{MOCK_USER}");
+            let authorized =
+                request.header("authorization") == Some(format!("Bearer {MOCK_TOKEN}").as_str());
+            match (request.method.as_str(), request.path.as_str()) {
+                _ if !authorized => (
+                    "401 Unauthorized",
+                    r#"{"errcode": "M_UNKNOWN_TOKEN"}"#.to_owned(),
+                ),
+                ("GET", "/_matrix/client/v3/account/whoami") => {
+                    ("200 OK", format!(r#"{{"user_id": "{MOCK_USER}"}}"#))
+                }
+                ("GET", p) if p == presence_path => (
+                    "200 OK",
+                    r#"{"presence": "unavailable", "last_active_ago": 42}"#.to_owned(),
+                ),
+                ("GET", p) if p == member_path => (
+                    "200 OK",
+                    r#"{"membership": "join", "displayname": "Alice", "avatar_url": "mxc://example.org/avatar"}"#.to_owned(),
+                ),
+                ("PUT", p) if p == presence_path || p == profile_path || p == member_path => {
+                    ("200 OK", "{}".to_owned())
+                }
+                _ => (
+                    "404 Not Found",
+                    r#"{"errcode": "M_UNRECOGNIZED"}"#.to_owned(),
+                ),
+            }
+        })
+        .await
+    }
+
+    fn create_test_config(
+        homeserver_url: &str,
+        token: &str,
+        scope: MatrixDisplayNameScope,
+    ) -> users::UserConfigForUpdater {
+        test_support::create_test_config(&UserConfigDbEntries {
+            enable_matrix: true,
+            matrix_homeserver_url: Some(homeserver_url.to_owned()),
+            matrix_displayname_scope: scope,
+            matrix_displayname_rooms: Some(vec![MOCK_ROOM.to_owned()]),
+            matrix_access_token: Some(Decrypted {
+                secret: token.to_owned(),
+            }),
+            ..test_support::db_config()
+        })
+    }
+
+    fn requests_with_bodies(received: &ReceivedRequests) -> Vec<String> {
+        received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| !request.body.is_empty())
+            .map(|request| format!("{} {}", request.request_line(), request.body))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_matrix_sets_status_msg_and_keeps_presence() {
+        let (url, received) = start_mock_matrix_server().await;
+        let config = create_test_config(&url, MOCK_TOKEN, MatrixDisplayNameScope::Unchanged);
+        let mut updater = MatrixUpdater::new();
+
+        updater.setup(&config).await.unwrap();
+        updater
+            .update_fronting_status(&config, &[fronter("Annalea"), fronter("Borgn")])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            requests_with_bodies(&received),
+            vec![format!(
+                r#"PUT /_matrix/client/v3/presence/{MOCK_USER}/status {{"presence":"unavailable","status_msg":"F: Annalea, Borgn"}}"#
+            )]
+        );
+        assert_eq!(updater.last_payload.as_deref(), Some("F: Annalea, Borgn"));
+        assert_eq!(updater.last_operation_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_matrix_sets_global_displayname() {
+        let (url, received) = start_mock_matrix_server().await;
+        let config = create_test_config(&url, MOCK_TOKEN, MatrixDisplayNameScope::Global);
+        let mut updater = MatrixUpdater::new();
+
+        updater.setup(&config).await.unwrap();
+        updater
+            .update_fronting_status(&config, &[fronter("Annalea")])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            requests_with_bodies(&received)[1],
+            format!(
+                r#"PUT /_matrix/client/v3/profile/{MOCK_USER}/displayname {{"displayname":"F: Annalea"}}"#
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_matrix_sets_room_displayname_and_keeps_membership() {
+        let (url, received) = start_mock_matrix_server().await;
+        let config = create_test_config(&url, MOCK_TOKEN, MatrixDisplayNameScope::Rooms);
+        let mut updater = MatrixUpdater::new();
+
+        updater.setup(&config).await.unwrap();
+        updater
+            .update_fronting_status(&config, &[fronter("Annalea")])
+            .await
+            .unwrap();
+
+        let member_put = &requests_with_bodies(&received)[1];
+        assert!(member_put.starts_with(&format!(
+            "PUT /_matrix/client/v3/rooms/{MOCK_ROOM}/state/m.room.member/{MOCK_USER} "
+        )));
+        let member_event: serde_json::Value = serde_json::from_str(
+            member_put
+                .split_once(' ')
+                .unwrap()
+                .1
+                .split_once(' ')
+                .unwrap()
+                .1,
+        )
+        .unwrap();
+        assert_eq!(member_event["displayname"], "F: Annalea");
+        assert_eq!(member_event["membership"], "join");
+        assert_eq!(member_event["avatar_url"], "mxc://example.org/avatar");
+    }
+
+    #[tokio::test]
+    async fn test_matrix_setup_fails_with_invalid_token() {
+        let (url, _) = start_mock_matrix_server().await;
+        let config = create_test_config(&url, "wrong_token", MatrixDisplayNameScope::Unchanged);
+        let mut updater = MatrixUpdater::new();
+
+        assert!(updater.setup(&config).await.is_err());
+        assert!(
+            updater
+                .last_operation_error
+                .as_ref()
+                .unwrap()
+                .contains("401 Unauthorized")
+        );
+
+        assert!(
+            updater
+                .update_fronting_status(&config, &[fronter("Annalea")])
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_matrix_setup_fails_for_private_homeserver() {
+        let (url, received) = start_mock_matrix_server().await;
+        let mut config = create_test_config(&url, MOCK_TOKEN, MatrixDisplayNameScope::Unchanged);
+        config.allow_private_network_urls = false;
+        let mut updater = MatrixUpdater::new();
+
+        assert!(updater.setup(&config).await.is_err());
+        assert!(
+            updater
+                .last_operation_error
+                .unwrap()
+                .contains("is not a public address")
+        );
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_matrix_api_url_encodes_segments() {
+        let config = create_test_config(
+            "https://matrix.example.org/",
+            MOCK_TOKEN,
+            MatrixDisplayNameScope::Unchanged,
+        );
+
+        assert_eq!(
+            matrix_api_url(&config, &["profile", "@a/b:example.org", "displayname"])
+                .unwrap()
+                .as_str(),
+            "https://matrix.example.org/_matrix/client/v3/profile/@a%2Fb:example.org/displayname"
+        );
+    }
+}
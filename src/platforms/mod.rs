@@ -1,16 +1,35 @@
+mod bluesky;
 mod discord;
 pub mod discord_api;
 mod discord_status_message;
+mod discord_webhook;
+mod from_pluralkit;
+mod mastodon;
+mod matrix;
+mod mqtt;
+pub mod pluralkit_api;
+mod pluralkit_system_sync;
+mod public_url;
 mod to_pluralkit;
 mod updater_macro;
 mod vrchat;
 pub mod vrchat_api;
 mod vrchat_auth;
 mod vrchat_auth_types;
+mod webhook;
 pub mod webview_api;
 
+pub use bluesky::*;
 pub use discord::*;
 pub use discord_status_message::*;
+pub use discord_webhook::*;
+pub use from_pluralkit::*;
+pub use mastodon::*;
+pub use matrix::*;
+pub use mqtt::*;
+pub use pluralkit_system_sync::*;
+pub use public_url::*;
 pub use to_pluralkit::*;
 pub use vrchat::*;
 pub use vrchat_auth_types::*;
+pub use webhook::*;
@@ -0,0 +1,487 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::{
+    platforms::{self, WebhookFronter},
+    plurality, record_if_error, users,
+};
+use anyhow::{Result, anyhow};
+use rumqttc::{Event, Outgoing, Packet, QoS};
+use serde::Serialize;
+
+/// Home Assistant doesn't accept longer sensor states.
+const MQTT_STATUS_MAX_LENGTH: usize = 255;
+
+const MQTT_KEEP_ALIVE: Duration = Duration::from_secs(30);
+const MQTT_ACKNOWLEDGEMENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A retained message. Subscribers, which connect later, receive the last one of each topic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RetainedMessage {
+    topic: String,
+    payload: String,
+}
+
+#[derive(Serialize, Debug)]
+struct HomeAssistantDevice {
+    identifiers: Vec<String>,
+    name: &'static str,
+    manufacturer: &'static str,
+}
+
+/// See <https://www.home-assistant.io/integrations/sensor.mqtt/>
+#[derive(Serialize, Debug)]
+struct HomeAssistantSensorConfig {
+    name: &'static str,
+    unique_id: String,
+    state_topic: String,
+    icon: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_class: Option<&'static str>,
+    device: HomeAssistantDevice,
+}
+
+pub struct MqttUpdater {
+    pub last_operation_error: Option<String>,
+    pub last_payload: Option<String>,
+}
+
+impl Default for MqttUpdater {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MqttUpdater {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            last_operation_error: None,
+            last_payload: None,
+        }
+    }
+
+    /// Checks the connection to the broker and publishes the Home Assistant discovery config, if enabled.
+    pub async fn setup(&mut self, config: &users::UserConfigForUpdater) -> Result<()> {
+        let discovery_messages = if config.mqtt_home_assistant_discovery {
+            record_if_error!(self, home_assistant_discovery_messages(config))?
+        } else {
+            vec![]
+        };
+        record_if_error!(self, publish_retained(config, discovery_messages).await)?;
+        log::info!(
+            "# | mqtt setup | {} | connected to broker (home assistant discovery: {})",
+            config.user_id,
+            config.mqtt_home_assistant_discovery
+        );
+        Ok(())
+    }
+
+    pub async fn update_fronting_status(
+        &mut self,
+        config: &users::UserConfigForUpdater,
+        fronts: &[plurality::Fronter],
+    ) -> Result<()> {
+        let messages = record_if_error!(self, fronting_messages(config, fronts))?;
+        self.last_payload = messages
+            .iter()
+            .find(|message| message.topic == config.mqtt_status_topic)
+            .map(|message| message.payload.clone());
+
+        record_if_error!(self, publish_retained(config, messages).await)?;
+        log::info!(
+            "# | mqtt update_fronting_status | {} | published {} fronter(s)",
+            config.user_id,
+            fronts.len()
+        );
+        Ok(())
+    }
+}
+
+fn fronting_messages(
+    config: &users::UserConfigForUpdater,
+    fronts: &[plurality::Fronter],
+) -> Result<Vec<RetainedMessage>> {
+    let fronters: Vec<WebhookFronter> = fronts.iter().map(WebhookFronter::from).collect();
+
+    let mut fronting_format =
+        plurality::generic_fronting_format(config, plurality::DisplayNamePlatform::Mqtt);
+    fronting_format.max_length = Some(MQTT_STATUS_MAX_LENGTH);
+    fronting_format.length_metric = plurality::LengthMetric::UnicodeScalars;
+
+    Ok(vec![
+        RetainedMessage {
+            topic: config.mqtt_fronters_topic.clone(),
+            payload: serde_json::to_string(&fronters)?,
+        },
+        RetainedMessage {
+            topic: config.mqtt_status_topic.clone(),
+            payload: plurality::format_fronting_status(&fronting_format, fronts),
+        },
+        RetainedMessage {
+            topic: config.mqtt_fronter_count_topic.clone(),
+            payload: fronts.len().to_string(),
+        },
+    ])
+}
+
+/// Lets Home Assistant create sensors for the status and the fronter count without any configuration there.
+fn home_assistant_discovery_messages(
+    config: &users::UserConfigForUpdater,
+) -> Result<Vec<RetainedMessage>> {
+    let node_id = format!("pluralsync_{}", config.user_id.inner.simple());
+    let device = || HomeAssistantDevice {
+        identifiers: vec![node_id.clone()],
+        name: "PluralSync",
+        manufacturer: "PluralSync",
+    };
+
+    let sensors = [
+        (
+            "current_fronters",
+            HomeAssistantSensorConfig {
+                name: "Current fronters",
+                unique_id: format!("{node_id}_current_fronters"),
+                state_topic: config.mqtt_status_topic.clone(),
+                icon: "mdi:account-group",
+                state_class: None,
+                device: device(),
+            },
+        ),
+        (
+            "fronter_count",
+            HomeAssistantSensorConfig {
+                name: "Fronter count",
+                unique_id: format!("{node_id}_fronter_count"),
+                state_topic: config.mqtt_fronter_count_topic.clone(),
+                icon: "mdi:counter",
+                state_class: Some("measurement"),
+                device: device(),
+            },
+        ),
+    ];
+
+    sensors
+        .iter()
+        .map(|(object_id, sensor_config)| {
+            Ok(RetainedMessage {
+                topic: format!(
+                    "{}/sensor/{node_id}/{object_id}/config",
+                    config.mqtt_home_assistant_discovery_prefix
+                ),
+                payload: serde_json::to_string(sensor_config)?,
+            })
+        })
+        .collect()
+}
+
+async fn mqtt_options(config: &users::UserConfigForUpdater) -> Result<rumqttc::MqttOptions> {
+    let broker_url = reqwest::Url::parse(&config.mqtt_broker_url)?;
+    let host = broker_url
+        .host_str()
+        .ok_or_else(|| anyhow!("mqtt_options: mqtt_broker_url has no host"))?;
+    let use_tls = broker_url.scheme() == "mqtts";
+    let port = broker_url
+        .port()
+        .unwrap_or(if use_tls { 8883 } else { 1883 });
+
+    // rumqttc resolves the host again when connecting. Hence, the checked address is connected to instead.
+    // With TLS, the host name is kept for the certificate, which a private address can't present for it.
+    let broker_host = if config.allow_private_network_urls {
+        host.to_owned()
+    } else {
+        let addresses =
+            platforms::public_socket_addresses(config, &config.mqtt_broker_url, port).await?;
+        match addresses.first() {
+            _ if use_tls => host.to_owned(),
+            Some(SocketAddr::V6(address)) => format!("[{}]", address.ip()),
+            Some(address) => address.ip().to_string(),
+            None => return Err(anyhow!("mqtt_options: mqtt_broker_url has no address")),
+        }
+    };
+
+    let client_id = format!("pluralsync-{}", config.user_id.inner.simple());
+    let mut options = rumqttc::MqttOptions::new(client_id, broker_host, port);
+    options.set_keep_alive(MQTT_KEEP_ALIVE);
+    if let Some(username) = &config.mqtt_username {
+        let password = config
+            .mqtt_password
+            .as_ref()
+            .map(|password| password.secret.clone())
+            .unwrap_or_default();
+        options.set_credentials(username, password);
+    }
+    if use_tls {
+        options.set_transport(rumqttc::Transport::tls_with_default_config());
+    }
+    Ok(options)
+}
+
+/// Connects to the broker, publishes the messages and disconnects once the broker acknowledged all of them.
+/// Fronting changes are rare, hence no connection is kept open in between.
+async fn publish_retained(
+    config: &users::UserConfigForUpdater,
+    messages: Vec<RetainedMessage>,
+) -> Result<()> {
+    let message_count = messages.len();
+    let (client, mut event_loop) =
+        rumqttc::AsyncClient::new(mqtt_options(config).await?, message_count + 1);
+
+    for message in messages {
+        client
+            .publish(message.topic, QoS::AtLeastOnce, true, message.payload)
+            .await?;
+    }
+
+    let acknowledged = async {
+        let mut connected = false;
+        let mut acknowledged_count = 0;
+        while !connected || acknowledged_count < message_count {
+            match event_loop.poll().await? {
+                Event::Incoming(Packet::ConnAck(_)) => connected = true,
+                Event::Incoming(Packet::PubAck(_)) => acknowledged_count += 1,
+                _ => (),
+            }
+        }
+
+        client.disconnect().await?;
+        while !matches!(
+            event_loop.poll().await?,
+            Event::Outgoing(Outgoing::Disconnect)
+        ) {}
+        Ok::<(), anyhow::Error>(())
+    };
+
+    tokio::time::timeout(MQTT_ACKNOWLEDGEMENT_TIMEOUT, acknowledged)
+        .await
+        .map_err(|_| {
+            anyhow!(
+                "publish_retained: broker didn't acknowledge the messages within {}s",
+                MQTT_ACKNOWLEDGEMENT_TIMEOUT.as_secs()
+            )
+        })?
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::database::Decrypted;
+    use crate::test_support::{self, fronter};
+    use crate::users::UserConfigDbEntries;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    const MOCK_USERNAME: &str = "pluralsync";
+    const MOCK_PASSWORD: &str = "mqtt_password_123";
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct ReceivedPublish {
+        topic: String,
+        payload: String,
+        retain: bool,
+    }
+
+    async fn read_packet(stream: &mut tokio::net::TcpStream) -> Option<(u8, Vec<u8>)> {
+        let header = stream.read_u8().await.ok()?;
+        let mut remaining_length = 0usize;
+        for shift in (0..4).map(|i| i * 7) {
+            let byte = stream.read_u8().await.ok()?;
+            remaining_length |= usize::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        let mut body = vec![0u8; remaining_length];
+        stream.read_exact(&mut body).await.ok()?;
+        Some((header, body))
+    }
+
+    /// Minimal stand-in for a broker like mosquitto. Speaks just enough MQTT 3.1.1 for QoS 1 publishes.
+    async fn start_mock_broker() -> (String, Arc<Mutex<Vec<ReceivedPublish>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let received_by_broker = received.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                while let Some((header, body)) = read_packet(&mut stream).await {
+                    match header >> 4 {
+                        // CONNECT: the credentials are at the end of the payload
+                        1 => {
+                            let credentials = String::from_utf8_lossy(&body);
+                            let return_code = if credentials.contains(MOCK_USERNAME)
+                                && credentials.ends_with(MOCK_PASSWORD)
+                            {
+                                0x00
+                            } else {
+                                0x05 // not authorized
+                            };
+                            let _ = stream.write_all(&[0x20, 0x02, 0x00, return_code]).await;
+                        }
+                        // PUBLISH with QoS 1: topic, packet id, payload
+                        3 => {
+                            let topic_length = usize::from(u16::from_be_bytes([body[0], body[1]]));
+                            let topic = String::from_utf8_lossy(&body[2..2 + topic_length]);
+                            let packet_id = &body[2 + topic_length..4 + topic_length];
+                            let payload = String::from_utf8_lossy(&body[4 + topic_length..]);
+                            received_by_broker.lock().unwrap().push(ReceivedPublish {
+                                topic: topic.into_owned(),
+                                payload: payload.into_owned(),
+                                retain: header & 0x01 == 1,
+                            });
+                            let _ = stream
+                                .write_all(&[0x40, 0x02, packet_id[0], packet_id[1]])
+                                .await;
+                        }
+                        // PINGREQ
+                        12 => {
+                            let _ = stream.write_all(&[0xd0, 0x00]).await;
+                        }
+                        // DISCONNECT
+                        _ => break,
+                    }
+                }
+            }
+        });
+
+        (format!("mqtt://{address}"), received)
+    }
+
+    fn create_test_config(
+        broker_url: &str,
+        password: &str,
+        home_assistant_discovery: bool,
+    ) -> users::UserConfigForUpdater {
+        test_support::create_test_config(&UserConfigDbEntries {
+            enable_mqtt: true,
+            mqtt_broker_url: Some(broker_url.to_owned()),
+            mqtt_username: Some(MOCK_USERNAME.to_owned()),
+            mqtt_home_assistant_discovery: home_assistant_discovery,
+            mqtt_password: Some(Decrypted {
+                secret: password.to_owned(),
+            }),
+            ..test_support::db_config()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_mqtt_publishes_retained_fronters_status_and_count() {
+        let (url, received) = start_mock_broker().await;
+        let config = create_test_config(&url, MOCK_PASSWORD, false);
+        let mut updater = MqttUpdater::new();
+
+        updater.setup(&config).await.unwrap();
+        updater
+            .update_fronting_status(&config, &[fronter("Annalea"), fronter("Borgn")])
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received.len(), 3);
+        assert!(received.iter().all(|publish| publish.retain));
+
+        assert_eq!(received[0].topic, "pluralsync/fronters");
+        let fronters: serde_json::Value = serde_json::from_str(&received[0].payload).unwrap();
+        assert_eq!(fronters[0]["id"], "annalea");
+        assert_eq!(fronters[1]["name"], "Borgn");
+
+        assert_eq!(
+            received[1],
+            ReceivedPublish {
+                topic: "pluralsync/status".to_owned(),
+                payload: "F: Annalea, Borgn".to_owned(),
+                retain: true,
+            }
+        );
+        assert_eq!(
+            received[2],
+            ReceivedPublish {
+                topic: "pluralsync/fronter_count".to_owned(),
+                payload: "2".to_owned(),
+                retain: true,
+            }
+        );
+
+        assert_eq!(updater.last_payload.as_deref(), Some("F: Annalea, Borgn"));
+        assert_eq!(updater.last_operation_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_mqtt_publishes_home_assistant_discovery_on_setup() {
+        let (url, received) = start_mock_broker().await;
+        let config = create_test_config(&url, MOCK_PASSWORD, true);
+        let mut updater = MqttUpdater::new();
+
+        updater.setup(&config).await.unwrap();
+
+        let received = received.lock().unwrap().clone();
+        let node_id = format!("pluralsync_{}", config.user_id.inner.simple());
+        assert_eq!(
+            received
+                .iter()
+                .map(|publish| publish.topic.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                format!("homeassistant/sensor/{node_id}/current_fronters/config"),
+                format!("homeassistant/sensor/{node_id}/fronter_count/config"),
+            ]
+        );
+        assert!(received.iter().all(|publish| publish.retain));
+
+        let sensor_config: serde_json::Value = serde_json::from_str(&received[0].payload).unwrap();
+        assert_eq!(sensor_config["state_topic"], "pluralsync/status");
+        assert_eq!(
+            sensor_config["unique_id"],
+            format!("{node_id}_current_fronters")
+        );
+        assert_eq!(sensor_config["device"]["identifiers"][0], node_id);
+    }
+
+    #[tokio::test]
+    async fn test_mqtt_setup_fails_with_invalid_password() {
+        let (url, received) = start_mock_broker().await;
+        let config = create_test_config(&url, "wrong_password", false);
+        let mut updater = MqttUpdater::new();
+
+        assert!(updater.setup(&config).await.is_err());
+        assert!(updater.last_operation_error.is_some());
+        assert!(
+            updater
+                .update_fronting_status(&config, &[fronter("Annalea")])
+                .await
+                .is_err()
+        );
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mqtt_setup_fails_for_private_broker() {
+        let (url, received) = start_mock_broker().await;
+        let mut config = create_test_config(&url, MOCK_PASSWORD, true);
+        config.allow_private_network_urls = false;
+        let mut updater = MqttUpdater::new();
+
+        assert!(updater.setup(&config).await.is_err());
+        assert!(
+            updater
+                .last_operation_error
+                .unwrap()
+                .contains("is not a public address")
+        );
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mqtt_status_is_limited_for_home_assistant() {
+        let config = create_test_config("mqtt://localhost", MOCK_PASSWORD, false);
+        let fronts: Vec<_> = (0..40)
+            .map(|i| fronter(&format!("Fronter number {i}")))
+            .collect();
+
+        let messages = fronting_messages(&config, &fronts).unwrap();
+        assert!(messages[1].payload.chars().count() <= MQTT_STATUS_MAX_LENGTH);
+        assert_eq!(messages[2].payload, "40");
+    }
+}
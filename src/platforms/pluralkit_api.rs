@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::database;
+use crate::meta_api::{HttpResult, expose_internal_error};
+use crate::platforms::{PluralKitSystemSyncPlan, pluralkit_system_sync};
+use crate::{plurality, updater, users};
+use rocket::http;
+use rocket::{State, serde::json::Json};
+use sqlx::PgPool;
+
+const DISPATCH_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+const DISPATCH_REQUESTS_PER_USER: u32 = 60;
+/// `PluralKit` sends the events of all users from the same few addresses.
+const DISPATCH_REQUESTS_PER_ADDRESS: u32 = 1200;
+const DISPATCH_RATE_LIMIT_MAX_TRACKED: usize = 10_000;
+
+/// The dispatch endpoint is reachable without a login. Hence, its requests are limited per user and per address.
+#[derive(Default)]
+pub struct PluralKitDispatchRateLimit {
+    // requests in the current window by "user:<id>" or "address:<ip>"
+    requests: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl PluralKitDispatchRateLimit {
+    pub fn allow_request(&self, user_id: &str, client_address: Option<IpAddr>) -> bool {
+        let Ok(mut requests) = self.requests.lock() else {
+            return false;
+        };
+
+        if requests.len() > DISPATCH_RATE_LIMIT_MAX_TRACKED {
+            requests
+                .retain(|_, (window_start, _)| window_start.elapsed() < DISPATCH_RATE_LIMIT_WINDOW);
+        }
+
+        let address = client_address.map_or_else(|| "unknown".to_owned(), |a| a.to_string());
+        let mut allow = |key: String, limit: u32| {
+            let (window_start, count) = requests.entry(key).or_insert((Instant::now(), 0));
+            if window_start.elapsed() >= DISPATCH_RATE_LIMIT_WINDOW {
+                *window_start = Instant::now();
+                *count = 0;
+            }
+            *count += 1;
+            *count <= limit
+        };
+        let user_allowed = allow(format!("user:{user_id}"), DISPATCH_REQUESTS_PER_USER);
+        let address_allowed = allow(format!("address:{address}"), DISPATCH_REQUESTS_PER_ADDRESS);
+        user_allowed && address_allowed
+    }
+}
+
+#[get("/api/user/platform/pluralkit/system_sync/preview")]
+pub async fn get_api_user_platform_pluralkit_system_sync_preview(
+    jwt: users::Jwt,
+    db_pool: &State<PgPool>,
+    client: &State<reqwest::Client>,
+    application_user_secrets: &State<database::ApplicationUserSecrets>,
+    shared_updaters: &State<updater::UpdaterManager>,
+) -> HttpResult<Json<PluralKitSystemSyncPlan>> {
+    let user_id = jwt.user_id().map_err(expose_internal_error)?;
+
+    log::info!("# | GET /api/user/platform/pluralkit/system_sync/preview | {user_id}");
+
+    let config = system_sync_config(
+        db_pool,
+        &user_id,
+        client,
+        application_user_secrets,
+        shared_updaters,
+    )
+    .await?;
+
+    let plan = pluralkit_system_sync::preview_pluralkit_system_sync(&config)
+        .await
+        .map_err(expose_internal_error)?;
+
+    log::info!(
+        "# | GET /api/user/platform/pluralkit/system_sync/preview | {user_id} | {} changes",
+        plan.changes.len()
+    );
+
+    Ok(Json(plan))
+}
+
+#[post("/api/user/platform/pluralkit/system_sync/apply?<plan_version>")]
+pub async fn post_api_user_platform_pluralkit_system_sync_apply(
+    plan_version: &str,
+    jwt: users::Jwt,
+    db_pool: &State<PgPool>,
+    client: &State<reqwest::Client>,
+    application_user_secrets: &State<database::ApplicationUserSecrets>,
+    shared_updaters: &State<updater::UpdaterManager>,
+) -> HttpResult<Json<PluralKitSystemSyncPlan>> {
+    let user_id = jwt.user_id().map_err(expose_internal_error)?;
+
+    log::info!("# | POST /api/user/platform/pluralkit/system_sync/apply | {user_id}");
+
+    let config = system_sync_config(
+        db_pool,
+        &user_id,
+        client,
+        application_user_secrets,
+        shared_updaters,
+    )
+    .await?;
+
+    let plan = pluralkit_system_sync::preview_pluralkit_system_sync(&config)
+        .await
+        .map_err(expose_internal_error)?;
+
+    if plan.version != plan_version {
+        log::info!(
+            "# | POST /api/user/platform/pluralkit/system_sync/apply | {user_id} | plan changed since preview"
+        );
+        return Err((
+            http::Status::Conflict,
+            "The members changed since the preview. Please preview the system sync again."
+                .to_owned(),
+        ));
+    }
+
+    let plan = pluralkit_system_sync::apply_pluralkit_system_sync(&config, plan)
+        .await
+        .map_err(expose_internal_error)?;
+
+    log::info!(
+        "# | POST /api/user/platform/pluralkit/system_sync/apply | {user_id} | {} changes applied",
+        plan.changes.len()
+    );
+
+    Ok(Json(plan))
+}
+
+/// Receives the events of the `PluralKit` dispatch webhook, which the user registers via `pk;system webhook <url>`.
+#[post("/api/pluralkit/dispatch/<user_id>", data = "<event>")]
+pub async fn post_api_pluralkit_dispatch(
+    user_id: &str,
+    client_address: Option<IpAddr>,
+    event: Json<plurality::PluralKitDispatchEvent>,
+    db_pool: &State<PgPool>,
+    application_user_secrets: &State<database::ApplicationUserSecrets>,
+    shared_updaters: &State<updater::UpdaterManager>,
+    rate_limit: &State<PluralKitDispatchRateLimit>,
+) -> HttpResult<()> {
+    log::info!(
+        "# | POST /api/pluralkit/dispatch/{user_id} | {}",
+        event.event_type
+    );
+
+    if !rate_limit.allow_request(user_id, client_address) {
+        log::warn!("# | POST /api/pluralkit/dispatch/{user_id} | rate limited");
+        return Err((
+            http::Status::TooManyRequests,
+            "Too many PluralKit dispatch requests.".to_owned(),
+        ));
+    }
+
+    // PluralKit expects 401 for events which aren't signed with the token of the user
+    let unauthorized = || {
+        (
+            http::Status::Unauthorized,
+            "Invalid PluralKit dispatch signing token.".to_owned(),
+        )
+    };
+
+    let user_id = users::UserId::try_from(user_id).map_err(|_| unauthorized())?;
+    if event.signing_token.is_empty() {
+        return Err(unauthorized());
+    }
+
+    let is_signed = database::is_pluralkit_dispatch_signing_token(
+        db_pool,
+        &user_id,
+        application_user_secrets,
+        &event.signing_token,
+    )
+    .await
+    .map_err(|e| {
+        log::warn!("# | POST /api/pluralkit/dispatch/{user_id} | checking token failed: {e}");
+        unauthorized()
+    })?;
+    if !is_signed {
+        return Err(unauthorized());
+    }
+
+    if event.might_change_fronters() {
+        shared_updaters
+            .notify_pluralkit_dispatch(&user_id)
+            .map_err(expose_internal_error)?;
+    }
+
+    log::info!(
+        "# | POST /api/pluralkit/dispatch/{user_id} | {} | ok",
+        event.event_type
+    );
+
+    Ok(())
+}
+
+async fn system_sync_config(
+    db_pool: &PgPool,
+    user_id: &users::UserId,
+    client: &reqwest::Client,
+    application_user_secrets: &database::ApplicationUserSecrets,
+    shared_updaters: &updater::UpdaterManager,
+) -> HttpResult<users::UserConfigForUpdater> {
+    let config = database::get_user_config_with_secrets(
+        db_pool,
+        user_id,
+        client,
+        &shared_updaters.simply_plural_endpoints,
+        application_user_secrets,
+    )
+    .await
+    .map_err(expose_internal_error)?;
+
+    if !config.enable_pluralkit_system_sync {
+        return Err((
+            http::Status::BadRequest,
+            "The PluralKit system sync is not enabled in the saved config.".to_owned(),
+        ));
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatch_rate_limit_per_user_and_address() {
+        let rate_limit = PluralKitDispatchRateLimit::default();
+        let address: Option<IpAddr> = Some("203.0.113.7".parse().unwrap());
+
+        for _ in 0..DISPATCH_REQUESTS_PER_USER {
+            assert!(rate_limit.allow_request("user-1", address));
+        }
+        assert!(!rate_limit.allow_request("user-1", address));
+        assert!(rate_limit.allow_request("user-2", address));
+
+        for i in 0..DISPATCH_REQUESTS_PER_ADDRESS {
+            rate_limit.allow_request(&format!("user-{i}"), None);
+        }
+        assert!(!rate_limit.allow_request("user-3", None));
+        assert!(rate_limit.allow_request("user-3", address));
+    }
+}
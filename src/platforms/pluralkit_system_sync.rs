@@ -0,0 +1,653 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::plurality::{self, Member, PluralKitMember};
+use crate::users;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+// Limits of the PluralKit member model: https://pluralkit.me/api/models/#member-model
+const PLURALKIT_NAME_MAX_LENGTH: usize = 100;
+const PLURALKIT_PRONOUNS_MAX_LENGTH: usize = 100;
+const PLURALKIT_DESCRIPTION_MAX_LENGTH: usize = 1000;
+const PLURALKIT_AVATAR_URL_MAX_LENGTH: usize = 256;
+
+// PluralKit only allows a few write requests per second
+const PLURALKIT_WRITE_INTERVAL: Duration = Duration::from_millis(400);
+
+/// The member fields which are synced from Simply Plural. Serialized as the field names of the `PluralKit` API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluralKitMemberField {
+    Name,
+    AvatarUrl,
+    Pronouns,
+    Color,
+    Description,
+}
+
+impl PluralKitMemberField {
+    #[must_use]
+    pub const fn api_name(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::AvatarUrl => "avatar_url",
+            Self::Pronouns => "pronouns",
+            Self::Color => "color",
+            Self::Description => "description",
+        }
+    }
+
+    fn value_of(self, member: &PluralKitMember) -> Option<&str> {
+        match self {
+            Self::Name => Some(&member.name),
+            Self::AvatarUrl => member.avatar_url.as_deref(),
+            Self::Pronouns => member.pronouns.as_deref(),
+            Self::Color => member.color.as_deref(),
+            Self::Description => member.description.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PluralKitMemberFieldChange {
+    pub field: PluralKitMemberField,
+    pub old_value: Option<String>,
+    pub new_value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PluralKitMemberSyncAction {
+    Create,
+    Update,
+}
+
+/// What happens to a single Simply Plural member during the system sync.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PluralKitMemberSyncChange {
+    pub simply_plural_member_id: String,
+    pub name: String,
+    pub action: PluralKitMemberSyncAction,
+    /// None, while the `PluralKit` member is yet to be created
+    pub pluralkit_id: Option<String>,
+    /// Whether the `PluralKit` ID is written into the `pkId` of the Simply Plural member
+    pub link_pluralkit_id: bool,
+    pub field_changes: Vec<PluralKitMemberFieldChange>,
+}
+
+/// The dry-run diff of the system sync. Members which are already up to date are only counted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PluralKitSystemSyncPlan {
+    pub changes: Vec<PluralKitMemberSyncChange>,
+    pub unchanged_members_count: usize,
+    /// Hash of the changes. Applying requires the version of the previewed plan, such that only reviewed changes are made.
+    pub version: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct CreatedPluralKitMember {
+    id: String,
+}
+
+/// Computes what the system sync would change without changing anything.
+pub async fn preview_pluralkit_system_sync(
+    config: &users::UserConfigForUpdater,
+) -> Result<PluralKitSystemSyncPlan> {
+    log::info!("# | preview_pluralkit_system_sync | {}", config.user_id);
+
+    let simply_plural_members = plurality::fetch_members_by_privacy_rules(config).await?;
+    let pluralkit_members = plurality::pluralkit_http_get_members(config).await?;
+
+    let plan = plan_pluralkit_system_sync(&simply_plural_members, &pluralkit_members);
+
+    log::info!(
+        "# | preview_pluralkit_system_sync | {} | {} changes, {} unchanged",
+        config.user_id,
+        plan.changes.len(),
+        plan.unchanged_members_count
+    );
+
+    Ok(plan)
+}
+
+/// Creates and updates the `PluralKit` members and links them in Simply Plural.
+///
+/// The plan should be freshly previewed and match the version the user reviewed.
+/// Each member is linked right after it was created, hence an interrupted sync can simply be started again.
+pub async fn apply_pluralkit_system_sync(
+    config: &users::UserConfigForUpdater,
+    mut plan: PluralKitSystemSyncPlan,
+) -> Result<PluralKitSystemSyncPlan> {
+    for change in &mut plan.changes {
+        let pluralkit_id = match change.action {
+            PluralKitMemberSyncAction::Create => {
+                pluralkit_http_create_member(config, &change.field_changes).await?
+            }
+            PluralKitMemberSyncAction::Update => {
+                let pluralkit_id = change
+                    .pluralkit_id
+                    .clone()
+                    .ok_or_else(|| anyhow!("Member to update has no PluralKit ID"))?;
+                if !change.field_changes.is_empty() {
+                    pluralkit_http_patch_member(config, &pluralkit_id, &change.field_changes)
+                        .await?;
+                }
+                pluralkit_id
+            }
+        };
+
+        if change.link_pluralkit_id {
+            plurality::simply_plural_http_patch_member_pluralkit_id(
+                config,
+                &change.simply_plural_member_id,
+                &pluralkit_id,
+            )
+            .await?;
+        }
+
+        log::info!(
+            "# | apply_pluralkit_system_sync | {} | {:?} {} -> {pluralkit_id}",
+            config.user_id,
+            change.action,
+            change.simply_plural_member_id
+        );
+
+        change.pluralkit_id = Some(pluralkit_id);
+
+        tokio::time::sleep(PLURALKIT_WRITE_INTERVAL).await;
+    }
+
+    Ok(plan)
+}
+
+/// Matches the Simply Plural members to the `PluralKit` members and computes the necessary changes.
+///
+/// Members are matched by their `pkId` and otherwise by their name.
+/// Empty values in Simply Plural don't clear the values in `PluralKit`.
+fn plan_pluralkit_system_sync(
+    simply_plural_members: &[Member],
+    pluralkit_members: &[PluralKitMember],
+) -> PluralKitSystemSyncPlan {
+    let find_by_id = |pluralkit_id: &str| {
+        pluralkit_members
+            .iter()
+            .find(|pk| pk.id.eq_ignore_ascii_case(pluralkit_id))
+    };
+
+    // members which are already linked must not be matched by name to another member
+    let mut claimed_pluralkit_ids: HashSet<&str> = simply_plural_members
+        .iter()
+        .filter_map(|m| m.content.pluralkit_id.as_deref())
+        .filter_map(find_by_id)
+        .map(|pk| pk.id.as_str())
+        .collect();
+
+    let mut plan = PluralKitSystemSyncPlan::default();
+
+    for member in simply_plural_members {
+        let linked = member.content.pluralkit_id.as_deref().and_then(find_by_id);
+        let existing = linked.or_else(|| {
+            pluralkit_members
+                .iter()
+                .filter(|pk| !claimed_pluralkit_ids.contains(pk.id.as_str()))
+                .find(|pk| pk.name == member.content.name)
+        });
+
+        let desired_fields = desired_pluralkit_fields(member);
+
+        let Some(existing) = existing else {
+            plan.changes.push(PluralKitMemberSyncChange {
+                simply_plural_member_id: member.member_id.clone(),
+                name: member.content.name.clone(),
+                action: PluralKitMemberSyncAction::Create,
+                pluralkit_id: None,
+                link_pluralkit_id: true,
+                field_changes: desired_fields
+                    .into_iter()
+                    .map(|(field, new_value)| PluralKitMemberFieldChange {
+                        field,
+                        old_value: None,
+                        new_value,
+                    })
+                    .collect(),
+            });
+            continue;
+        };
+
+        claimed_pluralkit_ids.insert(existing.id.as_str());
+
+        let field_changes: Vec<PluralKitMemberFieldChange> = desired_fields
+            .into_iter()
+            .filter(|(field, new_value)| field.value_of(existing) != Some(new_value.as_str()))
+            .map(|(field, new_value)| PluralKitMemberFieldChange {
+                field,
+                old_value: field.value_of(existing).map(str::to_owned),
+                new_value,
+            })
+            .collect();
+
+        // also corrects the pkId, if it only matched case-insensitively
+        let link_pluralkit_id = member.content.pluralkit_id.as_deref() != Some(&existing.id);
+
+        if field_changes.is_empty() && !link_pluralkit_id {
+            plan.unchanged_members_count += 1;
+            continue;
+        }
+
+        plan.changes.push(PluralKitMemberSyncChange {
+            simply_plural_member_id: member.member_id.clone(),
+            name: member.content.name.clone(),
+            action: PluralKitMemberSyncAction::Update,
+            pluralkit_id: Some(existing.id.clone()),
+            link_pluralkit_id,
+            field_changes,
+        });
+    }
+
+    plan.version = plan_version(&plan.changes, plan.unchanged_members_count);
+
+    plan
+}
+
+fn plan_version(changes: &[PluralKitMemberSyncChange], unchanged_members_count: usize) -> String {
+    // serializing these plain structs doesn't fail
+    let serialized = serde_json::to_vec(&(changes, unchanged_members_count)).unwrap_or_default();
+    let digest = Sha256::digest(serialized);
+    format!("{digest:x}")
+}
+
+/// The values of the Simply Plural member as accepted by `PluralKit`. Empty and invalid values are left out.
+fn desired_pluralkit_fields(member: &Member) -> Vec<(PluralKitMemberField, String)> {
+    let content = &member.content;
+    let non_empty = |value: Option<&String>, max_length: usize| {
+        value
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .map(|v| v.chars().take(max_length).collect::<String>())
+    };
+
+    [
+        (
+            PluralKitMemberField::Name,
+            non_empty(Some(&content.name), PLURALKIT_NAME_MAX_LENGTH),
+        ),
+        (
+            PluralKitMemberField::AvatarUrl,
+            Some(content.avatar_url.trim().to_owned()).filter(|url| {
+                url.starts_with("https://") && url.len() <= PLURALKIT_AVATAR_URL_MAX_LENGTH
+            }),
+        ),
+        (
+            PluralKitMemberField::Pronouns,
+            non_empty(content.pronouns.as_ref(), PLURALKIT_PRONOUNS_MAX_LENGTH),
+        ),
+        (
+            PluralKitMemberField::Color,
+            content.color.as_deref().and_then(pluralkit_color),
+        ),
+        (
+            PluralKitMemberField::Description,
+            non_empty(
+                content.description.as_ref(),
+                PLURALKIT_DESCRIPTION_MAX_LENGTH,
+            ),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(field, value)| value.map(|v| (field, v)))
+    .collect()
+}
+
+/// Simply Plural colors are CSS-like ("#RRGGBB", sometimes with alpha). `PluralKit` wants "rrggbb".
+fn pluralkit_color(simply_plural_color: &str) -> Option<String> {
+    let hex = simply_plural_color.trim().trim_start_matches('#');
+    let valid = matches!(hex.len(), 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit());
+    valid.then(|| hex[..6].to_ascii_lowercase())
+}
+
+fn field_changes_as_json(
+    field_changes: &[PluralKitMemberFieldChange],
+) -> serde_json::Map<String, serde_json::Value> {
+    field_changes
+        .iter()
+        .map(|c| (c.field.api_name().to_owned(), c.new_value.clone().into()))
+        .collect()
+}
+
+async fn pluralkit_http_create_member(
+    config: &users::UserConfigForUpdater,
+    field_changes: &[PluralKitMemberFieldChange],
+) -> Result<String> {
+    let mut body = field_changes_as_json(field_changes);
+    // PluralKit members are public by default. Created members start private, so that nothing is exposed before the user reviewed them in PluralKit.
+    body.insert(
+        "privacy".to_owned(),
+        serde_json::json!({ "visibility": "private" }),
+    );
+
+    let response = config
+        .client
+        .post(format!("{}/members", config.pluralkit_base_url))
+        .header("Authorization", &config.pluralkit_token.secret)
+        .header("User-Agent", plurality::PLURALKIT_USER_AGENT)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let created: CreatedPluralKitMember = serde_json::from_str(&response).inspect_err(|e| {
+        log::warn!(
+            "# | pluralkit_http_create_member | {} | {} | input: {}",
+            config.user_id,
+            e,
+            response.chars().take(500).collect::<String>()
+        );
+    })?;
+
+    Ok(created.id)
+}
+
+async fn pluralkit_http_patch_member(
+    config: &users::UserConfigForUpdater,
+    pluralkit_id: &str,
+    field_changes: &[PluralKitMemberFieldChange],
+) -> Result<()> {
+    config
+        .client
+        .patch(format!(
+            "{}/members/{pluralkit_id}",
+            config.pluralkit_base_url
+        ))
+        .header("Authorization", &config.pluralkit_token.secret)
+        .header("User-Agent", plurality::PLURALKIT_USER_AGENT)
+        .json(&field_changes_as_json(field_changes))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::database::Decrypted;
+    use crate::plurality::MemberContent;
+    use crate::test_support::{self, MOCK_SIMPLY_PLURAL_TOKEN, ReceivedRequests};
+    use crate::users::UserConfigDbEntries;
+    use std::collections::HashMap;
+
+    const MOCK_PK_TOKEN: &str = "pk_token_123";
+
+    /// Minimal stand-in for both the Simply Plural and the `PluralKit` API.
+    async fn start_mock_server(
+        simply_plural_members: serde_json::Value,
+        pluralkit_members: serde_json::Value,
+    ) -> (String, ReceivedRequests) {
+        test_support::start_mock_http_server(move |request| {
+            let token = if request.path.starts_with("/sp/") {
+                MOCK_SIMPLY_PLURAL_TOKEN
+            } else {
+                MOCK_PK_TOKEN
+            };
+            match (request.method.as_str(), request.path.as_str()) {
+                _ if request.header("authorization") != Some(token) => {
+                    ("401 Unauthorized", String::from("{}"))
+                }
+                ("GET", "/sp/me") => (
+                    "200 OK",
+                    r#"{"id": "system_123", "exists": true, "content": {}}"#.to_owned(),
+                ),
+                ("GET", "/sp/members/system_123") => ("200 OK", simply_plural_members.to_string()),
+                ("PATCH", p) if p.starts_with("/sp/member/") => ("200 OK", String::new()),
+                ("GET", "/pk/systems/@me/members") => ("200 OK", pluralkit_members.to_string()),
+                ("POST", "/pk/members") => ("200 OK", r#"{"id": "newpk"}"#.to_owned()),
+                ("PATCH", p) if p.starts_with("/pk/members/") => ("200 OK", String::from("{}")),
+                _ => ("404 Not Found", String::from("{}")),
+            }
+        })
+        .await
+    }
+
+    /// The write requests with their JSON bodies.
+    fn sent_writes(received: &ReceivedRequests) -> Vec<(String, serde_json::Value)> {
+        received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.method != "GET")
+            .map(|request| (request.request_line(), request.json()))
+            .collect()
+    }
+
+    fn create_test_config(base_url: &str) -> users::UserConfigForUpdater {
+        let mut config = test_support::create_test_config_with_endpoints(
+            &UserConfigDbEntries {
+                pluralkit_token: Some(Decrypted {
+                    secret: MOCK_PK_TOKEN.to_owned(),
+                }),
+                show_members_non_archived: true,
+                enable_pluralkit_system_sync: true,
+                ..test_support::db_config()
+            },
+            &plurality::SimplyPluralEndpoints {
+                base_url: format!("{base_url}/sp"),
+                websocket_url: String::new(),
+            },
+        );
+        config.pluralkit_base_url = format!("{base_url}/pk");
+        config
+    }
+
+    fn sp_member(member_id: &str, name: &str, pluralkit_id: Option<&str>) -> Member {
+        Member {
+            member_id: member_id.to_owned(),
+            content: MemberContent {
+                name: name.to_owned(),
+                avatar_url: String::new(),
+                info: serde_json::Value::Null,
+                archived: false,
+                front_notifications_disabled: false,
+                privacy_buckets: vec![],
+                display_name_field_ids: HashMap::new(),
+                pluralkit_id: pluralkit_id.map(str::to_owned),
+                pronouns: None,
+                color: None,
+                description: None,
+            },
+        }
+    }
+
+    fn pk_member(id: &str, name: &str) -> PluralKitMember {
+        serde_json::from_value(serde_json::json!({"id": id, "name": name})).unwrap()
+    }
+
+    #[test]
+    fn test_plan_creates_missing_members_and_updates_changed_fields() {
+        let mut annalea = sp_member("sp_a", "Annalea", Some("aaaaa"));
+        annalea.content.pronouns = Some("she/her".to_owned());
+        annalea.content.color = Some("#FF8800".to_owned());
+        let borgn = sp_member("sp_b", "Borgn", None);
+        let cassia = sp_member("sp_c", "Cassia", Some("bbbbb"));
+
+        let mut pk_annalea = pk_member("aaaaa", "Annalea");
+        pk_annalea.pronouns = Some("they/them".to_owned());
+        let plan = plan_pluralkit_system_sync(
+            &[annalea, borgn, cassia],
+            &[pk_annalea, pk_member("bbbbb", "Cassia")],
+        );
+
+        assert_eq!(plan.unchanged_members_count, 1);
+        assert_eq!(
+            plan.changes,
+            vec![
+                PluralKitMemberSyncChange {
+                    simply_plural_member_id: "sp_a".to_owned(),
+                    name: "Annalea".to_owned(),
+                    action: PluralKitMemberSyncAction::Update,
+                    pluralkit_id: Some("aaaaa".to_owned()),
+                    link_pluralkit_id: false,
+                    field_changes: vec![
+                        PluralKitMemberFieldChange {
+                            field: PluralKitMemberField::Pronouns,
+                            old_value: Some("they/them".to_owned()),
+                            new_value: "she/her".to_owned(),
+                        },
+                        PluralKitMemberFieldChange {
+                            field: PluralKitMemberField::Color,
+                            old_value: None,
+                            new_value: "ff8800".to_owned(),
+                        },
+                    ],
+                },
+                PluralKitMemberSyncChange {
+                    simply_plural_member_id: "sp_b".to_owned(),
+                    name: "Borgn".to_owned(),
+                    action: PluralKitMemberSyncAction::Create,
+                    pluralkit_id: None,
+                    link_pluralkit_id: true,
+                    field_changes: vec![PluralKitMemberFieldChange {
+                        field: PluralKitMemberField::Name,
+                        old_value: None,
+                        new_value: "Borgn".to_owned(),
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_links_members_by_name_unless_already_linked() {
+        let plan = plan_pluralkit_system_sync(
+            &[
+                sp_member("sp_a1", "Annalea", Some("aaaaa")),
+                sp_member("sp_a2", "Annalea", None),
+                sp_member("sp_b", "Borgn", Some("stale")),
+            ],
+            &[pk_member("aaaaa", "Annalea"), pk_member("bbbbb", "Borgn")],
+        );
+
+        assert_eq!(plan.unchanged_members_count, 1);
+        let actions: Vec<_> = plan
+            .changes
+            .iter()
+            .map(|c| {
+                (
+                    c.simply_plural_member_id.as_str(),
+                    c.action,
+                    c.pluralkit_id.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("sp_a2", PluralKitMemberSyncAction::Create, None),
+                ("sp_b", PluralKitMemberSyncAction::Update, Some("bbbbb")),
+            ]
+        );
+        assert!(plan.changes.iter().all(|c| c.link_pluralkit_id));
+        assert!(plan.changes[1].field_changes.is_empty());
+    }
+
+    #[test]
+    fn test_plan_version_changes_with_the_plan() {
+        let members = [sp_member("sp_a", "Annalea", None)];
+
+        let plan = plan_pluralkit_system_sync(&members, &[]);
+        let same_plan = plan_pluralkit_system_sync(&members, &[]);
+        let linked_plan = plan_pluralkit_system_sync(&members, &[pk_member("aaaaa", "Annalea")]);
+
+        assert_eq!(plan.version.len(), 64);
+        assert_eq!(plan.version, same_plan.version);
+        assert_ne!(plan.version, linked_plan.version);
+    }
+
+    #[test]
+    fn test_desired_fields_respect_pluralkit_limits() {
+        let mut member = sp_member("sp_a", &"N".repeat(150), None);
+        member.content.avatar_url = "http://insecure.example/a.png".to_owned();
+        member.content.pronouns = Some("  ".to_owned());
+        member.content.description = Some("D".repeat(1500));
+        member.content.color = Some("#12345".to_owned());
+
+        let fields = desired_pluralkit_fields(&member);
+
+        assert_eq!(
+            fields.iter().map(|(f, _)| *f).collect::<Vec<_>>(),
+            vec![
+                PluralKitMemberField::Name,
+                PluralKitMemberField::Description
+            ]
+        );
+        assert_eq!(fields[0].1.chars().count(), PLURALKIT_NAME_MAX_LENGTH);
+        assert_eq!(
+            fields[1].1.chars().count(),
+            PLURALKIT_DESCRIPTION_MAX_LENGTH
+        );
+    }
+
+    #[test]
+    fn test_pluralkit_color() {
+        assert_eq!(pluralkit_color("#A1B2C3"), Some("a1b2c3".to_owned()));
+        assert_eq!(pluralkit_color("a1b2c3ff"), Some("a1b2c3".to_owned()));
+        assert_eq!(pluralkit_color(""), None);
+        assert_eq!(pluralkit_color("#xyzxyz"), None);
+    }
+
+    #[tokio::test]
+    async fn test_apply_creates_updates_and_links_members() {
+        let (url, received) = start_mock_server(
+            serde_json::json!([
+                {"id": "sp_a", "content": {"name": "Annalea", "pkId": "aaaaa", "pronouns": "she/her"}},
+                {"id": "sp_b", "content": {"name": "Borgn", "desc": "Likes tea", "pkId": ""}},
+                {"id": "sp_c", "content": {"name": "Archived", "archived": true}},
+            ]),
+            serde_json::json!([{"id": "aaaaa", "name": "Annalea"}]),
+        )
+        .await;
+        let config = create_test_config(&url);
+
+        let preview = preview_pluralkit_system_sync(&config).await.unwrap();
+        assert!(sent_writes(&received).is_empty());
+
+        let applied = apply_pluralkit_system_sync(&config, preview.clone())
+            .await
+            .unwrap();
+        assert_eq!(applied.changes.len(), preview.changes.len());
+        assert_eq!(applied.changes[1].pluralkit_id.as_deref(), Some("newpk"));
+
+        assert_eq!(
+            sent_writes(&received),
+            vec![
+                (
+                    "PATCH /pk/members/aaaaa".to_owned(),
+                    serde_json::json!({"pronouns": "she/her"})
+                ),
+                (
+                    "POST /pk/members".to_owned(),
+                    serde_json::json!({"name": "Borgn", "description": "Likes tea", "privacy": {"visibility": "private"}})
+                ),
+                (
+                    "PATCH /sp/member/sp_b".to_owned(),
+                    serde_json::json!({"pkId": "newpk"})
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preview_fails_with_invalid_pluralkit_token() {
+        let (url, _) = start_mock_server(serde_json::json!([]), serde_json::json!([])).await;
+        let mut config = create_test_config(&url);
+        config.pluralkit_token.secret = "wrong_token".to_owned();
+
+        let result = preview_pluralkit_system_sync(&config).await;
+
+        assert!(result.unwrap_err().to_string().contains("401 Unauthorized"));
+    }
+}
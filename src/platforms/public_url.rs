@@ -0,0 +1,144 @@
+use std::net::{IpAddr, SocketAddr};
+
+use anyhow::{Result, anyhow};
+use reqwest::Url;
+
+use crate::{setup, users};
+
+/// Rejects URLs whose host is or resolves to a loopback, private or link-local address.
+/// Otherwise, returns the client to reach the URL with.
+///
+/// Users configure these URLs themselves. Hence, they must not reach into the network of the server,
+/// e.g. the database or the metadata service of the cloud provider.
+/// The client connects only to the checked addresses and doesn't follow redirects.
+/// Otherwise, a second DNS lookup or a redirect could still lead into a private network.
+pub async fn public_url_client(
+    config: &users::UserConfigForUpdater,
+    url: &str,
+) -> Result<reqwest::Client> {
+    if config.allow_private_network_urls {
+        return setup::make_user_url_client(None);
+    }
+
+    let addresses = public_socket_addresses(config, url, 443).await?;
+    let parsed = Url::parse(url)?;
+    let host = parsed.host_str().unwrap_or_default();
+
+    setup::make_user_url_client(Some((host, addresses.as_slice())))
+}
+
+/// The addresses of the host of the URL. Fails, if any of them isn't public.
+///
+/// The `default_port` is used for schemes without a well-known port, e.g. `mqtt`.
+/// Connections must only be made to the returned addresses, as a second DNS lookup may resolve differently.
+pub async fn public_socket_addresses(
+    config: &users::UserConfigForUpdater,
+    url: &str,
+    default_port: u16,
+) -> Result<Vec<SocketAddr>> {
+    let parsed = Url::parse(url)?;
+    let port = parsed.port_or_known_default().unwrap_or(default_port);
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| anyhow!("The URL '{url}' has no host."))?;
+
+    // IPv6 addresses are enclosed in brackets within URLs
+    let addresses: Vec<SocketAddr> =
+        match host.trim_start_matches('[').trim_end_matches(']').parse() {
+            Ok(ip) => vec![SocketAddr::new(ip, port)],
+            Err(_) => tokio::net::lookup_host((host, port)).await?.collect(),
+        };
+
+    if addresses.is_empty() || !addresses.iter().all(|a| is_public_address(a.ip())) {
+        log::warn!(
+            "# | public_socket_addresses | {} | rejected {url} -> {addresses:?}",
+            config.user_id
+        );
+        return Err(anyhow!(
+            "The host of '{url}' is not a public address. Local and private networks cannot be reached."
+        ));
+    }
+
+    Ok(addresses)
+}
+
+fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            // 100.64.0.0/10 is shared between the customers of a carrier
+            let carrier_grade_nat = first == 100 && (second & 0b1100_0000) == 64;
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || carrier_grade_nat)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ipv4) = ip.to_ipv4_mapped() {
+                return is_public_address(ipv4.into());
+            }
+            let first_segment = ip.segments()[0];
+            let unique_local = (first_segment & 0xfe00) == 0xfc00;
+            let link_local = (first_segment & 0xffc0) == 0xfe80;
+            !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn test_is_public_address() {
+        for public in ["1.1.1.1", "100.128.0.1", "2606:4700:4700::1111"] {
+            assert!(is_public_address(public.parse().unwrap()), "{public}");
+        }
+        for private in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_address(private.parse().unwrap()), "{private}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_public_url_client_does_not_follow_redirects() {
+        let (base_url, received) = test_support::start_mock_http_server(|_| {
+            ("302 Found\r\nLocation: /redirected", String::new())
+        })
+        .await;
+        let config = test_support::create_test_config(&test_support::db_config());
+
+        let client = public_url_client(&config, &base_url).await.unwrap();
+        let response = client.get(&base_url).send().await.unwrap();
+
+        assert_eq!(response.status(), reqwest::StatusCode::FOUND);
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_public_url_client_rejects_host_names_of_private_addresses() {
+        let mut config = test_support::create_test_config(&test_support::db_config());
+        config.allow_private_network_urls = false;
+
+        let result = public_url_client(&config, "http://localhost:8080/hook").await;
+
+        assert!(result.is_err());
+    }
+}
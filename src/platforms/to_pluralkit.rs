@@ -1,9 +1,11 @@
 use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 
+use crate::platforms::{FromPluralKitState, sync_switches_from_pluralkit};
 use crate::{
     int_counter_metric, metric, plurality, record_if_error, users, users::UserConfigForUpdater,
 };
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 
 int_counter_metric!(PLURALKIT_API_REQUESTS_TOTAL);
@@ -14,15 +16,38 @@ metric!(
     &["user_id", "scope"]
 );
 
-const TO_PLURALKIT_UPDATER_USER_AGENT: &str = concat!(
-    "PluralSync/",
-    env!("CARGO_PKG_VERSION"),
-    " Discord: ",
-    env!("USER_AGENT_DISCORD_USERNAME")
-);
+/// The order of the members in the switches created in `PluralKit`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    sqlx::Type,
+    specta::Type,
+    strum_macros::Display,
+)]
+#[specta(export)]
+#[sqlx(type_name = "pluralkit_switch_order_enum")]
+pub enum PluralKitSwitchOrder {
+    /// Fronters who keep fronting stay in their order in `PluralKit`. New fronters are appended.
+    #[default]
+    PreservePluralKitOrder,
+    /// The order of the fronters as shown on the other platforms
+    SimplyPluralOrder,
+    StartTimeNewestFirst,
+    StartTimeOldestFirst,
+    /// The configured member first, followed by the others as in `PreservePluralKitOrder`
+    PrimaryMemberFirst,
+}
 
 pub struct ToPluralKitUpdater {
     pub last_operation_error: Option<String>,
+    pub last_payload: Option<String>,
+    from_pluralkit: Arc<Mutex<FromPluralKitState>>,
 }
 
 impl Default for ToPluralKitUpdater {
@@ -33,15 +58,23 @@ impl Default for ToPluralKitUpdater {
 
 impl ToPluralKitUpdater {
     #[must_use]
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             last_operation_error: None,
+            last_payload: None,
+            from_pluralkit: Arc::new(Mutex::new(FromPluralKitState::default())),
         }
     }
 
     #[allow(clippy::unused_async)]
-    pub async fn setup(&mut self, _config: &users::UserConfigForUpdater) -> Result<()> {
-        // Nothing to do here for now
+    pub async fn setup(&mut self, config: &users::UserConfigForUpdater) -> Result<()> {
+        if config.enable_from_pluralkit {
+            // the task holds only a weak reference and hence stops, once this updater is dropped
+            tokio::spawn(sync_switches_from_pluralkit(
+                config.clone(),
+                Arc::downgrade(&self.from_pluralkit),
+            ));
+        }
         Ok(())
     }
 
@@ -50,25 +83,60 @@ impl ToPluralKitUpdater {
         config: &users::UserConfigForUpdater,
         fronts: &[plurality::Fronter],
     ) -> Result<()> {
-        record_if_error!(self, update_to_pluralkit(config, fronts).await)
+        let pluralkit_ids: Vec<&str> = fronts
+            .iter()
+            .filter_map(|f| f.pluralkit_id.as_deref())
+            .collect();
+        self.last_payload = Some(pluralkit_ids.join(", "));
+        let result = update_to_pluralkit(config, fronts, &self.from_pluralkit)
+            .await
+            .and_then(|()| self.last_from_pluralkit_error());
+        record_if_error!(self, result)
+    }
+
+    /// The switches from `PluralKit` are synced in the background. Their errors are surfaced here.
+    fn last_from_pluralkit_error(&self) -> Result<()> {
+        let last_error = self
+            .from_pluralkit
+            .lock()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .last_error
+            .clone();
+        match last_error {
+            Some(e) => Err(anyhow!("Syncing from PluralKit failed: {e}")),
+            None => Ok(()),
+        }
     }
 }
 
 async fn update_to_pluralkit(
     config: &UserConfigForUpdater,
     fronts: &[plurality::Fronter],
+    from_pluralkit: &Mutex<FromPluralKitState>,
 ) -> Result<()> {
-    let new_members: Vec<String> = fronts
-        .iter()
-        .filter_map(|f| f.pluralkit_id.clone())
-        .collect();
+    let new_members = pluralkit_ids(fronts);
+
+    let synced_from_pluralkit = from_pluralkit
+        .lock()
+        .map_err(|e| anyhow!(e.to_string()))?
+        .is_synced_from_pluralkit(&new_members);
+    if synced_from_pluralkit {
+        log::info!(
+            "update_to_pluralkit | {} | The fronters were synced from PluralKit. Not pushing them back.",
+            config.user_id
+        );
+        return Ok(());
+    }
 
     let response = config
         .client
-        .get("https://api.pluralkit.me/v2/systems/@me/switches?limit=1")
+        .get(format!(
+            "{}/systems/@me/switches?limit=1",
+            config.pluralkit_base_url
+        ))
         .header("Authorization", &config.pluralkit_token.secret)
         .header("Content-Type", "application/json")
-        .header("User-Agent", TO_PLURALKIT_UPDATER_USER_AGENT)
+        .header("User-Agent", plurality::PLURALKIT_USER_AGENT)
         .send()
         .await?
         .error_for_status()?
@@ -101,14 +169,20 @@ async fn update_to_pluralkit(
         new_members
     );
 
-    let new_switch_members = customization_preserving_members_list_for_new_switch(
-        &new_members,
+    let new_switch_members = ordered_members_for_new_switch(
+        config.pluralkit_switch_order,
+        config.pluralkit_switch_primary_member.as_deref(),
+        fronts,
         &existing_fronting_members,
     );
 
-    if same_members(&new_switch_members, &existing_fronting_members) {
+    if same_switch(
+        config.pluralkit_switch_order,
+        &new_switch_members,
+        &existing_fronting_members,
+    ) {
         log::info!(
-            "update_to_pluralkit | {} | No change will be propagated to PluralKit due to lists containing the same members (pk-order preservation).",
+            "update_to_pluralkit | {} | No change will be propagated to PluralKit due to the switch being unchanged.",
             config.user_id
         );
         return Ok(());
@@ -120,10 +194,13 @@ async fn update_to_pluralkit(
 
     let response = config
         .client
-        .post("https://api.pluralkit.me/v2/systems/@me/switches")
+        .post(format!(
+            "{}/systems/@me/switches",
+            config.pluralkit_base_url
+        ))
         .header("Authorization", &config.pluralkit_token.secret)
         .header("Content-Type", "application/json")
-        .header("User-Agent", TO_PLURALKIT_UPDATER_USER_AGENT)
+        .header("User-Agent", plurality::PLURALKIT_USER_AGENT)
         .json(&PluralKitSwitch {
             members: new_switch_members.clone(),
         })
@@ -132,7 +209,22 @@ async fn update_to_pluralkit(
 
     measure_rate_limits(config, &response);
 
-    response.error_for_status()?;
+    let response = response.error_for_status()?.text().await?;
+
+    // remembered, such that the switch isn't synced back into Simply Plural
+    let created_switch: plurality::PluralKitSwitchEntry = serde_json::from_str(&response)
+        .inspect_err(|e| {
+            log::warn!(
+                "# | update_to_pluralkit | {} | {} | input: {}",
+                config.user_id,
+                e,
+                response.chars().take(500).collect::<String>()
+            );
+        })?;
+    from_pluralkit
+        .lock()
+        .map_err(|e| anyhow!(e.to_string()))?
+        .record_own_switch(created_switch.id);
 
     log::info!(
         "update_to_pluralkit | {} | Updated PluralKit to {:?}",
@@ -143,14 +235,81 @@ async fn update_to_pluralkit(
     Ok(())
 }
 
-fn same_members(new_switch_members: &[String], existing_members: &[String]) -> bool {
+pub fn same_members(new_switch_members: &[String], existing_members: &[String]) -> bool {
     let new_set: HashSet<_> = new_switch_members.iter().collect();
     let existing_set: HashSet<_> = existing_members.iter().collect();
     new_set == existing_set
 }
 
-// todo. add configurations values for these things here
-// For now we'll simply add the new members at the end (in the same order as from caller) and preserve the order of the old members
+/// The order only matters, if PluralSync chooses it. A reordering in `PluralKit` itself is preserved otherwise.
+fn same_switch(
+    order: PluralKitSwitchOrder,
+    new_switch_members: &[String],
+    existing_members: &[String],
+) -> bool {
+    match order {
+        PluralKitSwitchOrder::PreservePluralKitOrder => {
+            same_members(new_switch_members, existing_members)
+        }
+        _ => new_switch_members == existing_members,
+    }
+}
+
+fn pluralkit_ids(fronts: &[plurality::Fronter]) -> Vec<String> {
+    fronts
+        .iter()
+        .filter_map(|f| f.pluralkit_id.clone())
+        .collect()
+}
+
+/// `PluralKit` uses the order of the switch for proxying and autoproxy. Hence the user chooses it.
+fn ordered_members_for_new_switch(
+    order: PluralKitSwitchOrder,
+    primary_member: Option<&str>,
+    fronts: &[plurality::Fronter],
+    existing_members: &[String],
+) -> Vec<String> {
+    match order {
+        PluralKitSwitchOrder::PreservePluralKitOrder => {
+            customization_preserving_members_list_for_new_switch(
+                &pluralkit_ids(fronts),
+                existing_members,
+            )
+        }
+        PluralKitSwitchOrder::SimplyPluralOrder => pluralkit_ids(fronts),
+        PluralKitSwitchOrder::StartTimeNewestFirst | PluralKitSwitchOrder::StartTimeOldestFirst => {
+            let fronter_order = if order == PluralKitSwitchOrder::StartTimeNewestFirst {
+                plurality::FronterOrder::StartTimeNewestFirst
+            } else {
+                plurality::FronterOrder::StartTimeOldestFirst
+            };
+            let mut sorted_fronts = fronts.to_vec();
+            plurality::sort_fronters(fronter_order, &[], &mut sorted_fronts);
+            pluralkit_ids(&sorted_fronts)
+        }
+        PluralKitSwitchOrder::PrimaryMemberFirst => {
+            let mut members = customization_preserving_members_list_for_new_switch(
+                &pluralkit_ids(fronts),
+                existing_members,
+            );
+            // the primary member can be configured with its Simply Plural or PluralKit ID
+            let primary_position = primary_member
+                .and_then(|primary| {
+                    fronts.iter().find(|f| {
+                        f.fronter_id == primary || f.pluralkit_id.as_deref() == Some(primary)
+                    })
+                })
+                .and_then(|f| f.pluralkit_id.as_ref())
+                .and_then(|pluralkit_id| members.iter().position(|m| m == pluralkit_id));
+            if let Some(position) = primary_position {
+                members[..=position].rotate_right(1);
+            }
+            members
+        }
+    }
+}
+
+/// Adds the new members at the end (in the same order as from caller) and preserves the order of the old members
 fn customization_preserving_members_list_for_new_switch(
     new_members: &[String],
     existing_members: &[String],
@@ -206,7 +365,95 @@ struct PluralKitSwitch {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
+    use crate::database::Decrypted;
+    use crate::platforms::sync_switch_into_simply_plural;
+    use crate::test_support;
+    use crate::users::UserConfigDbEntries;
+
+    fn fronter(id: &str, start_time_secs: i64) -> plurality::Fronter {
+        plurality::Fronter {
+            fronter_id: format!("sp-{id}"),
+            name: id.to_owned(),
+            avatar_url: String::new(),
+            display_names: HashMap::new(),
+            pluralkit_id: Some(id.to_owned()),
+            pronouns: None,
+            custom_status: None,
+            start_time: chrono::DateTime::from_timestamp(start_time_secs, 0),
+            privacy_buckets: vec![],
+            groups: vec![],
+            is_custom_front: false,
+        }
+    }
+
+    fn ordered_ids(order: PluralKitSwitchOrder, primary_member: Option<&str>) -> Vec<String> {
+        // fronts in simply plural order. "d" isn't fronting anymore.
+        let fronts = vec![fronter("a", 200), fronter("b", 300), fronter("c", 100)];
+        let existing_members = vec!["d".to_string(), "c".to_string(), "a".to_string()];
+        ordered_members_for_new_switch(order, primary_member, &fronts, &existing_members)
+    }
+
+    #[test]
+    fn test_ordered_members_for_new_switch() {
+        assert_eq!(
+            ordered_ids(PluralKitSwitchOrder::PreservePluralKitOrder, None),
+            vec!["c", "a", "b"]
+        );
+        assert_eq!(
+            ordered_ids(PluralKitSwitchOrder::SimplyPluralOrder, None),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(
+            ordered_ids(PluralKitSwitchOrder::StartTimeNewestFirst, None),
+            vec!["b", "a", "c"]
+        );
+        assert_eq!(
+            ordered_ids(PluralKitSwitchOrder::StartTimeOldestFirst, None),
+            vec!["c", "a", "b"]
+        );
+        assert_eq!(
+            ordered_ids(PluralKitSwitchOrder::PrimaryMemberFirst, Some("b")),
+            vec!["b", "c", "a"]
+        );
+        assert_eq!(
+            ordered_ids(PluralKitSwitchOrder::PrimaryMemberFirst, Some("sp-a")),
+            vec!["a", "c", "b"]
+        );
+        // a primary member who isn't fronting changes nothing
+        assert_eq!(
+            ordered_ids(PluralKitSwitchOrder::PrimaryMemberFirst, Some("d")),
+            vec!["c", "a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_same_switch_compares_order_unless_preserving_pluralkit_order() {
+        let existing_members = vec!["c".to_string(), "a".to_string(), "b".to_string()];
+        let reordered = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+
+        assert!(same_switch(
+            PluralKitSwitchOrder::PreservePluralKitOrder,
+            &reordered,
+            &existing_members
+        ));
+        for order in [
+            PluralKitSwitchOrder::SimplyPluralOrder,
+            PluralKitSwitchOrder::StartTimeNewestFirst,
+            PluralKitSwitchOrder::StartTimeOldestFirst,
+            PluralKitSwitchOrder::PrimaryMemberFirst,
+        ] {
+            assert!(!same_switch(order, &reordered, &existing_members));
+            assert!(same_switch(order, &existing_members, &existing_members));
+        }
+        assert!(!same_switch(
+            PluralKitSwitchOrder::PreservePluralKitOrder,
+            &reordered[..2],
+            &existing_members
+        ));
+    }
 
     #[test]
     fn test_customization_preserving_members_list_for_new_switch_simple() {
@@ -258,4 +505,118 @@ mod tests {
             vec!["b".to_string(), "a".to_string(), "c".to_string()]
         );
     }
+
+    /// Simply Plural with Annalea fronting and `PluralKit` with a new switch to Borgn and a member unknown to Simply Plural
+    async fn start_mock_systems() -> (users::UserConfigForUpdater, test_support::ReceivedRequests) {
+        let simply_plural_url = test_support::start_mock_simply_plural(serde_json::json!({
+            "system_id": "system_123",
+            "members": [
+                {"id": "sp_a", "content": {"name": "Annalea", "pkId": "aaaaa"}},
+                {"id": "sp_b", "content": {"name": "Borgn", "pkId": "bbbbb"}},
+            ],
+            "customFronts": [],
+            "customFields": [],
+            "friend": {},
+            "frontHistory": [
+                {"id": "fe_a", "content": {"member": "sp_a", "uid": "system_123", "startTime": 1_000, "live": true}},
+            ],
+        }))
+        .await;
+        let (pluralkit_url, received) = test_support::start_mock_http_server(|request| {
+            let switch = |id: &str| {
+                serde_json::json!({
+                    "id": id,
+                    "timestamp": "2026-01-01T00:00:00Z",
+                    "members": ["bbbbb", "zzzzz"],
+                })
+            };
+            match request.method.as_str() {
+                "GET" => ("200 OK", serde_json::json!([switch("sw1")]).to_string()),
+                _ => ("200 OK", switch("sw2").to_string()),
+            }
+        })
+        .await;
+
+        let mut config = test_support::create_test_config_with_endpoints(
+            &UserConfigDbEntries {
+                pluralkit_token: Some(Decrypted {
+                    secret: "pk_token_123".to_owned(),
+                }),
+                enable_to_pluralkit: true,
+                show_members_non_archived: true,
+                ..test_support::db_config()
+            },
+            &plurality::SimplyPluralEndpoints {
+                base_url: simply_plural_url,
+                websocket_url: String::new(),
+            },
+        );
+        config.pluralkit_base_url = pluralkit_url;
+        (config, received)
+    }
+
+    async fn fetch_simply_plural_fronts(
+        config: &users::UserConfigForUpdater,
+    ) -> Vec<plurality::Fronter> {
+        plurality::fetch_fronts(
+            config,
+            &Mutex::new(plurality::SimplyPluralSystemCache::new()),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn switches_created(received: &test_support::ReceivedRequests) -> Vec<serde_json::Value> {
+        received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.request_line() == "POST /systems/@me/switches")
+            .map(test_support::MockRequest::json)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_switch_synced_from_pluralkit_is_not_pushed_back() {
+        let (config, received) = start_mock_systems().await;
+        let mut updater = ToPluralKitUpdater::new();
+        let switch = plurality::PluralKitSwitchEntry {
+            id: "sw1".to_owned(),
+            timestamp: chrono::DateTime::from_timestamp_millis(9_000).unwrap(),
+            members: vec!["bbbbb".to_owned(), "zzzzz".to_owned()],
+        };
+
+        // simply plural shows annalea and borgn in between
+        let fronts_in_between = vec![fronter("aaaaa", 1), fronter("bbbbb", 9)];
+        sync_switch_into_simply_plural(&config, &updater.from_pluralkit, &switch)
+            .await
+            .unwrap();
+        updater
+            .update_fronting_status(&config, &fronts_in_between)
+            .await
+            .unwrap();
+        let fronts = fetch_simply_plural_fronts(&config).await;
+        updater
+            .update_fronting_status(&config, &fronts)
+            .await
+            .unwrap();
+
+        // borgn and the member unknown to simply plural stay in pluralkit
+        assert_eq!(
+            fronts.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+            vec!["Borgn"]
+        );
+        assert_eq!(switches_created(&received), Vec::<serde_json::Value>::new());
+
+        // changes in simply plural are pushed again
+        let fronts = vec![fronter("aaaaa", 10)];
+        updater
+            .update_fronting_status(&config, &fronts)
+            .await
+            .unwrap();
+        assert_eq!(
+            switches_created(&received),
+            vec![serde_json::json!({"members": ["aaaaa"]})]
+        );
+    }
 }
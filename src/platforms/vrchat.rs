@@ -20,6 +20,7 @@ type InitializedUpdater = (
 );
 pub struct VRChatUpdater {
     pub last_operation_error: Option<String>,
+    pub last_payload: Option<String>,
     initialized: Option<InitializedUpdater>,
 }
 impl Default for VRChatUpdater {
@@ -33,6 +34,7 @@ impl VRChatUpdater {
     pub const fn new() -> Self {
         Self {
             last_operation_error: None,
+            last_payload: None,
             initialized: None,
         }
     }
@@ -71,29 +73,38 @@ impl VRChatUpdater {
                 .as_ref()
                 .ok_or_else(|| anyhow!("update_fronting_status: Updater not initalized!"))
         );
+        let status_string = format_vrchat_status(config, fronts);
+        self.last_payload = Some(status_string.clone());
         record_if_error!(
             self,
-            update_to_vrchat(config, initialized_updater?, fronts).await
+            set_vrchat_status(initialized_updater?, &config.user_id, &status_string).await
         )
     }
 }
 
-async fn update_to_vrchat(
+fn format_vrchat_status(
     config: &users::UserConfigForUpdater,
-    initialized_updater: &InitializedUpdater,
     fronts: &[plurality::Fronter],
-) -> Result<()> {
-    let fronting_format = plurality::FrontingFormat {
-        max_length: Some(plurality::VRCHAT_MAX_ALLOWED_STATUS_LENGTH),
-        cleaning: plurality::CleanForPlatform::VRChat,
-        prefix: config.status_prefix.clone(),
-        status_if_no_fronters: config.status_no_fronts.clone(),
-        truncate_names_to_length_if_status_too_long: config.status_truncate_names_to,
-    };
-
-    let status_string = plurality::format_fronting_status(&fronting_format, fronts);
+) -> String {
+    let fronting_format = plurality::fronting_format_with_overrides(
+        config,
+        plurality::PlatformOverrides {
+            max_length: Some(plurality::VRCHAT_MAX_ALLOWED_STATUS_LENGTH),
+            length_metric: Some(plurality::LengthMetric::Utf16CodeUnits),
+            cleaning: Some(plurality::CleanForPlatform::VRChat(
+                config.vrchat_unsupported_characters,
+            )),
+            prefix: config.vrchat_status_prefix.clone(),
+            status_if_no_fronters: config.vrchat_status_no_fronts.clone(),
+            truncate_names_to: config.vrchat_status_truncate_names_to,
+            show_custom_status: config.vrchat_show_custom_status,
+            group_collapsing: config.vrchat_status_group_collapsing,
+            display_name_platform: Some(plurality::DisplayNamePlatform::VRChat),
+            status_templates: config.vrchat_status_templates.clone(),
+        },
+    );
 
-    set_vrchat_status(initialized_updater, &config.user_id, status_string.as_str()).await
+    plurality::format_fronting_status(&fronting_format, fronts)
 }
 
 async fn set_vrchat_status(
@@ -0,0 +1,500 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::{platforms, plurality, updater, users};
+use anyhow::{Result, anyhow};
+use hmac::{Hmac, Mac};
+use pluralsync_base::clock;
+use pluralsync_base::communication::{self, FireAndForgetChannel, LatestReceiver};
+use serde::Serialize;
+use sha2::Sha256;
+
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-PluralSync-Signature-256";
+const WEBHOOK_MAX_ATTEMPTS: u32 = 3;
+const WEBHOOK_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// A fronter as it's sent to the webhook.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct WebhookFronter {
+    pub id: String,
+    pub name: String,
+    pub avatar_url: Option<String>,
+    pub pronouns: Option<String>,
+    pub start_time: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<&plurality::Fronter> for WebhookFronter {
+    fn from(fronter: &plurality::Fronter) -> Self {
+        Self {
+            id: fronter.fronter_id.clone(),
+            name: fronter.name.clone(),
+            avatar_url: Some(fronter.avatar_url.clone()).filter(|url| !url.is_empty()),
+            pronouns: fronter.pronouns.clone(),
+            start_time: fronter.start_time,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    event: &'static str,
+    sent_at: chrono::DateTime<chrono::Utc>,
+    status: String,
+    fronters: &'a [WebhookFronter],
+    /// None, if the previous fronters are unknown, e.g. directly after a restart.
+    previous_fronters: Option<&'a [WebhookFronter]>,
+}
+
+/// What is delivered to the webhook. The previous fronters are added by the delivery.
+#[derive(Debug, Clone)]
+struct WebhookDelivery {
+    status: String,
+    fronters: Vec<WebhookFronter>,
+}
+
+pub struct WebhookUpdater {
+    pub last_operation_error: Option<String>,
+    pub last_payload: Option<String>,
+    deliveries: Option<FireAndForgetChannel<WebhookDelivery>>,
+    /// Outcome of the most recent delivery, as the webhooks are delivered in the background.
+    last_delivery_error: Arc<Mutex<Option<String>>>,
+}
+
+impl Default for WebhookUpdater {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebhookUpdater {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            last_operation_error: None,
+            last_payload: None,
+            deliveries: None,
+            last_delivery_error: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    #[allow(clippy::unused_async)]
+    pub async fn setup(&mut self, config: &users::UserConfigForUpdater) -> Result<()> {
+        let deliveries = communication::fire_and_forget_channel();
+
+        // the delivery task ends by itself, once the updater and with it the channel is dropped.
+        // it retries failed deliveries without blocking the updates of the other platforms.
+        tokio::spawn(deliver_webhooks(
+            config.clone(),
+            deliveries.subscribe(),
+            self.last_delivery_error.clone(),
+        ));
+
+        self.deliveries = Some(deliveries);
+        Ok(())
+    }
+
+    pub async fn update_fronting_status(
+        &mut self,
+        config: &users::UserConfigForUpdater,
+        fronts: &[plurality::Fronter],
+    ) -> Result<()> {
+        // errors of the background delivery show up here, such that they're part of the updater status
+        self.last_operation_error = self
+            .last_delivery_error
+            .lock()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .clone();
+
+        let deliveries = self
+            .deliveries
+            .as_mut()
+            .ok_or_else(|| anyhow!("update_fronting_status: Webhook updater not initialized!"))?;
+
+        let delivery = WebhookDelivery {
+            status: plurality::format_fronting_status(
+                &plurality::generic_fronting_format(
+                    config,
+                    plurality::DisplayNamePlatform::Webhook,
+                ),
+                fronts,
+            ),
+            fronters: fronts.iter().map(WebhookFronter::from).collect(),
+        };
+
+        // the delivery decides on the previous fronters. this is the payload, if the previous delivery succeeds.
+        let previous = deliveries.most_recent_sent_value();
+        self.last_payload = Some(webhook_body(
+            &delivery,
+            previous.as_ref().map(|p| p.fronters.as_slice()),
+        )?);
+
+        deliveries.send(delivery);
+        Ok(())
+    }
+}
+
+async fn deliver_webhooks(
+    config: users::UserConfigForUpdater,
+    mut receiver: LatestReceiver<WebhookDelivery>,
+    last_delivery_error: Arc<Mutex<Option<String>>>,
+) {
+    let user_id = &config.user_id;
+    let mut previous_fronters: Option<Vec<WebhookFronter>> = None;
+
+    while let Some(delivery) = receiver.recv().await {
+        let result = match webhook_body(&delivery, previous_fronters.as_deref()) {
+            Ok(body) => deliver_webhook(&config, &body).await,
+            Err(e) => Err(e),
+        };
+
+        match &result {
+            // only remember the fronters once they've been delivered, such that the receiver sees each transition
+            Ok(()) => previous_fronters = Some(delivery.fronters),
+            Err(e) => log::warn!("# | deliver_webhooks | {user_id} | failed: {e}"),
+        }
+
+        match last_delivery_error.lock() {
+            Ok(mut error) => *error = result.err().map(|e| e.to_string()),
+            Err(e) => log::error!("# | deliver_webhooks | {user_id} | shouldn't happen: {e}"),
+        }
+    }
+
+    log::info!("# | deliver_webhooks | {user_id} | end of delivery channel");
+}
+
+fn webhook_body(
+    delivery: &WebhookDelivery,
+    previous_fronters: Option<&[WebhookFronter]>,
+) -> Result<String> {
+    let payload = WebhookPayload {
+        event: "fronters_changed",
+        sent_at: clock::now(),
+        status: delivery.status.clone(),
+        fronters: &delivery.fronters,
+        previous_fronters,
+    };
+    Ok(serde_json::to_string(&payload)?)
+}
+
+async fn deliver_webhook(config: &users::UserConfigForUpdater, body: &str) -> Result<()> {
+    let signature = sign_webhook_body(&config.webhook_signing_secret.secret, body)?;
+
+    let client = platforms::public_url_client(config, &config.webhook_url).await?;
+
+    let mut backoff = WEBHOOK_INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        let result = send_webhook(&client, config, body, &signature).await;
+
+        let Err(e) = result else {
+            log::info!(
+                "# | deliver_webhook | {} | delivered after {attempt} attempt(s)",
+                config.user_id
+            );
+            return Ok(());
+        };
+
+        let retryable = matches!(
+            updater::categorize_error(&e),
+            updater::UpdateErrorCategory::Network
+                | updater::UpdateErrorCategory::PlatformUnavailable
+                | updater::UpdateErrorCategory::RateLimited
+        );
+
+        if !retryable || attempt >= WEBHOOK_MAX_ATTEMPTS {
+            let message = format!("Webhook delivery failed after {attempt} attempt(s): {e}");
+            return Err(e.context(message));
+        }
+
+        log::warn!(
+            "# | deliver_webhook | {} | attempt {attempt} failed, retrying in {backoff:?} | {e}",
+            config.user_id
+        );
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+        attempt += 1;
+    }
+}
+
+async fn send_webhook(
+    client: &reqwest::Client,
+    config: &users::UserConfigForUpdater,
+    body: &str,
+    signature: &str,
+) -> Result<()> {
+    client
+        .post(&config.webhook_url)
+        .header("Content-Type", "application/json")
+        .header(WEBHOOK_SIGNATURE_HEADER, signature)
+        .body(body.to_owned())
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// HMAC-SHA256 of the exact request body, formatted as `sha256=<hex>`.
+fn sign_webhook_body(secret: &str, body: &str) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| anyhow!("Invalid webhook signing secret: {e}"))?;
+    mac.update(body.as_bytes());
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    Ok(format!("sha256={hex}"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::database::Decrypted;
+    use crate::test_support::{self, MockRequest, ReceivedRequests};
+    use crate::users::UserConfigDbEntries;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const MOCK_SECRET: &str = "webhook_mock_secret";
+
+    /// Local HTTP receiver. Answers the requests with the given status codes in order and then with 200.
+    async fn start_webhook_receiver(statuses: Vec<&'static str>) -> (String, ReceivedRequests) {
+        let count = AtomicUsize::new(0);
+        let (base_url, received) = test_support::start_mock_http_server(move |_| {
+            let status = statuses
+                .get(count.fetch_add(1, Ordering::SeqCst))
+                .copied()
+                .unwrap_or("200 OK");
+            (status, String::new())
+        })
+        .await;
+        (format!("{base_url}/hook"), received)
+    }
+
+    fn create_test_config(webhook_url: &str) -> users::UserConfigForUpdater {
+        test_support::create_test_config(&UserConfigDbEntries {
+            enable_webhook: true,
+            webhook_url: Some(webhook_url.to_owned()),
+            webhook_signing_secret: Some(Decrypted {
+                secret: MOCK_SECRET.to_owned(),
+            }),
+            ..test_support::db_config()
+        })
+    }
+
+    fn fronter(id: &str, name: &str) -> plurality::Fronter {
+        plurality::Fronter {
+            fronter_id: id.to_owned(),
+            avatar_url: format!("https://example.com/{id}.png"),
+            pronouns: Some("they/them".to_owned()),
+            ..test_support::fronter(name)
+        }
+    }
+
+    async fn started_updater(config: &users::UserConfigForUpdater) -> WebhookUpdater {
+        let mut updater = WebhookUpdater::new();
+        updater.setup(config).await.unwrap();
+        updater
+    }
+
+    /// The webhooks are delivered in the background. Waits until the receiver got the given number of requests.
+    async fn wait_for_requests(received: &ReceivedRequests, count: usize) -> Vec<MockRequest> {
+        for _ in 0..100 {
+            let requests = received.lock().unwrap().clone();
+            if requests.len() >= count {
+                return requests;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("webhook receiver didn't get {count} requests");
+    }
+
+    async fn wait_for_delivery_error(updater: &WebhookUpdater) -> String {
+        for _ in 0..100 {
+            if let Some(error) = updater.last_delivery_error.lock().unwrap().clone() {
+                return error;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("webhook delivery didn't fail");
+    }
+
+    fn without_sent_at(body: &str) -> serde_json::Value {
+        let mut json: serde_json::Value = serde_json::from_str(body).unwrap();
+        json.as_object_mut().unwrap().remove("sent_at");
+        json
+    }
+
+    #[tokio::test]
+    async fn test_webhook_is_signed_and_contains_previous_fronters() {
+        let (url, received) = start_webhook_receiver(vec![]).await;
+        let config = create_test_config(&url);
+        let mut updater = started_updater(&config).await;
+
+        updater
+            .update_fronting_status(&config, &[fronter("a", "Annalea")])
+            .await
+            .unwrap();
+        wait_for_requests(&received, 1).await;
+        updater
+            .update_fronting_status(&config, &[fronter("b", "Borgn")])
+            .await
+            .unwrap();
+        let received = wait_for_requests(&received, 2).await;
+        assert_eq!(received.len(), 2);
+
+        for request in &received {
+            assert_eq!(
+                request.header(WEBHOOK_SIGNATURE_HEADER),
+                Some(
+                    sign_webhook_body(MOCK_SECRET, &request.body)
+                        .unwrap()
+                        .as_str()
+                )
+            );
+        }
+
+        let first: serde_json::Value = serde_json::from_str(&received[0].body).unwrap();
+        assert_eq!(first["event"], "fronters_changed");
+        assert_eq!(first["fronters"][0]["id"], "a");
+        assert_eq!(
+            first["fronters"][0]["avatar_url"],
+            "https://example.com/a.png"
+        );
+        assert!(first["status"].as_str().unwrap().contains("Annalea"));
+        assert!(first["previous_fronters"].is_null());
+
+        let second: serde_json::Value = serde_json::from_str(&received[1].body).unwrap();
+        assert_eq!(second["fronters"][0]["name"], "Borgn");
+        assert_eq!(second["previous_fronters"][0]["name"], "Annalea");
+        assert_eq!(
+            without_sent_at(updater.last_payload.as_deref().unwrap()),
+            without_sent_at(&received[1].body)
+        );
+        assert_eq!(updater.last_operation_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retries_server_errors() {
+        let (url, received) = start_webhook_receiver(vec!["500 Internal Server Error"]).await;
+        let config = create_test_config(&url);
+        let mut updater = started_updater(&config).await;
+
+        updater
+            .update_fronting_status(&config, &[fronter("a", "Annalea")])
+            .await
+            .unwrap();
+
+        let received = wait_for_requests(&received, 2).await;
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].body, received[1].body);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retries_do_not_block_updates() {
+        let (url, received) = start_webhook_receiver(vec!["503 Service Unavailable"; 3]).await;
+        let config = create_test_config(&url);
+        let mut updater = started_updater(&config).await;
+
+        let started = std::time::Instant::now();
+        updater
+            .update_fronting_status(&config, &[fronter("a", "Annalea")])
+            .await
+            .unwrap();
+        assert!(started.elapsed() < WEBHOOK_INITIAL_BACKOFF);
+
+        assert!(
+            wait_for_delivery_error(&updater)
+                .await
+                .contains("Webhook delivery failed after 3 attempt(s)")
+        );
+        assert_eq!(received.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_does_not_retry_rejected_requests() {
+        let (url, received) = start_webhook_receiver(vec!["400 Bad Request"]).await;
+        let config = create_test_config(&url);
+        let mut updater = started_updater(&config).await;
+
+        updater
+            .update_fronting_status(&config, &[fronter("a", "Annalea")])
+            .await
+            .unwrap();
+
+        assert!(
+            wait_for_delivery_error(&updater)
+                .await
+                .contains("Webhook delivery failed after 1 attempt(s)")
+        );
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        // the failed delivery is reported with the next update and isn't remembered as the previous fronters
+        updater
+            .update_fronting_status(&config, &[fronter("b", "Borgn")])
+            .await
+            .unwrap();
+        assert!(
+            updater
+                .last_operation_error
+                .unwrap()
+                .contains("Webhook delivery failed after 1 attempt(s)")
+        );
+        let received = wait_for_requests(&received, 2).await;
+        let body: serde_json::Value = serde_json::from_str(&received[1].body).unwrap();
+        assert_eq!(body["fronters"][0]["name"], "Borgn");
+        assert!(body["previous_fronters"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_status_ignores_the_website_settings() {
+        let (url, received) = start_webhook_receiver(vec![]).await;
+        let mut config = create_test_config(&url);
+        "F:".clone_into(&mut config.status_prefix);
+        config.website_status_prefix = Some("Website:".to_owned());
+        let mut annalea = fronter("a", "Annalea");
+        annalea
+            .display_names
+            .insert(plurality::DisplayNamePlatform::Website, "Anna".to_owned());
+
+        started_updater(&config)
+            .await
+            .update_fronting_status(&config, &[annalea])
+            .await
+            .unwrap();
+
+        let body: serde_json::Value =
+            serde_json::from_str(&wait_for_requests(&received, 1).await[0].body).unwrap();
+        assert_eq!(body["status"], "F: Annalea");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_is_not_sent_into_private_networks() {
+        let (url, received) = start_webhook_receiver(vec![]).await;
+        let mut config = create_test_config(&url);
+        config.allow_private_network_urls = false;
+        let mut updater = started_updater(&config).await;
+
+        updater
+            .update_fronting_status(&config, &[fronter("a", "Annalea")])
+            .await
+            .unwrap();
+
+        assert!(
+            wait_for_delivery_error(&updater)
+                .await
+                .contains("is not a public address")
+        );
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sign_webhook_body() {
+        // reference value from RFC 4231, test case 2
+        assert_eq!(
+            sign_webhook_body("Jefe", "what do ya want for nothing?").unwrap(),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
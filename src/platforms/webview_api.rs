@@ -27,10 +27,15 @@ pub async fn get_api_fronting_status(
 
     log::info!("# | GET /api/fronting-status/{user_id}");
 
-    let config =
-        database::get_user_config_with_secrets(db_pool, &user_id, client, application_user_secrets)
-            .await
-            .map_err(expose_internal_error)?;
+    let config = database::get_user_config_with_secrets(
+        db_pool,
+        &user_id,
+        client,
+        &shared_updaters.simply_plural_endpoints,
+        application_user_secrets,
+    )
+    .await
+    .map_err(expose_internal_error)?;
 
     log::info!("# | GET /api/fronting-status/{user_id} | got_config");
 
@@ -45,13 +50,7 @@ pub async fn get_api_fronting_status(
         fronters.len()
     );
 
-    let fronting_format = plurality::FrontingFormat {
-        cleaning: plurality::CleanForPlatform::NoClean,
-        max_length: None,
-        prefix: config.status_prefix,
-        status_if_no_fronters: config.status_no_fronts,
-        truncate_names_to_length_if_status_too_long: config.status_truncate_names_to,
-    };
+    let fronting_format = website_fronting_format(&config);
 
     let as_status = plurality::format_fronting_status(&fronting_format, &fronters);
 
@@ -65,6 +64,24 @@ pub async fn get_api_fronting_status(
     Ok(Json(result))
 }
 
+/// The fronting status without length limit, as it's shown on the website.
+#[must_use]
+pub fn website_fronting_format(config: &users::UserConfigForUpdater) -> plurality::FrontingFormat {
+    plurality::fronting_format_with_overrides(
+        config,
+        plurality::PlatformOverrides {
+            prefix: config.website_status_prefix.clone(),
+            status_if_no_fronters: config.website_status_no_fronts.clone(),
+            truncate_names_to: config.website_status_truncate_names_to,
+            show_custom_status: config.website_show_custom_status,
+            group_collapsing: config.website_status_group_collapsing,
+            display_name_platform: Some(plurality::DisplayNamePlatform::Website),
+            status_templates: config.website_status_templates.clone(),
+            ..Default::default()
+        },
+    )
+}
+
 #[get("/fronting/<website_url_name>")]
 pub async fn get_api_fronting_by_user_id(
     website_url_name: &str,
@@ -82,10 +99,15 @@ pub async fn get_api_fronting_by_user_id(
 
     log::info!("# | GET /fronting/{website_url_name} | {user_id}");
 
-    let config =
-        database::get_user_config_with_secrets(db_pool, &user_id, client, application_user_secrets)
-            .await
-            .map_err(expose_internal_error)?;
+    let config = database::get_user_config_with_secrets(
+        db_pool,
+        &user_id,
+        client,
+        &shared_updaters.simply_plural_endpoints,
+        application_user_secrets,
+    )
+    .await
+    .map_err(expose_internal_error)?;
 
     log::info!("# | GET /fronting/{website_url_name} | {user_id} | got_config");
 
@@ -100,7 +122,11 @@ pub async fn get_api_fronting_by_user_id(
         fronts.len()
     );
 
-    let html = generate_html(&config.website_system_name, &fronts);
+    let html = generate_html(
+        &config.website_system_name,
+        &fronts,
+        config.website_show_custom_status,
+    );
 
     log::info!(
         "# | GET /fronting/{website_url_name} | {user_id} | got_config | {} fronts | HTML generated",
@@ -110,14 +136,25 @@ pub async fn get_api_fronting_by_user_id(
     Ok(RawHtml(html))
 }
 
-fn generate_html(website_system_name: &str, fronts: &[plurality::Fronter]) -> String {
+fn generate_html(
+    website_system_name: &str,
+    fronts: &[plurality::Fronter],
+    show_custom_status: bool,
+) -> String {
     let fronts_formatted_and_escaped = fronts
         .iter()
         .map(|m| -> String {
+            let custom_status = m
+                .custom_status
+                .as_deref()
+                .filter(|s| show_custom_status && !s.trim().is_empty())
+                .map(|s| format!(" <small>({})</small>", html_escape::encode_text(s)))
+                .unwrap_or_default();
             format!(
-                "<div><img src=\"{}\" /><p>{}</p></div>",
+                "<div><img src=\"{}\" /><p>{}{}</p></div>",
                 html_escape::encode_double_quoted_attribute(&m.avatar_url),
-                html_escape::encode_text(&m.name)
+                html_escape::encode_text(m.display_name(plurality::DisplayNamePlatform::Website)),
+                custom_status
             )
         })
         .collect::<Vec<String>>()
@@ -165,6 +202,11 @@ fn generate_html(website_system_name: &str, fronts: &[plurality::Fronter]) -> St
                 font-weight:600;
             }}
 
+            body>div p small {{
+                font-size: 1.5rem;
+                font-weight:400;
+            }}
+
             /* --- phones & tablets ------------------------------------ */
             @media (max-width:800px) {{
                 body>div {{flex:1 1 calc(50% - 1rem);}}   /* 2-across */
@@ -185,6 +227,8 @@ fn generate_html(website_system_name: &str, fronts: &[plurality::Fronter]) -> St
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::generate_html;
     use crate::plurality::Fronter;
 
@@ -194,13 +238,17 @@ mod tests {
             fronter_id: "some-id".to_string(),
             name: "<script>alert('XSS')</script>".to_string(),
             avatar_url: "https://example.com/avatar.png".to_string(),
-            vrchat_status_name: None,
+            display_names: HashMap::new(),
             start_time: None,
             privacy_buckets: vec![],
+            groups: vec![],
+            is_custom_front: false,
             pluralkit_id: None,
+            pronouns: None,
+            custom_status: None,
         }];
         let system_name = "My <System>";
-        let html = generate_html(system_name, &fronters);
+        let html = generate_html(system_name, &fronters, false);
 
         // Test system name escaping
         assert!(html.contains("<title>My &lt;System&gt; - Fronting Status</title>"));
@@ -216,7 +264,7 @@ mod tests {
     fn test_generate_html_empty_fronters() {
         let fronters = vec![];
         let system_name = "My System";
-        let html = generate_html(system_name, &fronters);
+        let html = generate_html(system_name, &fronters, false);
 
         assert!(html.contains("<title>My System - Fronting Status</title>"));
         assert!(!html.contains("<div><img"));
@@ -229,23 +277,31 @@ mod tests {
                 fronter_id: "id1".to_string(),
                 name: "Fronter 1".to_string(),
                 avatar_url: "https://example.com/avatar1.png".to_string(),
-                vrchat_status_name: None,
+                display_names: HashMap::new(),
                 start_time: None,
                 privacy_buckets: vec![],
+                groups: vec![],
+                is_custom_front: false,
                 pluralkit_id: None,
+                pronouns: None,
+                custom_status: None,
             },
             Fronter {
                 fronter_id: "id2".to_string(),
                 name: "Fronter 2".to_string(),
                 avatar_url: "https://example.com/avatar2.png".to_string(),
-                vrchat_status_name: None,
+                display_names: HashMap::new(),
                 start_time: None,
                 privacy_buckets: vec![],
+                groups: vec![],
+                is_custom_front: false,
                 pluralkit_id: None,
+                pronouns: None,
+                custom_status: None,
             },
         ];
         let system_name = "My System";
-        let html = generate_html(system_name, &fronters);
+        let html = generate_html(system_name, &fronters, false);
 
         assert!(html.contains("<p>Fronter 1</p>"));
         assert!(html.contains("src=\"https://example.com/avatar1.png\""));
@@ -259,13 +315,17 @@ mod tests {
             fronter_id: "some-id".to_string(),
             name: "Dangerous".to_string(),
             avatar_url: "https://example.com/\" onerror=\"alert('oops')".to_string(),
-            vrchat_status_name: None,
+            display_names: HashMap::new(),
             start_time: None,
             privacy_buckets: vec![],
+            groups: vec![],
+            is_custom_front: false,
             pluralkit_id: None,
+            pronouns: None,
+            custom_status: None,
         }];
         let system_name = "My System";
-        let html = generate_html(system_name, &fronters);
+        let html = generate_html(system_name, &fronters, false);
 
         assert!(html.contains("src=\"https://example.com/&quot; onerror=&quot;alert('oops')\""));
     }
@@ -276,15 +336,42 @@ mod tests {
             fronter_id: "some-id".to_string(),
             name: "Hacker".to_string(),
             avatar_url: "\"><script>alert('xss')</script>".to_string(),
-            vrchat_status_name: None,
+            display_names: HashMap::new(),
             start_time: None,
             privacy_buckets: vec![],
+            groups: vec![],
+            is_custom_front: false,
             pluralkit_id: None,
+            pronouns: None,
+            custom_status: None,
         }];
         let system_name = "My System";
-        let html = generate_html(system_name, &fronters);
+        let html = generate_html(system_name, &fronters, false);
 
         assert!(!html.contains("\"><script>alert('xss')</script>"));
         assert!(html.contains("src=\"&quot;&gt;&lt;script&gt;alert('xss')&lt;/script&gt;\""));
     }
+
+    #[test]
+    fn test_generate_html_custom_status() {
+        let fronters = vec![Fronter {
+            fronter_id: "some-id".to_string(),
+            name: "Alex".to_string(),
+            avatar_url: String::new(),
+            display_names: HashMap::new(),
+            start_time: None,
+            privacy_buckets: vec![],
+            groups: vec![],
+            is_custom_front: false,
+            pluralkit_id: None,
+            pronouns: None,
+            custom_status: Some("<b>blurry</b>".to_string()),
+        }];
+
+        let html = generate_html("My System", &fronters, false);
+        assert!(html.contains("<p>Alex</p>"));
+
+        let html = generate_html("My System", &fronters, true);
+        assert!(html.contains("<p>Alex <small>(&lt;b&gt;blurry&lt;/b&gt;)</small></p>"));
+    }
 }
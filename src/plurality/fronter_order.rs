@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use crate::plurality::Fronter;
+
+/// The order in which the fronters are shown on all platforms.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    sqlx::Type,
+    specta::Type,
+    strum_macros::Display,
+)]
+#[specta(export)]
+#[sqlx(type_name = "fronter_order_enum")]
+pub enum FronterOrder {
+    /// The order of the member list in the fronting source
+    #[default]
+    SourceOrder,
+    StartTimeNewestFirst,
+    StartTimeOldestFirst,
+    Alphabetical,
+    /// The configured member IDs first (in that order), followed by all others in source order
+    Manual,
+}
+
+/// Sorts the fronters in place. The sorting is stable, hence equal fronters stay in source order.
+pub fn sort_fronters(order: FronterOrder, manual_order: &[String], fronters: &mut [Fronter]) {
+    match order {
+        FronterOrder::SourceOrder => (),
+        FronterOrder::StartTimeNewestFirst => {
+            // fronters without start time last
+            fronters.sort_by_key(|f| std::cmp::Reverse(f.start_time));
+        }
+        FronterOrder::StartTimeOldestFirst => {
+            fronters.sort_by_key(|f| (f.start_time.is_none(), f.start_time));
+        }
+        FronterOrder::Alphabetical => fronters.sort_by_key(|f| f.name.to_lowercase()),
+        FronterOrder::Manual => fronters.sort_by_key(|f| {
+            manual_order
+                .iter()
+                .position(|id| {
+                    *id == f.fronter_id || f.pluralkit_id.as_ref().is_some_and(|pk_id| id == pk_id)
+                })
+                .unwrap_or(usize::MAX)
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn fronter(id: &str, name: &str, start_time_secs: Option<i64>) -> Fronter {
+        Fronter {
+            fronter_id: id.to_owned(),
+            name: name.to_owned(),
+            avatar_url: String::new(),
+            display_names: HashMap::new(),
+            pluralkit_id: Some(format!("pk-{id}")),
+            pronouns: None,
+            custom_status: None,
+            start_time: start_time_secs.and_then(|s| chrono::DateTime::from_timestamp(s, 0)),
+            privacy_buckets: vec![],
+            groups: vec![],
+            is_custom_front: false,
+        }
+    }
+
+    fn sorted_ids(order: FronterOrder, manual_order: &[&str]) -> Vec<String> {
+        let mut fronters = vec![
+            fronter("m1", "bob", Some(200)),
+            fronter("m2", "Alice", None),
+            fronter("m3", "Claire", Some(100)),
+            fronter("m4", "dan", Some(300)),
+        ];
+        let manual_order: Vec<String> = manual_order.iter().map(|&s| s.to_owned()).collect();
+        sort_fronters(order, &manual_order, &mut fronters);
+        fronters.into_iter().map(|f| f.fronter_id).collect()
+    }
+
+    #[test]
+    fn test_sort_fronters() {
+        assert_eq!(
+            sorted_ids(FronterOrder::SourceOrder, &[]),
+            vec!["m1", "m2", "m3", "m4"]
+        );
+        assert_eq!(
+            sorted_ids(FronterOrder::StartTimeNewestFirst, &[]),
+            vec!["m4", "m1", "m3", "m2"]
+        );
+        assert_eq!(
+            sorted_ids(FronterOrder::StartTimeOldestFirst, &[]),
+            vec!["m3", "m1", "m4", "m2"]
+        );
+        assert_eq!(
+            sorted_ids(FronterOrder::Alphabetical, &[]),
+            vec!["m2", "m1", "m3", "m4"]
+        );
+        assert_eq!(
+            sorted_ids(FronterOrder::Manual, &["m3", "pk-m4", "unknown"]),
+            vec!["m3", "m4", "m1", "m2"]
+        );
+    }
+}
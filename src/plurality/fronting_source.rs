@@ -0,0 +1,130 @@
+use std::sync::Mutex;
+
+use anyhow::{Result, anyhow};
+use pluralsync_base::updater::UpdaterStatus;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    plurality::{Fronter, PluralKitSource, SimplyPluralSource},
+    users,
+};
+
+/// The system manager which is the source of truth for the fronters of a user.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    sqlx::Type,
+    specta::Type,
+    strum_macros::Display,
+)]
+#[specta(export)]
+#[sqlx(type_name = "fronting_source_enum")]
+pub enum FrontingSourceKind {
+    #[default]
+    SimplyPlural,
+    PluralKit,
+}
+
+// NOTE: specta::Type is manually exported in bindings
+#[derive(Debug, Clone, Serialize)]
+pub struct FrontingSourceStatus {
+    pub source: FrontingSourceKind,
+    pub status: UpdaterStatus,
+}
+
+/// A source of the current fronters.
+///
+/// The `UpdaterManager` uses the source configured by the user to feed the fronters into the `fronter_channel`.
+#[allow(async_fn_in_trait)]
+pub trait FrontingSource {
+    /// Fetches the current fronters from the source.
+    async fn fetch_fronters(&self, config: &users::UserConfigForUpdater) -> Result<Vec<Fronter>>;
+
+    /// Listens for changes at the source and calls `on_change` whenever the fronters might have changed.
+    ///
+    /// Changes might be missed while (re-)connecting. Hence, `on_change` is also called after each (re-)connect.
+    /// Only returns early, if the source cannot notify about changes with the given config.
+    async fn listen_for_changes<F>(
+        &self,
+        config: &users::UserConfigForUpdater,
+        on_change: impl Fn() -> F,
+    ) where
+        F: Future<Output = Result<()>>;
+
+    /// Health of the source as observed by the most recent fetch.
+    fn status(&self) -> Result<UpdaterStatus>;
+}
+
+/// Stores the outcome of the most recent fetch as the new status of the source.
+pub fn record_fetch_status<T>(
+    status: &Mutex<UpdaterStatus>,
+    fetch_result: &Result<T>,
+) -> Result<()> {
+    let new_status = match fetch_result {
+        Ok(_) => UpdaterStatus::Running,
+        Err(e) => UpdaterStatus::Error(e.to_string()),
+    };
+    *status.lock().map_err(|e| anyhow!(e.to_string()))? = new_status;
+    Ok(())
+}
+
+// there is only a single source per user. hence, the size difference doesn't matter.
+#[allow(clippy::large_enum_variant)]
+pub enum AnyFrontingSource {
+    SimplyPlural(SimplyPluralSource),
+    PluralKit(PluralKitSource),
+}
+
+impl AnyFrontingSource {
+    #[must_use]
+    pub fn new(kind: FrontingSourceKind) -> Self {
+        match kind {
+            FrontingSourceKind::SimplyPlural => Self::SimplyPlural(SimplyPluralSource::new()),
+            FrontingSourceKind::PluralKit => Self::PluralKit(PluralKitSource::new()),
+        }
+    }
+
+    #[must_use]
+    pub const fn kind(&self) -> FrontingSourceKind {
+        match self {
+            Self::SimplyPlural(_) => FrontingSourceKind::SimplyPlural,
+            Self::PluralKit(_) => FrontingSourceKind::PluralKit,
+        }
+    }
+}
+
+impl FrontingSource for AnyFrontingSource {
+    async fn fetch_fronters(&self, config: &users::UserConfigForUpdater) -> Result<Vec<Fronter>> {
+        match self {
+            Self::SimplyPlural(source) => source.fetch_fronters(config).await,
+            Self::PluralKit(source) => source.fetch_fronters(config).await,
+        }
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn listen_for_changes<F>(
+        &self,
+        config: &users::UserConfigForUpdater,
+        on_change: impl Fn() -> F,
+    ) where
+        F: Future<Output = Result<()>>,
+    {
+        match self {
+            Self::SimplyPlural(source) => source.listen_for_changes(config, on_change).await,
+            Self::PluralKit(source) => source.listen_for_changes(config, on_change).await,
+        }
+    }
+
+    fn status(&self) -> Result<UpdaterStatus> {
+        match self {
+            Self::SimplyPlural(source) => source.status(),
+            Self::PluralKit(source) => source.status(),
+        }
+    }
+}
@@ -1,6 +1,16 @@
-use crate::{metric, metrics::SHOULDNT_HAPPEN_BUT_IT_DID, plurality::Fronter};
+use crate::{
+    metric,
+    metrics::SHOULDNT_HAPPEN_BUT_IT_DID,
+    plurality::{
+        DisplayNamePlatform, Fronter, FronterGroup, StatusTemplate, TemplateContext,
+        transliterate_to_latin,
+    },
+    users,
+};
 
 use encoding_rs::ISO_8859_15;
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
 
 pub const VRCHAT_MAX_ALLOWED_STATUS_LENGTH: usize = 23;
 pub const DISCORD_STATUS_MAX_LENGTH: usize = 128;
@@ -14,15 +24,169 @@ metric!(
 
 pub struct FrontingFormat {
     pub max_length: Option<usize>,
+    /// How the platform counts the length of the status for `max_length`
+    pub length_metric: LengthMetric,
     pub cleaning: CleanForPlatform,
     pub prefix: String,
     pub status_if_no_fronters: String,
     pub truncate_names_to_length_if_status_too_long: usize,
+    /// Appends the custom status of the front, e.g. "Alex (blurry)"
+    pub show_custom_status: bool,
+    pub group_collapsing: GroupCollapsing,
+    /// Whose display names are used instead of the fronter names, if configured
+    pub display_name_platform: DisplayNamePlatform,
+    /// User defined fallback list of status shapes. The first one which fits is used.
+    /// If none are defined or none fit, then the built-in shapes are used.
+    pub status_templates: Vec<StatusTemplate>,
+}
+
+/// The fronting status from the global status settings without length limit.
+/// Platforms without own status settings (webhooks, Matrix, Mastodon, Bluesky, MQTT) start from this format.
+#[must_use]
+pub fn generic_fronting_format(
+    config: &users::UserConfigForUpdater,
+    display_name_platform: DisplayNamePlatform,
+) -> FrontingFormat {
+    fronting_format_with_overrides(
+        config,
+        PlatformOverrides {
+            display_name_platform: Some(display_name_platform),
+            ..Default::default()
+        },
+    )
+}
+
+/// What a platform sets differently from the global status settings. Unset values fall back to them.
+#[derive(Default)]
+pub struct PlatformOverrides {
+    /// No length limit, if unset
+    pub max_length: Option<usize>,
+    pub length_metric: Option<LengthMetric>,
+    pub cleaning: Option<CleanForPlatform>,
+    pub prefix: Option<String>,
+    pub status_if_no_fronters: Option<String>,
+    pub truncate_names_to: Option<usize>,
+    pub show_custom_status: bool,
+    pub group_collapsing: Option<GroupCollapsing>,
+    pub display_name_platform: Option<DisplayNamePlatform>,
+    pub status_templates: Vec<StatusTemplate>,
+}
+
+#[must_use]
+pub fn fronting_format_with_overrides(
+    config: &users::UserConfigForUpdater,
+    overrides: PlatformOverrides,
+) -> FrontingFormat {
+    FrontingFormat {
+        max_length: overrides.max_length,
+        length_metric: overrides
+            .length_metric
+            .unwrap_or(LengthMetric::GraphemeClusters),
+        cleaning: overrides.cleaning.unwrap_or(CleanForPlatform::NoClean),
+        prefix: overrides
+            .prefix
+            .unwrap_or_else(|| config.status_prefix.clone()),
+        status_if_no_fronters: overrides
+            .status_if_no_fronters
+            .unwrap_or_else(|| config.status_no_fronts.clone()),
+        truncate_names_to_length_if_status_too_long: overrides
+            .truncate_names_to
+            .unwrap_or(config.status_truncate_names_to),
+        show_custom_status: overrides.show_custom_status,
+        group_collapsing: overrides
+            .group_collapsing
+            .unwrap_or(config.status_group_collapsing),
+        display_name_platform: overrides
+            .display_name_platform
+            .unwrap_or(DisplayNamePlatform::Generic),
+        status_templates: overrides.status_templates,
+    }
+}
+
+/// When to show the name of a group (e.g. "Littles ×3") instead of its fronting members.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    sqlx::Type,
+    specta::Type,
+    strum_macros::Display,
+)]
+#[specta(export)]
+#[sqlx(type_name = "group_collapsing_enum")]
+pub enum GroupCollapsing {
+    Never,
+    /// Only if the names don't fit even when truncated
+    #[default]
+    WhenTooLong,
+    Always,
+}
+
+/// What to do with characters outside of the encoding of the status, e.g. "さくら", "Сергей" or emoji.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Default,
+    sqlx::Type,
+    specta::Type,
+    strum_macros::Display,
+)]
+#[specta(export)]
+#[sqlx(type_name = "unsupported_characters_enum")]
+pub enum UnsupportedCharacters {
+    #[default]
+    Drop,
+    /// Romanizes Cyrillic, Greek, kana and Hangul and replaces emoji by their shortname. The rest is dropped.
+    Transliterate,
+    /// Replaces each sequence of unsupported characters by a "?"
+    Placeholder,
+}
+
+/// How a platform counts the length of a text. Emoji like "👩‍👩‍👧" are one grapheme cluster but many code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthMetric {
+    /// User perceived characters
+    GraphemeClusters,
+    /// Like JavaScript's `String.length`, which Discord uses for its limits.
+    /// `VRChat` measures the status as a .NET string, i.e. in UTF-16 code units as well.
+    Utf16CodeUnits,
+    /// Like Ruby's `String#length`, which Mastodon uses for its limits
+    UnicodeScalars,
+}
+
+impl LengthMetric {
+    #[must_use]
+    pub fn length(self, text: &str) -> usize {
+        match self {
+            Self::GraphemeClusters => text.graphemes(true).count(),
+            Self::Utf16CodeUnits => text.encode_utf16().count(),
+            Self::UnicodeScalars => text.chars().count(),
+        }
+    }
 }
 
 pub enum CleanForPlatform {
     NoClean,
-    VRChat,
+    VRChat(UnsupportedCharacters),
+}
+
+impl CleanForPlatform {
+    fn clean(&self, text: &str) -> String {
+        match self {
+            Self::NoClean => text.to_owned(),
+            Self::VRChat(unsupported) => clean_name_for_vrchat_status(text, *unsupported),
+        }
+    }
 }
 
 #[must_use]
@@ -30,14 +194,15 @@ pub fn format_fronting_status(fronting_format: &FrontingFormat, fronts: &[Fronte
     let cleaned_fronter_names = collect_clean_fronter_names(fronting_format, fronts);
     log::debug!("# | format_fronting_status | cleaned to '{cleaned_fronter_names:?}'");
 
-    let status_strings =
-        compute_status_strings_of_decreasing_lengths_for_aesthetics_and_information_tradeoff(
-            fronting_format,
-            &cleaned_fronter_names,
-        );
-
     let status =
-        pick_longest_string_within_vrchat_status_length_limit(fronting_format, &status_strings);
+        pick_first_fitting_status_template(fronting_format, fronts, &cleaned_fronter_names)
+            .unwrap_or_else(|| {
+                format_fronting_status_with_builtin_shapes(
+                    fronting_format,
+                    fronts,
+                    &cleaned_fronter_names,
+                )
+            });
 
     FRONTING_STATUS_STRING
         .with_label_values(&[&status.len().to_string()])
@@ -46,6 +211,99 @@ pub fn format_fronting_status(fronting_format: &FrontingFormat, fronts: &[Fronte
     status
 }
 
+fn pick_first_fitting_status_template(
+    fronting_format: &FrontingFormat,
+    fronts: &[Fronter],
+    cleaned_fronter_names: &[String],
+) -> Option<String> {
+    if fronting_format.status_templates.is_empty() {
+        return None;
+    }
+
+    let names_candidates = match fronting_format.group_collapsing {
+        GroupCollapsing::Always if !fronts.is_empty() => vec![collect_group_collapsed_names(
+            fronting_format,
+            fronts,
+            cleaned_fronter_names,
+        )],
+        GroupCollapsing::WhenTooLong if !fronts.is_empty() => vec![
+            cleaned_fronter_names.to_vec(),
+            collect_group_collapsed_names(fronting_format, fronts, cleaned_fronter_names),
+        ],
+        _ => vec![cleaned_fronter_names.to_vec()],
+    };
+
+    let pronouns: Vec<String> = fronts
+        .iter()
+        .filter_map(|f| f.pronouns.as_deref())
+        .map(|pronouns| fronting_format.cleaning.clean(pronouns))
+        .filter(|pronouns| !pronouns.trim().is_empty())
+        .collect();
+
+    names_candidates.iter().find_map(|names| {
+        let context = TemplateContext {
+            prefix: &fronting_format.prefix,
+            names,
+            pronouns: &pronouns,
+            count: fronts.len(),
+        };
+        fronting_format
+            .status_templates
+            .iter()
+            .map(|template| template.render(&context))
+            .inspect(|s| log::debug!("# | fronting_status_template | '{s}'"))
+            .find(|s| fits(fronting_format, s))
+    })
+}
+
+fn format_fronting_status_with_builtin_shapes(
+    fronting_format: &FrontingFormat,
+    fronts: &[Fronter],
+    cleaned_fronter_names: &[String],
+) -> String {
+    let is_custom_front: Vec<bool> = fronts.iter().map(|f| f.is_custom_front).collect();
+
+    let status_candidates = match fronting_format.group_collapsing {
+        GroupCollapsing::Always if !fronts.is_empty() => {
+            let group_collapsed_names =
+                collect_group_collapsed_names(fronting_format, fronts, cleaned_fronter_names);
+            compute_group_collapsed_status_candidates(
+                fronting_format,
+                fronts.len(),
+                &group_collapsed_names,
+            )
+        }
+        GroupCollapsing::WhenTooLong if !fronts.is_empty() => {
+            let mut status_candidates =
+                compute_status_candidates_for_aesthetics_and_information_tradeoff(
+                    fronting_format,
+                    cleaned_fronter_names,
+                    &is_custom_front,
+                );
+            let all_names_fit = status_candidates
+                .iter()
+                .any(|c| c.names_shown == fronts.len() && fits(fronting_format, &c.status));
+            if !all_names_fit {
+                let group_collapsed_names =
+                    collect_group_collapsed_names(fronting_format, fronts, cleaned_fronter_names);
+                status_candidates.extend(compute_group_collapsed_status_candidates(
+                    fronting_format,
+                    fronts.len(),
+                    &group_collapsed_names,
+                ));
+            }
+            status_candidates
+        }
+        _ => compute_status_candidates_for_aesthetics_and_information_tradeoff(
+            fronting_format,
+            cleaned_fronter_names,
+            &is_custom_front,
+        ),
+    };
+
+    pick_best_scoring_status_within_length_limit(fronting_format, &status_candidates)
+}
+
 fn collect_clean_fronter_names(
     fronting_format: &FrontingFormat,
     fronts: &[Fronter],
@@ -55,125 +313,324 @@ fn collect_clean_fronter_names(
     } else {
         fronts
             .iter()
-            .map(|f| match fronting_format.cleaning {
-                CleanForPlatform::NoClean => f.preferred_vrchat_status_name().to_owned(),
-                CleanForPlatform::VRChat => f
-                    .vrchat_status_name
-                    .clone()
-                    .unwrap_or_else(|| clean_name_for_vrchat_status(&f.name)),
+            .map(|f| {
+                // explicitly configured display names are used as they are
+                let display_name = f.display_names.get(&fronting_format.display_name_platform);
+                let name = display_name
+                    .cloned()
+                    .unwrap_or_else(|| fronting_format.cleaning.clean(&f.name));
+                let custom_status = f
+                    .custom_status
+                    .as_deref()
+                    .filter(|_| fronting_format.show_custom_status)
+                    .map(|custom_status| fronting_format.cleaning.clean(custom_status))
+                    .filter(|custom_status| !custom_status.trim().is_empty());
+                match custom_status {
+                    Some(custom_status) => format!("{name} ({custom_status})"),
+                    None => name,
+                }
             })
             .collect()
     }
 }
 
-fn compute_status_strings_of_decreasing_lengths_for_aesthetics_and_information_tradeoff(
+/// Replaces the fronters by the names of their groups, where at least two fronters are in the same group.
+///
+/// Groups covering the most fronters are picked first. The group name is suffixed with the number of fronting
+/// members, unless all members of the group are fronting. E.g. "Littles ×3, Protectors, Alex"
+fn collect_group_collapsed_names(
     fronting_format: &FrontingFormat,
-    fronter_names: &[String],
+    fronts: &[Fronter],
+    cleaned_fronter_names: &[String],
 ) -> Vec<String> {
-    // Convert Vec<String> to Vec<&str> for convenient joining and slicing.
-    let fronter_names_as_str: Vec<&str> = fronter_names.iter().map(String::as_str).collect();
-
-    let long_string = format!(
-        "{} {}",
-        fronting_format.prefix,
-        fronter_names_as_str.join(", ")
-    );
-    let short_string = format!(
-        "{}{}",
-        fronting_format.prefix,
-        fronter_names_as_str.join(",")
-    );
-    let truncated_string = {
-        let truncated_names: Vec<String> = fronter_names_as_str
+    let mut assigned_group: Vec<Option<&FronterGroup>> = vec![None; fronts.len()];
+
+    loop {
+        let unassigned_members_of = |group: &FronterGroup| {
+            fronts
+                .iter()
+                .zip(&assigned_group)
+                .filter(|(f, assigned)| assigned.is_none() && f.groups.contains(group))
+                .count()
+        };
+        let best_group = fronts
             .iter()
-            .map(|&name| {
-                let mut truncated_name = String::new();
+            .zip(&assigned_group)
+            .filter(|(_, assigned)| assigned.is_none())
+            .flat_map(|(f, _)| &f.groups)
+            .map(|g| (unassigned_members_of(g), g))
+            .filter(|(count, _)| *count >= 2)
+            // most fronters first, then the most specific group, then deterministic by id
+            .max_by(|(count_a, a), (count_b, b)| {
+                count_a
+                    .cmp(count_b)
+                    .then(b.members_count.cmp(&a.members_count))
+                    .then(b.group_id.cmp(&a.group_id))
+            })
+            .map(|(_, g)| g);
 
-                let () = &name
-                    .chars()
-                    .take(fronting_format.truncate_names_to_length_if_status_too_long)
-                    .for_each(|c| truncated_name.push(c));
+        let Some(best_group) = best_group else {
+            break;
+        };
 
-                truncated_name
-            })
+        for (f, assigned) in fronts.iter().zip(assigned_group.iter_mut()) {
+            if assigned.is_none() && f.groups.contains(best_group) {
+                *assigned = Some(best_group);
+            }
+        }
+    }
+
+    let mut names: Vec<String> = vec![];
+    let mut already_shown_groups: Vec<&str> = vec![];
+    for (name, assigned) in cleaned_fronter_names.iter().zip(&assigned_group) {
+        match assigned {
+            None => names.push(name.clone()),
+            Some(group) if already_shown_groups.contains(&group.group_id.as_str()) => (),
+            Some(group) => {
+                already_shown_groups.push(&group.group_id);
+                let fronting_count = assigned_group
+                    .iter()
+                    .filter(|a| a.is_some_and(|a| a.group_id == group.group_id))
+                    .count();
+                let group_name = fronting_format.cleaning.clean(&group.name);
+                if fronting_count >= group.members_count {
+                    names.push(group_name);
+                } else {
+                    names.push(format!("{group_name} ×{fronting_count}"));
+                }
+            }
+        }
+    }
+    names
+}
+
+/// A possible status string together with how much it tells about the fronters.
+struct StatusCandidate {
+    status: String,
+    /// Number of fronters which are mentioned by their (possibly truncated) name or by their group
+    names_shown: usize,
+    /// Number of characters of the names which made it into the status
+    name_characters_shown: usize,
+}
+
+impl StatusCandidate {
+    /// Higher is better: more fronters beat longer names and longer names beat compact separators.
+    fn score(&self) -> (usize, usize, usize) {
+        (
+            self.names_shown,
+            self.name_characters_shown,
+            LengthMetric::GraphemeClusters.length(&self.status),
+        )
+    }
+}
+
+fn compute_status_candidates_for_aesthetics_and_information_tradeoff(
+    fronting_format: &FrontingFormat,
+    fronter_names: &[String],
+    is_custom_front: &[bool],
+) -> Vec<StatusCandidate> {
+    let prefix = &fronting_format.prefix;
+    // without prefix, the separator is left out as well, e.g. for a profile field which only lists the names
+    let spaced_prefix = if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("{prefix} ")
+    };
+    let names_count = fronter_names.len();
+    let name_lengths: Vec<usize> = fronter_names
+        .iter()
+        .map(|name| LengthMetric::GraphemeClusters.length(name))
+        .collect();
+    // names which were cleaned away entirely don't tell anything
+    let count_shown = |lengths: &[usize]| lengths.iter().filter(|&&l| l > 0).count();
+
+    let mut candidates = vec![
+        StatusCandidate {
+            status: format!("{spaced_prefix}{}", fronter_names.join(", ")),
+            names_shown: count_shown(&name_lengths),
+            name_characters_shown: name_lengths.iter().sum(),
+        },
+        StatusCandidate {
+            status: format!("{prefix}{}", fronter_names.join(",")),
+            names_shown: count_shown(&name_lengths),
+            name_characters_shown: name_lengths.iter().sum(),
+        },
+    ];
+
+    // Only the names longer than the limit are truncated, so that short names stay complete.
+    // The smallest limit truncates every name to the configured length.
+    // Names are cut between grapheme clusters, so that no emoji or accent is split up.
+    let longest_name = name_lengths.iter().copied().max().unwrap_or_default();
+    let shortest_limit = fronting_format.truncate_names_to_length_if_status_too_long;
+    for limit in (shortest_limit..longest_name).rev() {
+        let truncated_names: Vec<String> = fronter_names
+            .iter()
+            .map(|name| name.graphemes(true).take(limit).collect())
             .collect();
-        format!(
-            "{}{}",
-            fronting_format.prefix,
-            truncated_names.join(",").as_str()
+        let truncated_lengths: Vec<usize> = name_lengths.iter().map(|&l| l.min(limit)).collect();
+        candidates.push(StatusCandidate {
+            status: format!("{prefix}{}", truncated_names.join(",")),
+            names_shown: count_shown(&truncated_lengths),
+            name_characters_shown: truncated_lengths.iter().sum(),
+        });
+    }
+
+    // The first names and the number of the remaining ones, e.g. "F: Alex, Bob +2".
+    // Custom fronts are left out before members.
+    let mut kept_by_priority: Vec<usize> = (0..names_count).collect();
+    kept_by_priority.sort_by_key(|&i| {
+        (
+            name_lengths[i] == 0,
+            is_custom_front.get(i).copied().unwrap_or_default(),
         )
+    });
+    for kept_count in (1..names_count).rev() {
+        let mut kept = kept_by_priority[..kept_count].to_vec();
+        kept.sort_unstable();
+        let kept_names: Vec<&str> = kept.iter().map(|&i| fronter_names[i].as_str()).collect();
+        let kept_lengths: Vec<usize> = kept.iter().map(|&i| name_lengths[i]).collect();
+        let remaining_count = names_count - kept_count;
+        candidates.push(StatusCandidate {
+            status: format!(
+                "{spaced_prefix}{} +{remaining_count}",
+                kept_names.join(", ")
+            ),
+            names_shown: count_shown(&kept_lengths),
+            name_characters_shown: kept_lengths.iter().sum(),
+        });
+        candidates.push(StatusCandidate {
+            status: format!("{prefix}{}+{remaining_count}", kept_names.join(",")),
+            names_shown: count_shown(&kept_lengths),
+            name_characters_shown: kept_lengths.iter().sum(),
+        });
+    }
+
+    // without any visible names, the count tells more
+    candidates.retain(|c| c.names_shown > 0);
+    candidates.push(StatusCandidate {
+        status: format!("{spaced_prefix}{names_count}#"),
+        names_shown: 0,
+        name_characters_shown: 0,
+    });
+
+    candidates
+}
+
+fn compute_group_collapsed_status_candidates(
+    fronting_format: &FrontingFormat,
+    fronters_count: usize,
+    group_collapsed_names: &[String],
+) -> Vec<StatusCandidate> {
+    let prefix = &fronting_format.prefix;
+    // without prefix, the separator is left out as well, e.g. for a profile field which only lists the names
+    let spaced_prefix = if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("{prefix} ")
     };
-    let count_string = format!("{} {}#", fronting_format.prefix, fronter_names.len());
-
-    log::debug!(
-        "# | fronting_status_lengths | long:  '{}' ({})",
-        long_string,
-        string_unicode_codepoints_length(&long_string)
-    );
-    log::debug!(
-        "# | fronting_status_lengths | short: '{}' ({})",
-        short_string,
-        string_unicode_codepoints_length(&short_string)
-    );
-    log::debug!(
-        "# | fronting_status_lengths | trunc: '{}' ({})",
-        truncated_string,
-        string_unicode_codepoints_length(&truncated_string)
-    );
-    log::debug!(
-        "# | fronting_status_lengths | count: '{}' ({})",
-        count_string,
-        string_unicode_codepoints_length(&count_string)
-    );
-
-    vec![long_string, short_string, truncated_string, count_string]
-}
-
-fn pick_longest_string_within_vrchat_status_length_limit(
+    let name_lengths: Vec<usize> = group_collapsed_names
+        .iter()
+        .map(|name| LengthMetric::GraphemeClusters.length(name))
+        .collect();
+    // a group stands for all of its fronters, unless some names were cleaned away entirely
+    let names_shown = if name_lengths.iter().all(|&l| l > 0) {
+        fronters_count
+    } else {
+        name_lengths.iter().filter(|&&l| l > 0).count()
+    };
+
+    let mut candidates = vec![
+        StatusCandidate {
+            status: format!("{spaced_prefix}{}", group_collapsed_names.join(", ")),
+            names_shown,
+            name_characters_shown: name_lengths.iter().sum(),
+        },
+        StatusCandidate {
+            status: format!("{prefix}{}", group_collapsed_names.join(",")),
+            names_shown,
+            name_characters_shown: name_lengths.iter().sum(),
+        },
+    ];
+    candidates.retain(|c| c.names_shown > 0);
+    candidates.push(StatusCandidate {
+        status: format!("{spaced_prefix}{fronters_count}#"),
+        names_shown: 0,
+        name_characters_shown: 0,
+    });
+    candidates
+}
+
+fn pick_best_scoring_status_within_length_limit(
     fronting_format: &FrontingFormat,
-    status_strings: &[String],
+    status_candidates: &[StatusCandidate],
 ) -> String {
-    let empty_string = String::new();
-    status_strings
+    status_candidates
         .iter()
-        .filter(|s| {
-            fronting_format
-                .max_length
-                .is_none_or(|l| string_unicode_codepoints_length(*s) <= l)
+        .inspect(|c| {
+            log::debug!(
+                "# | fronting_status_candidate | '{}' ({:?})",
+                c.status,
+                c.score()
+            );
         })
-        .max_by_key(|s| string_unicode_codepoints_length(*s))
-        .unwrap_or_else(|| {
-            SHOULDNT_HAPPEN_BUT_IT_DID
-                .with_label_values(&["pick_longest_string_within_vrchat_status_length_limit"])
-                .inc();
-            &empty_string
-        })
-        .clone()
+        .filter(|c| fits(fronting_format, &c.status))
+        .max_by_key(|c| c.score())
+        .map_or_else(
+            || {
+                SHOULDNT_HAPPEN_BUT_IT_DID
+                    .with_label_values(&["pick_best_scoring_status_within_length_limit"])
+                    .inc();
+                String::new()
+            },
+            |c| c.status.clone(),
+        )
 }
 
-pub fn string_unicode_codepoints_length<S: Into<String>>(s: S) -> usize {
-    s.into().chars().count() // doesn'T count graphemes, but I think that should still work well.
+fn fits(fronting_format: &FrontingFormat, status: &str) -> bool {
+    fronting_format
+        .max_length
+        .is_none_or(|l| fronting_format.length_metric.length(status) <= l)
 }
 
 // VRChat status messages does not display all UTF-8 characters.
 // This function removes all characters which are not of a specific encoding from the string.
+// Depending on the user's choice, they are transliterated beforehand or replaced by a placeholder.
 // We also trim the name, in case the cleanup made new spaces appear.
 #[must_use]
-pub fn clean_name_for_vrchat_status(dirty_name: &str) -> String {
-    dirty_name
-        .chars()
-        .filter(|&ch| {
-            let mut buf = [0u8; 4];
-            let s = ch.encode_utf8(&mut buf);
-            let mut out = [0u8; 20];
-            let (_, _, _, unsupported) = ISO_8859_15
-                .new_encoder()
-                .encode_from_utf8(s, &mut out, true);
-
-            !unsupported
-        })
-        .collect::<String>()
+pub fn clean_name_for_vrchat_status(
+    dirty_name: &str,
+    unsupported: UnsupportedCharacters,
+) -> String {
+    let name = match unsupported {
+        UnsupportedCharacters::Transliterate => transliterate_to_latin(dirty_name),
+        UnsupportedCharacters::Drop | UnsupportedCharacters::Placeholder => dirty_name.to_owned(),
+    };
+
+    let mut cleaned = String::new();
+    let mut previous_was_unsupported = false;
+    for ch in name.chars() {
+        let supported = is_supported_by_vrchat(ch);
+        if supported {
+            cleaned.push(ch);
+        } else if unsupported == UnsupportedCharacters::Placeholder && !previous_was_unsupported {
+            cleaned.push('?');
+        }
+        previous_was_unsupported = !supported;
+    }
+
+    cleaned
         .split_whitespace() // remove consecutive whitespace resulting from cleanup. also trims string.
         .collect::<Vec<&str>>()
         .join(" ")
 }
+
+fn is_supported_by_vrchat(ch: char) -> bool {
+    let mut buf = [0u8; 4];
+    let s = ch.encode_utf8(&mut buf);
+    let mut out = [0u8; 20];
+    let (_, _, _, unsupported) = ISO_8859_15
+        .new_encoder()
+        .encode_from_utf8(s, &mut out, true);
+
+    !unsupported
+}
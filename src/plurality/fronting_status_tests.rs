@@ -1,7 +1,12 @@
+use std::collections::HashMap;
+
 use crate::plurality::{
-    CleanForPlatform, Fronter, FrontingFormat, VRCHAT_MAX_ALLOWED_STATUS_LENGTH,
-    clean_name_for_vrchat_status, format_fronting_status, string_unicode_codepoints_length,
+    CleanForPlatform, DisplayNamePlatform, Fronter, FronterGroup, FrontingFormat, GroupCollapsing,
+    LengthMetric, PlatformOverrides, UnsupportedCharacters, VRCHAT_MAX_ALLOWED_STATUS_LENGTH,
+    clean_name_for_vrchat_status, format_fronting_status, fronting_format_with_overrides,
+    parse_status_templates, transliterate_to_latin,
 };
+use crate::test_support;
 
 fn mock_formatter_for_tests(
     prefix: &str,
@@ -13,8 +18,13 @@ fn mock_formatter_for_tests(
         prefix: prefix.to_owned(),
         status_if_no_fronters: no_fronts.to_owned(),
         truncate_names_to_length_if_status_too_long: name_truncate_to,
-        cleaning: CleanForPlatform::VRChat,
+        cleaning: CleanForPlatform::VRChat(UnsupportedCharacters::Drop),
         max_length: Some(max_length),
+        length_metric: LengthMetric::Utf16CodeUnits,
+        show_custom_status: false,
+        group_collapsing: GroupCollapsing::WhenTooLong,
+        display_name_platform: DisplayNamePlatform::VRChat,
+        status_templates: vec![],
     }
 }
 
@@ -24,14 +34,18 @@ fn mock_member_content(name: &str, vrchat_status_name: &str) -> Fronter {
         fronter_id: String::new(),
         name: name.to_string(),
         avatar_url: String::new(),
-        vrchat_status_name: if vrchat_status_name.is_empty() {
-            None
+        display_names: if vrchat_status_name.is_empty() {
+            HashMap::new()
         } else {
-            Some(vrchat_status_name.to_owned())
+            HashMap::from([(DisplayNamePlatform::VRChat, vrchat_status_name.to_owned())])
         },
         start_time: None,
         privacy_buckets: vec![],
+        groups: vec![],
+        is_custom_front: false,
         pluralkit_id: None,
+        pronouns: None,
+        custom_status: None,
     }
 }
 
@@ -59,6 +73,17 @@ fn test_format_vrchat_status_multiple_members_fit_long_string() {
     assert_eq!(format_fronting_status(&config, &fronts), "F: Alice, Bob");
 }
 
+#[test]
+fn test_format_status_without_prefix_has_no_leading_space() {
+    let config = mock_formatter_for_tests("", "N/A", 3, VRCHAT_MAX_ALLOWED_STATUS_LENGTH);
+    let fronts = vec![
+        mock_member_content("Alice", ""),
+        mock_member_content("Bob", ""),
+    ];
+    assert_eq!(format_fronting_status(&config, &fronts), "Alice, Bob");
+    assert_eq!(format_fronting_status(&config, &[]), "N/A");
+}
+
 #[test]
 fn test_format_vrchat_status_fits_short_string_not_long() {
     // VRCHAT_MAX_ALLOWED_STATUS_LENGTH is 23
@@ -83,10 +108,14 @@ fn test_format_vrchat_status_fits_truncated_string_not_short() {
         mock_member_content("Benjamin", ""),
         mock_member_content("Charlotte", ""),
     ];
-    // Long: "P: Alexander, Benjamin, Charlotte" 33 > 23
-    // Short: "P:Alexander,Benjamin,Charlotte" 31 > 23
-    // Truncated: "P:Ale,Ben,Cha" 14 <= 23
-    assert_eq!(format_fronting_status(&config, &fronts), "F:Ale,Ben,Cha");
+    // Long: "F: Alexander, Benjamin, Charlotte" 33 > 23
+    // Short: "F:Alexander,Benjamin,Charlotte" 30 > 23
+    // Truncated to 7: "F:Alexand,Benjami,Charlot" 25 > 23
+    // Truncated to 6: "F:Alexan,Benjam,Charlo" 22 <= 23
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F:Alexan,Benjam,Charlo"
+    );
 }
 
 #[test]
@@ -99,6 +128,41 @@ fn test_format_vrchat_status_uses_vrchat_status_name() {
     );
 }
 
+#[test]
+fn test_format_status_uses_display_name_of_its_platform_only() {
+    let mut fronter = mock_member_content("Alex😊", "VRChatName");
+    fronter
+        .display_names
+        .insert(DisplayNamePlatform::Discord, "Discord😊Name".to_owned());
+    let fronts = vec![fronter.clone(), mock_member_content("Bob😊", "")];
+
+    let discord_format = FrontingFormat {
+        cleaning: CleanForPlatform::NoClean,
+        display_name_platform: DisplayNamePlatform::Discord,
+        ..mock_formatter_for_tests("F:", "N/A", 3, 50)
+    };
+    assert_eq!(
+        format_fronting_status(&discord_format, &fronts),
+        "F: Discord😊Name, Bob😊"
+    );
+
+    let website_format = FrontingFormat {
+        cleaning: CleanForPlatform::NoClean,
+        display_name_platform: DisplayNamePlatform::Website,
+        ..mock_formatter_for_tests("F:", "N/A", 3, 50)
+    };
+    assert_eq!(
+        format_fronting_status(&website_format, &fronts),
+        "F: Alex😊, Bob😊"
+    );
+
+    let vrchat_format = mock_formatter_for_tests("F:", "N/A", 3, VRCHAT_MAX_ALLOWED_STATUS_LENGTH);
+    assert_eq!(
+        format_fronting_status(&vrchat_format, &fronts),
+        "F: VRChatName, Bob"
+    );
+}
+
 #[test]
 fn test_format_vrchat_status_cleans_names() {
     let config = mock_formatter_for_tests("F:", "N/A", 3, VRCHAT_MAX_ALLOWED_STATUS_LENGTH);
@@ -124,9 +188,12 @@ fn test_format_vrchat_status_complex_truncation_and_vrc_name() {
     // Cleaned names for status: LongNameOne, VRC11, AnotherVeryLong
     // Long: "F: LongNameOne, VRC11, AnotherVeryLong" 38 > 23
     // Short: "F:LongNameOne,VRC11,AnotherVeryLong" 36 > 23
-    // Truncated names: Long, VRC1, Anot
-    // Truncated string: "F:Long,VRC1,Anot" 17 <= 23
-    assert_eq!(format_fronting_status(&config, &fronts), "F:Long,VRC1,Anot");
+    // Only the longest names are truncated: LongNam, VRC11, Another
+    // Truncated string: "F:LongNam,VRC11,Another" 23 <= 23
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F:LongNam,VRC11,Another"
+    );
 }
 
 #[test]
@@ -139,59 +206,431 @@ fn test_format_status_truncation() {
     ];
     // Cleaned names for status: LongNameOne, VRC11, AnotherVeryLong
     // Truncated names: Long, VRC1, Anot
-    // Truncated string: "F:Long,VRC1,Anot" 17 > 10
+    // Truncated string: "F:Long,VRC1,Anot" 16 > 10
+    // First name only: "F:LongNameOne+2" 15 > 10
     // Count: "F: 3#" 5 <= 10
     assert_eq!(format_fronting_status(&config, &fronts), "F: 3#");
 }
 
 #[test]
-fn length_counts_codepoints_and_not_bytes() {
-    assert_eq!(string_unicode_codepoints_length("123"), 3);
-    assert_eq!(string_unicode_codepoints_length("é"), 1);
-    assert_eq!(string_unicode_codepoints_length("你好"), 2);
+fn length_counts_graphemes_or_code_units_and_not_bytes() {
+    for metric in [
+        LengthMetric::GraphemeClusters,
+        LengthMetric::Utf16CodeUnits,
+        LengthMetric::UnicodeScalars,
+    ] {
+        assert_eq!(metric.length("123"), 3);
+        assert_eq!(metric.length("é"), 1);
+        assert_eq!(metric.length("你好"), 2);
+    }
+
+    let family = "👩‍👩‍👧"; // three emoji joined by zero width joiners
+    let thumbs_up = "👍🏽"; // with skin tone modifier
+    let decomposed_e = "e\u{301}";
+    assert_eq!(LengthMetric::GraphemeClusters.length(family), 1);
+    assert_eq!(LengthMetric::GraphemeClusters.length(thumbs_up), 1);
+    assert_eq!(LengthMetric::GraphemeClusters.length(decomposed_e), 1);
+    assert_eq!(LengthMetric::Utf16CodeUnits.length(family), 8);
+    assert_eq!(LengthMetric::Utf16CodeUnits.length(thumbs_up), 4);
+    assert_eq!(LengthMetric::Utf16CodeUnits.length(decomposed_e), 2);
+    assert_eq!(LengthMetric::UnicodeScalars.length(family), 5);
+    assert_eq!(LengthMetric::UnicodeScalars.length(thumbs_up), 2);
+    assert_eq!(LengthMetric::UnicodeScalars.length(decomposed_e), 2);
+}
+
+#[test]
+fn test_format_status_measures_with_platform_metric_and_truncates_between_graphemes() {
+    let fronts = vec![
+        mock_member_content("👩‍👩‍👧Fam", ""),
+        mock_member_content("Bo👍🏽b", ""),
+    ];
+    let mut config = FrontingFormat {
+        cleaning: CleanForPlatform::NoClean,
+        length_metric: LengthMetric::GraphemeClusters,
+        ..mock_formatter_for_tests("F:", "N/A", 3, 13)
+    };
+
+    // "F: 👩‍👩‍👧Fam, Bo👍🏽b" is 13 grapheme clusters
+    assert_eq!(format_fronting_status(&config, &fronts), "F: 👩‍👩‍👧Fam, Bo👍🏽b");
+
+    // but 23 UTF-16 code units. Truncated to 3 grapheme clusters, the emoji are kept whole.
+    config.length_metric = LengthMetric::Utf16CodeUnits;
+    config.max_length = Some(19);
+    assert_eq!(format_fronting_status(&config, &fronts), "F:👩‍👩‍👧Fa,Bo👍🏽");
 }
 
 #[test]
 fn test_clean_name_for_vrchat_encoding_and_whitespace() {
     assert_eq!(
-        clean_name_for_vrchat_status("ValidName123!€ Špecial Chars Ž"),
+        clean_name_for_vrchat_status(
+            "ValidName123!€ Špecial Chars Ž",
+            UnsupportedCharacters::Drop
+        ),
         "ValidName123!€ Špecial Chars Ž",
         "Should keep all valid ISO_8859_15 characters"
     );
 
     assert_eq!(
-        clean_name_for_vrchat_status("Name😊With🚀Emojis❤️Symbols✅"),
+        clean_name_for_vrchat_status("Name😊With🚀Emojis❤️Symbols✅", UnsupportedCharacters::Drop),
         "NameWithEmojisSymbols",
         "Should remove emojis"
     );
 
     assert_eq!(
-        clean_name_for_vrchat_status("Héllo Wörld🎉"),
+        clean_name_for_vrchat_status("Héllo Wörld🎉", UnsupportedCharacters::Drop),
         "Héllo Wörld",
         "Should handle mixed valid and invalid characters"
     );
 
     assert_eq!(
-        clean_name_for_vrchat_status("  Trimmed  From  Name  "),
+        clean_name_for_vrchat_status("  Trimmed  From  Name  ", UnsupportedCharacters::Drop),
         "Trimmed From Name",
         "Should collapse consecutive spaces and trim"
     );
 
-    assert_eq!(clean_name_for_vrchat_status(""), "");
+    assert_eq!(
+        clean_name_for_vrchat_status("", UnsupportedCharacters::Drop),
+        ""
+    );
 
-    assert_eq!(clean_name_for_vrchat_status("😊🚀🎉"), "");
+    assert_eq!(
+        clean_name_for_vrchat_status("😊🚀🎉", UnsupportedCharacters::Drop),
+        ""
+    );
 
-    assert_eq!(clean_name_for_vrchat_status("   \t\n   "), "");
+    assert_eq!(
+        clean_name_for_vrchat_status("   \t\n   ", UnsupportedCharacters::Drop),
+        ""
+    );
 
     assert_eq!(
-        clean_name_for_vrchat_status("你好WorldПриветUser1"),
+        clean_name_for_vrchat_status("你好WorldПриветUser1", UnsupportedCharacters::Drop),
         "WorldUser1",
         "Should remove characters from other scripts like Hanzi or Cyrillic"
     );
 
     assert_eq!(
-        clean_name_for_vrchat_status("A 😊B C🚀D"),
+        clean_name_for_vrchat_status("A 😊B C🚀D", UnsupportedCharacters::Drop),
         "A B CD",
         "Should collapse spaces created by invalid characters"
     );
 }
+
+#[test]
+fn test_format_status_with_custom_status() {
+    let mut config = mock_formatter_for_tests("F:", "N/A", 3, 100);
+    let mut alex = mock_member_content("Alex", "");
+    alex.custom_status = Some("blurry".to_owned());
+    let mut bob = mock_member_content("Bob", "");
+    bob.custom_status = Some("  ".to_owned());
+    let fronts = vec![alex, bob, mock_member_content("Cleo", "")];
+
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F: Alex, Bob, Cleo"
+    );
+
+    config.show_custom_status = true;
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F: Alex (blurry), Bob, Cleo"
+    );
+}
+
+#[test]
+fn test_format_vrchat_status_with_custom_status_cleaned() {
+    let mut config = mock_formatter_for_tests("F:", "N/A", 3, VRCHAT_MAX_ALLOWED_STATUS_LENGTH);
+    config.show_custom_status = true;
+    let mut alex = mock_member_content("Alex", "");
+    alex.custom_status = Some("co-con 💭".to_owned());
+    let mut bob = mock_member_content("Bob", "");
+    bob.custom_status = Some("💭".to_owned());
+
+    assert_eq!(
+        format_fronting_status(&config, &[alex, bob]),
+        "F: Alex (co-con), Bob"
+    );
+}
+
+fn mock_group(group_id: &str, name: &str, members_count: usize) -> FronterGroup {
+    FronterGroup {
+        group_id: group_id.to_owned(),
+        name: name.to_owned(),
+        members_count,
+    }
+}
+
+fn mock_member_in_groups(name: &str, groups: &[&FronterGroup]) -> Fronter {
+    let mut fronter = mock_member_content(name, "");
+    fronter.groups = groups.iter().map(|&g| g.clone()).collect();
+    fronter
+}
+
+#[test]
+fn test_format_status_group_collapsing_when_too_long() {
+    let littles = mock_group("g1", "Littles", 5);
+    let protectors = mock_group("g2", "Protectors", 2);
+    let fronts = vec![
+        mock_member_in_groups("Amelie", &[&littles]),
+        mock_member_in_groups("Ravenna", &[&protectors]),
+        mock_member_in_groups("Benedikt", &[&littles]),
+        mock_member_in_groups("Sebastian", &[&protectors]),
+        mock_member_in_groups("Clementine", &[&littles]),
+        mock_member_in_groups("Alex", &[]),
+    ];
+
+    // everything fits, hence no collapsing
+    let config = mock_formatter_for_tests("F:", "N/A", 3, 100);
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F: Amelie, Ravenna, Benedikt, Sebastian, Clementine, Alex"
+    );
+
+    // not even truncated names fit: "F:Ameli,Raven,Bened,Sebas,Cleme,Alex" (36 chars)
+    let config = mock_formatter_for_tests("F:", "N/A", 5, 31);
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F: Littles ×3, Protectors, Alex"
+    );
+
+    let mut config = mock_formatter_for_tests("F:", "N/A", 5, 31);
+    config.group_collapsing = GroupCollapsing::Never;
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F: Amelie, Ravenna, Benedikt +3"
+    );
+}
+
+#[test]
+fn test_format_status_group_collapsing_always() {
+    let littles = mock_group("g1", "Littles 🧸", 5);
+    let everyone = mock_group("g0", "Everyone", 20);
+    let fronts = vec![
+        mock_member_in_groups("Amelie", &[&everyone, &littles]),
+        mock_member_in_groups("Alex", &[&everyone]),
+        mock_member_in_groups("Benedikt", &[&everyone, &littles]),
+        mock_member_in_groups("Clementine", &[]),
+    ];
+
+    let mut config = mock_formatter_for_tests("F:", "N/A", 3, 100);
+    config.group_collapsing = GroupCollapsing::Always;
+
+    // the group with the most fronters wins. single fronters aren't collapsed.
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F: Everyone ×3, Clementine"
+    );
+
+    let fronts = vec![
+        mock_member_in_groups("Amelie", &[&everyone, &littles]),
+        mock_member_in_groups("Benedikt", &[&everyone, &littles]),
+    ];
+    // on ties, the more specific group wins. the group name is cleaned for VRChat.
+    assert_eq!(format_fronting_status(&config, &fronts), "F: Littles ×2");
+}
+
+#[test]
+fn test_format_status_with_status_templates() {
+    let mut alice = mock_member_content("Alice", "");
+    alice.pronouns = Some("she/her".to_owned());
+    let mut bob = mock_member_content("Bob 💭", "");
+    bob.pronouns = Some("💭".to_owned());
+    let fronts = vec![alice, bob, mock_member_content("Claire", "")];
+
+    let mut config = mock_formatter_for_tests("F:", "nobody?", 3, 23);
+    config.status_templates = parse_status_templates(
+        "templates",
+        &[
+            "{prefix} {names} ({pronouns})".to_owned(),
+            "{names|first(2)|join(\"+\")} +{count}".to_owned(),
+        ],
+    )
+    .unwrap();
+
+    // the first template is too long, the second one fits. pronouns are cleaned for VRChat.
+    assert_eq!(format_fronting_status(&config, &fronts), "Alice+Bob +3");
+
+    config.max_length = Some(100);
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F: Alice, Bob, Claire (she/her)"
+    );
+
+    // the no fronters text is used as the only name
+    assert_eq!(format_fronting_status(&config, &[]), "F: nobody? ()");
+
+    // if no template fits, the built-in shapes are used
+    config.max_length = Some(10);
+    assert_eq!(format_fronting_status(&config, &fronts), "F:Alice+2");
+}
+
+#[test]
+fn test_transliterate_to_latin() {
+    assert_eq!(transliterate_to_latin("Сергей"), "Sergey");
+    assert_eq!(transliterate_to_latin("Щука и Юля"), "Shchuka i Yulya");
+    assert_eq!(transliterate_to_latin("Αθηνά"), "Athina");
+    assert_eq!(transliterate_to_latin("さくら"), "Sakura");
+    assert_eq!(transliterate_to_latin("きょうこ"), "Kyouko");
+    assert_eq!(transliterate_to_latin("ちゃん"), "Chan");
+    assert_eq!(transliterate_to_latin("がっこう"), "Gakkou");
+    assert_eq!(transliterate_to_latin("マッチャ"), "Matcha");
+    assert_eq!(transliterate_to_latin("ルーシー"), "Ruushii");
+    assert_eq!(transliterate_to_latin("서울"), "Seoul");
+    assert_eq!(transliterate_to_latin("민지 한국"), "Minji Hanguk");
+    assert_eq!(transliterate_to_latin("Littles 🧸"), "Littles :teddy_bear:");
+    // supported and unknown characters are kept as they are
+    assert_eq!(transliterate_to_latin("Ärger ok"), "Ärger ok");
+    assert_eq!(transliterate_to_latin("你好🦖"), "你好🦖");
+}
+
+#[test]
+fn test_clean_name_for_vrchat_with_unsupported_characters_handling() {
+    let name = "さくら Сергей 你好 Alex❤️";
+    assert_eq!(
+        clean_name_for_vrchat_status(name, UnsupportedCharacters::Drop),
+        "Alex"
+    );
+    assert_eq!(
+        clean_name_for_vrchat_status(name, UnsupportedCharacters::Transliterate),
+        "Sakura Sergey Alex:heart:"
+    );
+    assert_eq!(
+        clean_name_for_vrchat_status(name, UnsupportedCharacters::Placeholder),
+        "? ? ? Alex?"
+    );
+    assert_eq!(
+        clean_name_for_vrchat_status("Ünïcödé€", UnsupportedCharacters::Placeholder),
+        "Ünïcödé€"
+    );
+}
+
+#[test]
+fn test_format_vrchat_status_transliterates_names() {
+    let mut config = mock_formatter_for_tests("F:", "N/A", 3, VRCHAT_MAX_ALLOWED_STATUS_LENGTH);
+    let fronts = vec![
+        mock_member_content("さくら", ""),
+        mock_member_content("Сергей", ""),
+    ];
+
+    // by default, the names disappear completely
+    assert_eq!(format_fronting_status(&config, &fronts), "F: 2#");
+
+    config.cleaning = CleanForPlatform::VRChat(UnsupportedCharacters::Transliterate);
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F: Sakura, Sergey"
+    );
+}
+
+#[test]
+fn test_format_status_shows_first_names_and_drops_custom_fronts_first() {
+    let mut sleepy = mock_member_content("Sleepy", "");
+    sleepy.is_custom_front = true;
+    let fronts = vec![
+        sleepy,
+        mock_member_content("Amelie", ""),
+        mock_member_content("Benedikt", ""),
+        mock_member_content("Clementine", ""),
+    ];
+
+    // "F:Slee,Amel,Bene,Clem" shows more fronters than "F: Amelie, Benedikt +2"
+    let config = mock_formatter_for_tests("F:", "N/A", 3, VRCHAT_MAX_ALLOWED_STATUS_LENGTH);
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F:Slee,Amel,Bene,Clem"
+    );
+
+    // names can't be truncated further, hence only the first members are shown
+    let config = mock_formatter_for_tests("F:", "N/A", 6, VRCHAT_MAX_ALLOWED_STATUS_LENGTH);
+    assert_eq!(
+        format_fronting_status(&config, &fronts),
+        "F: Amelie, Benedikt +2"
+    );
+}
+
+/// A loop over random inputs from a fixed seed, such that a failure is reproducible.
+#[test]
+fn test_format_status_fits_and_keeps_prefix_in_seeded_random_loop() {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    const NAME_CHARACTERS: &[char] = &['a', 'B', 'é', ' ', 'Ж', 'さ', '😊', '€', '\u{200d}', '🏽'];
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let random_text = |rng: &mut StdRng, max_length: usize| -> String {
+        let length = rng.random_range(0..=max_length);
+        (0..length)
+            .map(|_| NAME_CHARACTERS[rng.random_range(0..NAME_CHARACTERS.len())])
+            .collect()
+    };
+    let groups = [
+        mock_group("g1", "Littles", 4),
+        mock_group("g2", "Protectors 🛡", 2),
+    ];
+
+    for _ in 0..2000 {
+        let prefix = random_text(&mut rng, 5);
+        let fronts: Vec<Fronter> = (0..rng.random_range(0..8))
+            .map(|_| {
+                let mut fronter = mock_member_content(&random_text(&mut rng, 15), "");
+                fronter.is_custom_front = rng.random_bool(0.3);
+                fronter.groups = groups
+                    .iter()
+                    .filter(|_| rng.random_bool(0.3))
+                    .cloned()
+                    .collect();
+                fronter
+            })
+            .collect();
+        // the count of fronters, e.g. "F: 3#", must always fit
+        let min_length = LengthMetric::Utf16CodeUnits.length(&prefix) + 3;
+        let max_length = rng.random_range(min_length..min_length + 30);
+
+        let mut config = mock_formatter_for_tests(
+            &prefix,
+            &random_text(&mut rng, 10),
+            rng.random_range(0..6),
+            max_length,
+        );
+        config.group_collapsing = [
+            GroupCollapsing::Never,
+            GroupCollapsing::WhenTooLong,
+            GroupCollapsing::Always,
+        ][rng.random_range(0..3)];
+        if rng.random_bool(0.5) {
+            config.cleaning = CleanForPlatform::NoClean;
+        }
+
+        let status = format_fronting_status(&config, &fronts);
+        assert!(
+            LengthMetric::Utf16CodeUnits.length(&status) <= max_length,
+            "'{status}' is longer than {max_length}"
+        );
+        assert!(
+            status.starts_with(&prefix),
+            "'{status}' doesn't start with '{prefix}'"
+        );
+    }
+}
+
+#[test]
+fn test_fronting_format_with_overrides_falls_back_to_global_settings() {
+    let mut config = test_support::create_test_config(&test_support::db_config());
+    config.status_prefix = "F:".to_owned();
+    config.status_no_fronts = "nobody".to_owned();
+    config.status_truncate_names_to = 3;
+
+    let format = fronting_format_with_overrides(
+        &config,
+        PlatformOverrides {
+            max_length: Some(20),
+            prefix: Some("P:".to_owned()),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(format.max_length, Some(20));
+    assert_eq!(format.prefix, "P:");
+    assert_eq!(format.status_if_no_fronters, "nobody");
+    assert_eq!(format.truncate_names_to_length_if_status_too_long, 3);
+    assert_eq!(format.display_name_platform, DisplayNamePlatform::Generic);
+}
@@ -0,0 +1,279 @@
+use rocket::futures::{SinkExt, StreamExt};
+use rocket::response::content::RawJson;
+use rocket::serde::json::Json;
+use rocket::{
+    Build, Request, Rocket, State,
+    http::Status,
+    request::{FromRequest, Outcome},
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// A stand-in for the Simply Plural API, such that the integration tests can run without real SP test tokens.
+///
+/// The system is read from the JSON file at `MOCK_SIMPLY_PLURAL_SYSTEM_FILE` and only the front history can be modified.
+/// Point `SIMPLY_PLURAL_BASE_URL` to `http://localhost:<port>/v1` and `SIMPLY_PLURAL_WEBSOCKET_URL` to `ws://localhost:<port>/v1/socket`.
+struct MockSystem {
+    token: String,
+    system: MockSystemFile,
+    front_history: Mutex<Vec<Value>>,
+    updates: broadcast::Sender<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MockSystemFile {
+    system_id: String,
+    members: Vec<Value>,
+    #[serde(rename = "customFronts")]
+    custom_fronts: Vec<Value>,
+    #[serde(rename = "customFields")]
+    custom_fields: Vec<Value>,
+    #[serde(default)]
+    groups: Vec<Value>,
+    friend: Value,
+    /// The front history at the start, e.g. with live fronts.
+    #[serde(default, rename = "frontHistory")]
+    front_history: Vec<Value>,
+}
+
+struct Authorized;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Authorized {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let expected_token = req.rocket().state::<MockSystem>().map(|s| s.token.as_str());
+        match req.headers().get_one("Authorization") {
+            Some(token) if Some(token) == expected_token => Outcome::Success(Self),
+            _ => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
+
+impl MockSystem {
+    fn documents_of_system(&self, system_id: &str, documents: &[Value]) -> Option<Json<Value>> {
+        (system_id == self.system.system_id).then(|| Json(json!(documents)))
+    }
+
+    fn broadcast_front_history_update(&self, operation_type: &str, document: &Value) {
+        let message = json!({
+            "msg": "update",
+            "target": "frontHistory",
+            "results": [{
+                "operationType": operation_type,
+                "id": document["id"],
+                "content": document["content"],
+            }],
+        });
+        // no subscribers is fine
+        let _ = self.updates.send(message.to_string());
+    }
+}
+
+#[get("/v1/me")]
+fn get_me(_auth: Authorized, system: &State<MockSystem>) -> Json<Value> {
+    Json(json!({"exists": true, "id": system.system.system_id, "content": {}}))
+}
+
+#[get("/v1/fronters")]
+fn get_fronters(_auth: Authorized, system: &State<MockSystem>) -> Result<Json<Value>, Status> {
+    let live: Vec<Value> = system
+        .front_history
+        .lock()
+        .map_err(|_| Status::InternalServerError)?
+        .iter()
+        .filter(|d| d["content"]["live"] == json!(true))
+        .cloned()
+        .collect();
+    Ok(Json(json!(live)))
+}
+
+#[get("/v1/members/<system_id>")]
+fn get_members(
+    _auth: Authorized,
+    system_id: &str,
+    system: &State<MockSystem>,
+) -> Option<Json<Value>> {
+    system.documents_of_system(system_id, &system.system.members)
+}
+
+#[get("/v1/customFronts/<system_id>")]
+fn get_custom_fronts(
+    _auth: Authorized,
+    system_id: &str,
+    system: &State<MockSystem>,
+) -> Option<Json<Value>> {
+    system.documents_of_system(system_id, &system.system.custom_fronts)
+}
+
+#[get("/v1/customFields/<system_id>")]
+fn get_custom_fields(
+    _auth: Authorized,
+    system_id: &str,
+    system: &State<MockSystem>,
+) -> Option<Json<Value>> {
+    system.documents_of_system(system_id, &system.system.custom_fields)
+}
+
+#[get("/v1/groups/<system_id>")]
+fn get_groups(
+    _auth: Authorized,
+    system_id: &str,
+    system: &State<MockSystem>,
+) -> Option<Json<Value>> {
+    system.documents_of_system(system_id, &system.system.groups)
+}
+
+#[get("/v1/friend/<system_id>/<_friend_id>")]
+fn get_friend(
+    _auth: Authorized,
+    system_id: &str,
+    _friend_id: &str,
+    system: &State<MockSystem>,
+) -> Option<Json<Value>> {
+    (system_id == system.system.system_id).then(|| Json(system.system.friend.clone()))
+}
+
+#[post("/v1/frontHistory/<id>", data = "<content>")]
+fn post_front_history(
+    _auth: Authorized,
+    id: &str,
+    content: Json<Value>,
+    system: &State<MockSystem>,
+) -> Result<Json<Value>, Status> {
+    let mut content = content.into_inner();
+    content["uid"] = json!(system.system.system_id);
+    let document = json!({"exists": true, "id": id, "content": content});
+
+    system
+        .front_history
+        .lock()
+        .map_err(|_| Status::InternalServerError)?
+        .push(document.clone());
+
+    system.broadcast_front_history_update("insert", &document);
+
+    Ok(Json(document))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+#[patch("/v1/frontHistory/<id>", data = "<changes>")]
+fn patch_front_history(
+    _auth: Authorized,
+    id: &str,
+    changes: Json<Value>,
+    system: &State<MockSystem>,
+) -> Result<Json<Value>, Status> {
+    let document = {
+        let mut front_history = system
+            .front_history
+            .lock()
+            .map_err(|_| Status::InternalServerError)?;
+        let document = front_history
+            .iter_mut()
+            .find(|d| d["id"] == json!(id))
+            .ok_or(Status::NotFound)?;
+        if let (Some(content), Some(changes)) =
+            (document["content"].as_object_mut(), changes.as_object())
+        {
+            // like SP, the start time of an existing front is kept
+            for (key, value) in changes.iter().filter(|(k, _)| *k != "startTime") {
+                content.insert(key.clone(), value.clone());
+            }
+        }
+        let document = document.clone();
+        drop(front_history);
+        document
+    };
+
+    system.broadcast_front_history_update("update", &document);
+
+    Ok(Json(document))
+}
+
+#[get("/v1/friends/requests/incoming")]
+const fn get_incoming_friend_requests(_auth: Authorized) -> RawJson<&'static str> {
+    RawJson("[]")
+}
+
+#[post("/v1/friends/request/respond/<_id>")]
+const fn post_friend_request_response(_auth: Authorized, _id: &str) -> Status {
+    Status::Ok
+}
+
+/// Implements the subset of the SP websocket protocol which `PluralSync` uses: authenticate, ping and update.
+#[get("/v1/socket")]
+fn get_socket(ws: rocket_ws::WebSocket, system: &State<MockSystem>) -> rocket_ws::Channel<'static> {
+    let token = system.token.clone();
+    let mut updates = system.updates.subscribe();
+
+    ws.channel(move |mut stream| {
+        Box::pin(async move {
+            let mut authenticated = false;
+            loop {
+                tokio::select! {
+                    message = stream.next() => {
+                        let Some(message) = message else { break };
+                        let reply = match message? {
+                            rocket_ws::Message::Text(ping) if ping == "ping" => "pong".to_owned(),
+                            rocket_ws::Message::Text(text) => {
+                                let request: Value = serde_json::from_str(&text).unwrap_or_default();
+                                if request["op"] == json!("authenticate") && request["token"] == json!(token) {
+                                    authenticated = true;
+                                    json!({"msg": "Successfully authenticated"}).to_string()
+                                } else {
+                                    json!({"msg": "Authentication violation: Token is missing or invalid. Goodbye :)"}).to_string()
+                                }
+                            }
+                            rocket_ws::Message::Close(_) => break,
+                            _ => continue,
+                        };
+                        stream.send(rocket_ws::Message::Text(reply)).await?;
+                    }
+                    update = updates.recv() => {
+                        match update {
+                            Ok(update) if authenticated => stream.send(rocket_ws::Message::Text(update)).await?,
+                            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => (),
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })
+    })
+}
+
+#[must_use]
+pub fn build(token: String, mut system: MockSystemFile) -> Rocket<Build> {
+    let (updates, _) = broadcast::channel(64);
+    let front_history = Mutex::new(std::mem::take(&mut system.front_history));
+
+    rocket::build()
+        .manage(MockSystem {
+            token,
+            system,
+            front_history,
+            updates,
+        })
+        .mount(
+            "/",
+            routes![
+                get_me,
+                get_fronters,
+                get_members,
+                get_custom_fronts,
+                get_custom_fields,
+                get_groups,
+                get_friend,
+                post_front_history,
+                patch_front_history,
+                get_incoming_friend_requests,
+                post_friend_request_response,
+                get_socket,
+            ],
+        )
+}
@@ -1,13 +1,29 @@
+mod fronter_order;
+mod fronting_source;
 pub mod fronting_status;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock_simply_plural;
 
 #[cfg(test)]
 mod fronting_status_tests;
 
+mod pluralkit;
+mod pluralkit_model;
 mod simply_plural;
+mod simply_plural_cache;
 mod simply_plural_model;
 mod simply_plural_websocket;
+mod status_template;
+mod transliteration;
 
+pub use fronter_order::*;
+pub use fronting_source::*;
 pub use fronting_status::*;
+pub use pluralkit::*;
+pub use pluralkit_model::*;
 pub use simply_plural::*;
+pub use simply_plural_cache::*;
 pub use simply_plural_model::*;
 pub use simply_plural_websocket::*;
+pub use status_template::*;
+pub use transliteration::*;
@@ -0,0 +1,408 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use pluralsync_base::updater::UpdaterStatus;
+use tokio::sync::Notify;
+
+use crate::{
+    int_counter_metric, int_gauge_metric,
+    plurality::{
+        self, Fronter, FrontingSource, PLURALKIT_USER_AGENT, PluralKitMember, PluralKitSwitchEntry,
+    },
+    users,
+};
+
+int_counter_metric!(PLURALKIT_FETCH_FRONTS_TOTAL_COUNTER);
+int_gauge_metric!(PLURALKIT_FETCH_FRONTS_FRONTERS_COUNT);
+int_counter_metric!(PLURALKIT_POLL_SWITCH_CHANGED_TOTAL_COUNTER);
+
+// PluralKit has no websocket. Hence we poll the latest switch to detect changes.
+pub const PLURALKIT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+// With the dispatch webhook, polling only catches up on missed events.
+pub const PLURALKIT_POLL_INTERVAL_WITH_DISPATCH: Duration = Duration::from_secs(300);
+
+pub struct PluralKitSource {
+    status: Mutex<UpdaterStatus>,
+    dispatch: Arc<Notify>,
+}
+
+impl Default for PluralKitSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PluralKitSource {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            status: Mutex::new(UpdaterStatus::Starting),
+            dispatch: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Notified on events of the PluralKit dispatch webhook. Ends the wait for the next poll early.
+    #[must_use]
+    pub fn dispatch_notifier(&self) -> Arc<Notify> {
+        self.dispatch.clone()
+    }
+}
+
+impl FrontingSource for PluralKitSource {
+    async fn fetch_fronters(&self, config: &users::UserConfigForUpdater) -> Result<Vec<Fronter>> {
+        let fronters = fetch_fronts_from_pluralkit(config).await;
+        plurality::record_fetch_status(&self.status, &fronters)?;
+        fronters
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn listen_for_changes<F>(
+        &self,
+        config: &users::UserConfigForUpdater,
+        on_change: impl Fn() -> F,
+    ) where
+        F: Future<Output = Result<()>>,
+    {
+        let user_id = &config.user_id;
+
+        if config.pluralkit_token.secret.is_empty() {
+            log::info!("PK poll '{user_id}': Not polling, because token is not set.");
+            return;
+        }
+
+        let poll_interval = if config.pluralkit_dispatch_signing_token.is_some() {
+            PLURALKIT_POLL_INTERVAL_WITH_DISPATCH
+        } else {
+            PLURALKIT_POLL_INTERVAL
+        };
+
+        // None means, that we don't know the current switch (yet) and hence must assume a change.
+        // The members are compared as well, because a switch can be edited after its creation.
+        let mut last_seen_switch: Option<Option<(String, Vec<String>)>> = None;
+
+        loop {
+            match pluralkit_http_get_latest_switch(config).await {
+                Ok(latest_switch) => {
+                    let latest_switch = latest_switch.map(|s| (s.id, s.members));
+                    if last_seen_switch.as_ref() != Some(&latest_switch) {
+                        log::info!("PK poll '{user_id}': switch changed to {latest_switch:?}");
+                        PLURALKIT_POLL_SWITCH_CHANGED_TOTAL_COUNTER
+                            .with_label_values(&[&user_id.to_string()])
+                            .inc();
+                        match on_change().await {
+                            Ok(()) => last_seen_switch = Some(latest_switch),
+                            Err(e) => log::warn!("PK poll '{user_id}': on_change failed: {e}"),
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::warn!("PK poll '{user_id}': polling latest switch failed: {e}");
+                    last_seen_switch = None;
+                }
+            }
+
+            let dispatched = tokio::time::timeout(poll_interval, self.dispatch.notified())
+                .await
+                .is_ok();
+            if dispatched {
+                // e.g. a renamed member doesn't change the switch
+                log::info!("PK poll '{user_id}': dispatch event received");
+                last_seen_switch = None;
+            }
+        }
+    }
+
+    fn status(&self) -> Result<UpdaterStatus> {
+        Ok(self
+            .status
+            .lock()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .clone())
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub async fn fetch_fronts_from_pluralkit(
+    config: &users::UserConfigForUpdater,
+) -> Result<Vec<Fronter>> {
+    let user_id = &config.user_id;
+
+    log::info!("# | fetch_fronts_from_pluralkit | {user_id}");
+
+    PLURALKIT_FETCH_FRONTS_TOTAL_COUNTER
+        .with_label_values(&[&user_id.to_string()])
+        .inc();
+
+    let Some(latest_switch) = pluralkit_http_get_latest_switch(config).await? else {
+        PLURALKIT_FETCH_FRONTS_FRONTERS_COUNT
+            .with_label_values(&[&user_id.to_string()])
+            .set(0);
+        return Ok(vec![]);
+    };
+
+    let members = pluralkit_http_get_members(config).await?;
+
+    let fronters = fronters_from_switch(&latest_switch, members);
+
+    for f in &fronters {
+        log::info!("# | fetch_fronts_from_pluralkit | {user_id} | fronter[*] {f:?}");
+    }
+
+    PLURALKIT_FETCH_FRONTS_FRONTERS_COUNT
+        .with_label_values(&[&user_id.to_string()])
+        .set(fronters.len() as i64);
+
+    Ok(fronters)
+}
+
+// Keeps the order of the switch, as PluralKit users order their fronters deliberately.
+// Members with private visibility are never shown.
+// The Simply Plural visibility settings (e.g. show_members_non_archived) don't apply, as PluralKit has no archive.
+fn fronters_from_switch(
+    switch: &PluralKitSwitchEntry,
+    members: Vec<PluralKitMember>,
+) -> Vec<Fronter> {
+    let mut members = members;
+
+    switch
+        .members
+        .iter()
+        .filter_map(|member_id| {
+            let position = members.iter().position(|m| &m.id == member_id)?;
+            Some(members.swap_remove(position))
+        })
+        .filter(|m| !m.is_private())
+        .map(|m| {
+            let mut fronter = Fronter::from(m);
+            fronter.start_time = Some(switch.timestamp);
+            fronter
+        })
+        .collect()
+}
+
+pub async fn pluralkit_http_get_latest_switch(
+    config: &users::UserConfigForUpdater,
+) -> Result<Option<PluralKitSwitchEntry>> {
+    log::info!("# | pluralkit_http_get_latest_switch | {}", config.user_id);
+
+    let switches_url = format!(
+        "{}/systems/@me/switches?limit=1",
+        &config.pluralkit_base_url
+    );
+    let response = config
+        .client
+        .get(&switches_url)
+        .header("Authorization", &config.pluralkit_token.secret)
+        .header("User-Agent", PLURALKIT_USER_AGENT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let switches: Vec<PluralKitSwitchEntry> = serde_json::from_str(&response).inspect_err(|e| {
+        log::warn!(
+            "# | pluralkit_http_get_latest_switch | {} | {} | input: {}",
+            config.user_id,
+            e,
+            response.chars().take(500).collect::<String>()
+        );
+    })?;
+
+    Ok(switches.into_iter().next())
+}
+
+pub async fn pluralkit_http_get_members(
+    config: &users::UserConfigForUpdater,
+) -> Result<Vec<PluralKitMember>> {
+    log::info!("# | pluralkit_http_get_members | {}", config.user_id);
+
+    let members_url = format!("{}/systems/@me/members", &config.pluralkit_base_url);
+    let response = config
+        .client
+        .get(&members_url)
+        .header("Authorization", &config.pluralkit_token.secret)
+        .header("User-Agent", PLURALKIT_USER_AGENT)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let members = serde_json::from_str(&response).inspect_err(|e| {
+        log::warn!(
+            "# | pluralkit_http_get_members | {} | {} | input: {}",
+            config.user_id,
+            e,
+            response.chars().take(500).collect::<String>()
+        );
+    })?;
+
+    Ok(members)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::database::Decrypted;
+    use crate::plurality::{FrontingSourceKind, PluralKitDispatchEvent};
+    use crate::test_support;
+    use crate::users::UserConfigDbEntries;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const MOCK_TOKEN: &str = "pk_mock_token";
+
+    /// Minimal stand-in for the PluralKit API. Answers each known request with the given JSON body.
+    async fn start_mock_pluralkit_server(routes: Vec<(&'static str, String)>) -> String {
+        let (base_url, _) = test_support::start_mock_http_server(move |request| {
+            match routes.iter().find(|(r, _)| *r == request.request_line()) {
+                _ if request.header("authorization") != Some(MOCK_TOKEN) => {
+                    ("401 Unauthorized", String::from("{}"))
+                }
+                Some((_, body)) => ("200 OK", body.clone()),
+                None => ("404 Not Found", String::from("{}")),
+            }
+        })
+        .await;
+        base_url
+    }
+
+    fn create_test_config(base_url: &str, token: &str) -> users::UserConfigForUpdater {
+        let mut config = test_support::create_test_config(&UserConfigDbEntries {
+            fronting_source: FrontingSourceKind::PluralKit,
+            pluralkit_token: Some(Decrypted {
+                secret: token.to_owned(),
+            }),
+            ..Default::default()
+        });
+        base_url.clone_into(&mut config.pluralkit_base_url);
+        config
+    }
+
+    fn mock_members_json() -> String {
+        r#"[
+            {"id": "aaaaa", "name": "Annalea", "display_name": "Anna", "avatar_url": "https://example.com/a.png", "pronouns": "she/her", "privacy": {"visibility": "public"}},
+            {"id": "bbbbb", "name": "Borgn", "display_name": null, "avatar_url": null, "pronouns": null, "privacy": {"visibility": "public"}},
+            {"id": "ccccc", "name": "Hidden", "privacy": {"visibility": "private"}},
+            {"id": "ddddd", "name": "Dorian"}
+        ]"#
+        .to_owned()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_fronters_from_mock_pluralkit() {
+        let base_url = start_mock_pluralkit_server(vec![
+            (
+                "GET /systems/@me/switches?limit=1",
+                r#"[{"id": "switch-1", "timestamp": "2025-01-02T03:04:05Z", "members": ["bbbbb", "ccccc", "aaaaa"]}]"#.to_owned(),
+            ),
+            ("GET /systems/@me/members", mock_members_json()),
+        ])
+        .await;
+        let config = create_test_config(&base_url, MOCK_TOKEN);
+        let source = PluralKitSource::new();
+
+        let fronters = source.fetch_fronters(&config).await.unwrap();
+
+        let names: Vec<&str> = fronters.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["Borgn", "Anna"]);
+        assert_eq!(fronters[1].pronouns.as_deref(), Some("she/her"));
+        assert_eq!(fronters[1].avatar_url, "https://example.com/a.png");
+        assert_eq!(fronters[1].pluralkit_id.as_deref(), Some("aaaaa"));
+        assert_eq!(
+            fronters[0].start_time,
+            Some("2025-01-02T03:04:05Z".parse().unwrap())
+        );
+        assert!(matches!(source.status().unwrap(), UpdaterStatus::Running));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_fronters_from_mock_pluralkit_without_switches() {
+        let base_url = start_mock_pluralkit_server(vec![
+            ("GET /systems/@me/switches?limit=1", "[]".to_owned()),
+            ("GET /systems/@me/members", mock_members_json()),
+        ])
+        .await;
+        let config = create_test_config(&base_url, MOCK_TOKEN);
+
+        let fronters = PluralKitSource::new()
+            .fetch_fronters(&config)
+            .await
+            .unwrap();
+
+        assert!(fronters.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_fronters_from_mock_pluralkit_with_invalid_token() {
+        let base_url = start_mock_pluralkit_server(vec![]).await;
+        let config = create_test_config(&base_url, "wrong_token");
+        let source = PluralKitSource::new();
+
+        let result = source.fetch_fronters(&config).await;
+
+        assert!(result.is_err());
+        assert!(matches!(source.status().unwrap(), UpdaterStatus::Error(_)));
+    }
+
+    #[test]
+    fn test_dispatch_event_relevance() {
+        let event: PluralKitDispatchEvent = serde_json::from_str(
+            r#"{"type": "CREATE_SWITCH", "signing_token": "dispatch_token", "system_id": "some-uuid", "id": "switch-2"}"#,
+        )
+        .unwrap();
+
+        assert!(event.might_change_fronters());
+
+        let ping = PluralKitDispatchEvent {
+            event_type: "PING".to_owned(),
+            signing_token: "dispatch_token".to_owned(),
+        };
+        assert!(!ping.might_change_fronters());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_ends_the_wait_for_the_next_poll() {
+        let base_url = start_mock_pluralkit_server(vec![(
+            "GET /systems/@me/switches?limit=1",
+            r#"[{"id": "switch-1", "timestamp": "2025-01-02T03:04:05Z", "members": ["aaaaa"]}]"#
+                .to_owned(),
+        )])
+        .await;
+        let mut config = create_test_config(&base_url, MOCK_TOKEN);
+        config.pluralkit_dispatch_signing_token = Some(Decrypted {
+            secret: "dispatch_token".to_owned(),
+        });
+        let source = PluralKitSource::new();
+        let notifier = source.dispatch_notifier();
+
+        let calls = AtomicUsize::new(0);
+        let on_change = || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { anyhow::Ok(()) }
+        };
+        let calls = &calls;
+        let wait_for_calls = |count: usize| async move {
+            while calls.load(Ordering::SeqCst) < count {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        let dispatch = async {
+            wait_for_calls(1).await;
+            // the switch is unchanged, but e.g. a member might have been renamed
+            notifier.notify_one();
+            wait_for_calls(2).await;
+        };
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::select! {
+                () = source.listen_for_changes(&config, on_change) => {},
+                () = dispatch => {},
+            }
+        })
+        .await
+        .unwrap();
+    }
+}
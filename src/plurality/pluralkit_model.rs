@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::plurality::Fronter;
+
+pub const PLURALKIT_BASE_URL: &str = "https://api.pluralkit.me/v2";
+
+pub const PLURALKIT_USER_AGENT: &str = concat!(
+    "PluralSync/",
+    env!("CARGO_PKG_VERSION"),
+    " Discord: ",
+    env!("USER_AGENT_DISCORD_USERNAME")
+);
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PluralKitSwitchEntry {
+    pub id: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    // the PluralKit member IDs in the order defined in PluralKit
+    pub members: Vec<String>,
+}
+
+/// Event sent by the PluralKit dispatch webhook: <https://pluralkit.me/api/dispatch/>
+#[derive(Deserialize, Debug, Clone)]
+pub struct PluralKitDispatchEvent {
+    // e.g. "PING" or "CREATE_SWITCH"
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub signing_token: String,
+}
+
+impl PluralKitDispatchEvent {
+    /// Whether the current fronters might have changed, e.g. because of a new switch or a renamed member.
+    #[must_use]
+    pub fn might_change_fronters(&self) -> bool {
+        matches!(
+            self.event_type.as_str(),
+            "CREATE_SWITCH"
+                | "UPDATE_SWITCH"
+                | "DELETE_SWITCH"
+                | "DELETE_ALL_SWITCHES"
+                | "UPDATE_MEMBER"
+                | "DELETE_MEMBER"
+        )
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PluralKitMember {
+    // the short 5/6-letter PluralKit member ID
+    pub id: String,
+    pub name: String,
+
+    #[serde(default)]
+    pub display_name: Option<String>,
+
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+
+    #[serde(default)]
+    pub pronouns: Option<String>,
+
+    // hex color without a leading '#'
+    #[serde(default)]
+    pub color: Option<String>,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    // only present, if the request is authenticated as the owning system
+    #[serde(default)]
+    pub privacy: Option<PluralKitMemberPrivacy>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PluralKitMemberPrivacy {
+    #[serde(default)]
+    pub visibility: Option<String>,
+}
+
+impl PluralKitMember {
+    #[must_use]
+    pub fn is_private(&self) -> bool {
+        self.privacy
+            .as_ref()
+            .and_then(|p| p.visibility.as_deref())
+            .is_some_and(|v| v == "private")
+    }
+}
+
+impl From<PluralKitMember> for Fronter {
+    fn from(m: PluralKitMember) -> Self {
+        Self {
+            fronter_id: m.id.clone(),
+            name: m.display_name.unwrap_or(m.name),
+            avatar_url: m.avatar_url.unwrap_or_default(),
+            display_names: HashMap::new(),
+            pluralkit_id: Some(m.id),
+            pronouns: m.pronouns,
+            custom_status: None,
+            start_time: None,
+            privacy_buckets: vec![],
+            groups: vec![],
+            is_custom_front: false,
+        }
+    }
+}
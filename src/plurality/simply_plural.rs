@@ -1,13 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
 
 use anyhow::{Result, anyhow};
+use pluralsync_base::updater::UpdaterStatus;
 
 use crate::{
     int_counter_metric, int_gauge_metric,
     plurality::{
-        CustomField, CustomFront, Friend, FrontEntry, Fronter,
-        GLOBAL_PLURALSYNC_ON_SIMPLY_PLURAL_USER_ID, Member,
-        SIMPLY_PLURAL_VRCHAT_STATUS_NAME_FIELD_NAME,
+        self, CustomField, CustomFront, DisplayNamePlatform, Friend, FrontEntry, Fronter,
+        FronterGroup, FrontingSource, GLOBAL_PLURALSYNC_ON_SIMPLY_PLURAL_USER_ID, Group,
+        GroupCollapsing, Member, SimplyPluralSystemCache, SystemUser,
     },
     users::{self, PrivacyFineGrained},
 };
@@ -17,9 +19,118 @@ int_gauge_metric!(SIMPLY_PLURAL_FETCH_FRONTS_FRONTERS_COUNT);
 int_gauge_metric!(SIMPLY_PLURAL_FETCH_FRONTS_ACTIVE_MEMBERS_COUNT);
 int_gauge_metric!(SIMPLY_PLURAL_FETCH_FRONTS_ARCHIVED_MEMBERS_COUNT);
 int_gauge_metric!(SIMPLY_PLURAL_FETCH_FRONTS_CUSTOM_FRONTS_COUNT);
+int_gauge_metric!(SIMPLY_PLURAL_FETCH_FRONTS_GROUPS_COUNT);
+int_counter_metric!(UPDATER_MANAGER_SIMPLY_PLURAL_WEBSOCKET_RELEVANT_CHANGE_MESSAGE_COUNT);
+
+pub struct SimplyPluralSource {
+    status: Mutex<UpdaterStatus>,
+    cache: Mutex<SimplyPluralSystemCache>,
+}
+
+impl Default for SimplyPluralSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimplyPluralSource {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            status: Mutex::new(UpdaterStatus::Starting),
+            cache: Mutex::new(SimplyPluralSystemCache::new()),
+        }
+    }
+}
+
+impl FrontingSource for SimplyPluralSource {
+    async fn fetch_fronters(&self, config: &users::UserConfigForUpdater) -> Result<Vec<Fronter>> {
+        let fronters = fetch_fronts(config, &self.cache).await;
+        plurality::record_fetch_status(&self.status, &fronters)?;
+        fronters
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn listen_for_changes<F>(
+        &self,
+        config: &users::UserConfigForUpdater,
+        on_change: impl Fn() -> F,
+    ) where
+        F: Future<Output = Result<()>>,
+    {
+        let user_id = &config.user_id;
+
+        if config.simply_plural_token.secret.is_empty() {
+            log::info!("SP WS '{user_id}': Not creating websocket, because token is not set.");
+            return;
+        }
+
+        plurality::auto_reconnecting_websocket_client_to_simply_plural(
+            &user_id.to_string(),
+            &config.simply_plural_websocket_url,
+            &config.simply_plural_token.secret,
+            async |message| {
+                let applied = match plurality::parse_simply_plural_websocket_event(&message) {
+                    Ok(event) => self
+                        .cache
+                        .lock()
+                        .map_err(|e| anyhow!(e.to_string()))?
+                        .apply(event),
+                    Err(e) => {
+                        log::warn!("SP WS payload '{user_id}': not applicable ({e}). Refetching.");
+                        vec![]
+                    }
+                };
+                for collection in
+                    plurality::invalidated_collections_based_on_simply_plural_websocket_event(
+                        &message,
+                    )?
+                    .into_iter()
+                    .filter(|c| !applied.contains(c))
+                {
+                    log::info!("SP WS payload '{user_id}': invalidating cached {collection:?}");
+                    self.cache
+                        .lock()
+                        .map_err(|e| anyhow!(e.to_string()))?
+                        .invalidate(collection);
+                }
+                let changed =
+                    plurality::relevantly_changed_based_on_simply_plural_websocket_event(&message)?;
+                log::info!("SP WS payload '{user_id}': +{changed}");
+                UPDATER_MANAGER_SIMPLY_PLURAL_WEBSOCKET_RELEVANT_CHANGE_MESSAGE_COUNT
+                    .with_label_values(&[&user_id.to_string()])
+                    .inc();
+                if changed {
+                    on_change().await?;
+                }
+                Ok(())
+            },
+            async || {
+                // events might have been missed while we were disconnected
+                self.cache
+                    .lock()
+                    .map_err(|e| anyhow!(e.to_string()))?
+                    .invalidate_all();
+                on_change().await
+            },
+        )
+        .await;
+    }
+
+    fn status(&self) -> Result<UpdaterStatus> {
+        Ok(self
+            .status
+            .lock()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .clone())
+    }
+}
 
 #[allow(clippy::cast_possible_wrap)]
-pub async fn fetch_fronts(config: &users::UserConfigForUpdater) -> Result<Vec<Fronter>> {
+pub async fn fetch_fronts(
+    config: &users::UserConfigForUpdater,
+    cache: &Mutex<SimplyPluralSystemCache>,
+) -> Result<Vec<Fronter>> {
     let user_id = &config.user_id;
 
     log::info!("# | fetch_fronts | {user_id}");
@@ -28,7 +139,13 @@ pub async fn fetch_fronts(config: &users::UserConfigForUpdater) -> Result<Vec<Fr
         .with_label_values(&[&user_id.to_string()])
         .inc();
 
-    let front_entries = simply_plural_http_request_get_fronters(config).await?;
+    let front_entries = plurality::get_or_fetch(
+        cache,
+        user_id,
+        SimplyPluralSystemCache::front_entries,
+        simply_plural_http_request_get_fronters(config),
+    )
+    .await?;
 
     if front_entries.is_empty() {
         SIMPLY_PLURAL_FETCH_FRONTS_FRONTERS_COUNT
@@ -39,10 +156,30 @@ pub async fn fetch_fronts(config: &users::UserConfigForUpdater) -> Result<Vec<Fr
 
     let system_id = &front_entries[0].content.system_id.clone();
 
-    let vrcsn_field_id = get_vrchat_status_name_field_id(config, system_id).await?;
+    cache
+        .lock()
+        .map_err(|e| anyhow!(e.to_string()))?
+        .ensure_system(system_id);
+
+    let display_name_field_ids = if config.display_name_fields.is_empty() {
+        HashMap::new()
+    } else {
+        plurality::get_or_fetch(
+            cache,
+            user_id,
+            SimplyPluralSystemCache::display_name_field_ids,
+            get_display_name_field_ids(config, system_id),
+        )
+        .await?
+    };
 
-    let frontables =
-        get_members_and_custom_fronters_by_privacy_rules(system_id, vrcsn_field_id, config).await?;
+    let frontables = get_members_and_custom_fronters_by_privacy_rules(
+        system_id,
+        &display_name_field_ids,
+        config,
+        cache,
+    )
+    .await?;
 
     let fronters = filter_frontables_by_front_entries(front_entries, frontables);
 
@@ -57,6 +194,42 @@ pub async fn fetch_fronts(config: &users::UserConfigForUpdater) -> Result<Vec<Fr
     Ok(fronters)
 }
 
+/// All members which may be shown according to the privacy rules, regardless of whether they are fronting.
+///
+/// Unlike `fetch_fronts`, this doesn't rely on front entries for the system ID, because there may be none.
+pub async fn fetch_members_by_privacy_rules(
+    config: &users::UserConfigForUpdater,
+) -> Result<Vec<Member>> {
+    log::info!("# | fetch_members_by_privacy_rules | {}", config.user_id);
+
+    let system_id = simply_plural_http_get_system_id(config).await?;
+
+    let all_members = simply_plural_http_get_members(config, &system_id).await?;
+
+    let allowed_buckets = get_allowed_privacy_buckets(
+        &system_id,
+        config,
+        &Mutex::new(SimplyPluralSystemCache::new()),
+    )
+    .await?;
+
+    let members: Vec<Member> = all_members
+        .into_iter()
+        .filter(|m| show_member_according_to_privacy_rules(config, m))
+        .filter(|m| {
+            is_allowed_by_fine_grained_privacy(allowed_buckets.as_ref(), &m.content.privacy_buckets)
+        })
+        .collect();
+
+    log::info!(
+        "# | fetch_members_by_privacy_rules | {} | {} members",
+        config.user_id,
+        members.len()
+    );
+
+    Ok(members)
+}
+
 const fn show_member_according_to_privacy_rules(
     config: &users::UserConfigForUpdater,
     member_with_content: &Member,
@@ -76,10 +249,17 @@ const fn show_member_according_to_privacy_rules(
 #[allow(clippy::cast_possible_wrap)]
 async fn get_members_and_custom_fronters_by_privacy_rules(
     system_id: &str,
-    vrcsn_field_id: Option<String>,
+    display_name_field_ids: &HashMap<DisplayNamePlatform, String>,
     config: &users::UserConfigForUpdater,
+    cache: &Mutex<SimplyPluralSystemCache>,
 ) -> Result<Vec<Fronter>> {
-    let all_members: Vec<Member> = simply_plural_http_get_members(config, system_id).await?;
+    let all_members: Vec<Member> = plurality::get_or_fetch(
+        cache,
+        &config.user_id,
+        SimplyPluralSystemCache::members,
+        simply_plural_http_get_members(config, system_id),
+    )
+    .await?;
 
     let active_members_count = all_members.iter().filter(|m| !m.content.archived).count() as i64;
 
@@ -96,7 +276,13 @@ async fn get_members_and_custom_fronters_by_privacy_rules(
         .filter(|m| show_member_according_to_privacy_rules(config, m));
 
     let all_custom_fronts: Vec<CustomFront> = if config.show_custom_fronts {
-        let custom_fronts = simply_plural_http_get_custom_fronts(config, system_id).await?;
+        let custom_fronts = plurality::get_or_fetch(
+            cache,
+            &config.user_id,
+            SimplyPluralSystemCache::custom_fronts,
+            simply_plural_http_get_custom_fronts(config, system_id),
+        )
+        .await?;
 
         SIMPLY_PLURAL_FETCH_FRONTS_CUSTOM_FRONTS_COUNT
             .with_label_values(&[&config.user_id.to_string()])
@@ -107,35 +293,81 @@ async fn get_members_and_custom_fronters_by_privacy_rules(
         vec![]
     };
 
-    let all_frontables: Vec<Fronter> = privacy_filtered_members
+    let all_frontables = privacy_filtered_members
         .into_iter()
         .map(|m| {
             let mut enriched_member = m;
             enriched_member
                 .content
-                .vrcsn_field_id
-                .clone_from(&vrcsn_field_id);
+                .display_name_field_ids
+                .clone_from(display_name_field_ids);
             enriched_member
         })
         .map(Fronter::from)
-        .chain(all_custom_fronts.into_iter().map(Fronter::from))
-        .collect();
+        .chain(all_custom_fronts.into_iter().map(Fronter::from));
+
+    let allowed_buckets = get_allowed_privacy_buckets(system_id, config, cache).await?;
+
+    let groups: Vec<Group> = if config.status_group_collapsing == GroupCollapsing::Never {
+        vec![]
+    } else {
+        let groups = plurality::get_or_fetch(
+            cache,
+            &config.user_id,
+            SimplyPluralSystemCache::groups,
+            simply_plural_http_get_groups(config, system_id),
+        )
+        .await?;
 
-    let fine_grained_filtered_frontables =
-        filter_frontables_by_fine_grained_privacy(system_id, config, all_frontables).await?;
+        SIMPLY_PLURAL_FETCH_FRONTS_GROUPS_COUNT
+            .with_label_values(&[&config.user_id.to_string()])
+            .set(groups.len() as i64);
+
+        groups
+            .into_iter()
+            .filter(|g| {
+                is_allowed_by_fine_grained_privacy(
+                    allowed_buckets.as_ref(),
+                    &g.content.privacy_buckets,
+                )
+            })
+            .collect()
+    };
+
+    let fine_grained_filtered_frontables_with_groups = all_frontables
+        .into_iter()
+        .filter(|f| {
+            is_allowed_by_fine_grained_privacy(allowed_buckets.as_ref(), &f.privacy_buckets)
+        })
+        .map(|mut f| {
+            f.groups = groups
+                .iter()
+                .filter(|g| g.content.members.contains(&f.fronter_id))
+                .map(FronterGroup::from)
+                .collect();
+            f
+        })
+        .collect();
 
-    Ok(fine_grained_filtered_frontables)
+    Ok(fine_grained_filtered_frontables_with_groups)
 }
 
-async fn filter_frontables_by_fine_grained_privacy(
+/// The privacy buckets of which members, custom fronts and groups may be shown. None, if everything may be shown.
+async fn get_allowed_privacy_buckets(
     system_id: &str,
     config: &users::UserConfigForUpdater,
-    all_frontables: Vec<Fronter>,
-) -> Result<Vec<Fronter>> {
+    cache: &Mutex<SimplyPluralSystemCache>,
+) -> Result<Option<HashSet<String>>> {
     let allowed_buckets = match config.privacy_fine_grained {
-        PrivacyFineGrained::NoFineGrained => return Ok(all_frontables),
+        PrivacyFineGrained::NoFineGrained => return Ok(None),
         PrivacyFineGrained::ViaFriend => {
-            simply_plural_http_request_get_pluralsync_assigned_buckets(config, system_id).await?
+            plurality::get_or_fetch(
+                cache,
+                &config.user_id,
+                SimplyPluralSystemCache::friend_buckets,
+                simply_plural_http_request_get_pluralsync_assigned_buckets(config, system_id),
+            )
+            .await?
         }
         PrivacyFineGrained::ViaPrivacyBuckets => config
             .privacy_fine_grained_buckets
@@ -146,16 +378,14 @@ async fn filter_frontables_by_fine_grained_privacy(
             .collect(),
     };
 
-    let privacy_bucket_filtered = all_frontables
-        .into_iter()
-        .filter(|f| {
-            f.privacy_buckets
-                .iter()
-                .any(|b| allowed_buckets.contains(b))
-        })
-        .collect();
+    Ok(Some(allowed_buckets))
+}
 
-    Ok(privacy_bucket_filtered)
+fn is_allowed_by_fine_grained_privacy(
+    allowed_buckets: Option<&HashSet<String>>,
+    privacy_buckets: &[String],
+) -> bool {
+    allowed_buckets.is_none_or(|allowed| privacy_buckets.iter().any(|b| allowed.contains(b)))
 }
 
 #[allow(clippy::needless_pass_by_value)]
@@ -173,6 +403,9 @@ fn filter_frontables_by_front_entries(
                     let mut fronter_with_start_time = f.clone();
                     fronter_with_start_time.start_time = Some(fe.content.start_time);
                     fronter_with_start_time
+                        .custom_status
+                        .clone_from(&fe.content.custom_status);
+                    fronter_with_start_time
                 })
         })
         .collect();
@@ -180,7 +413,7 @@ fn filter_frontables_by_front_entries(
     fronters
 }
 
-async fn simply_plural_http_request_get_fronters(
+pub async fn simply_plural_http_request_get_fronters(
     config: &users::UserConfigForUpdater,
 ) -> Result<Vec<FrontEntry>> {
     log::info!(
@@ -211,11 +444,13 @@ async fn simply_plural_http_request_get_fronters(
     Ok(result)
 }
 
-async fn get_vrchat_status_name_field_id(
+/// Resolves the configured display name custom fields of all platforms to their field IDs.
+/// Platforms whose custom field doesn't exist in the system are left out.
+async fn get_display_name_field_ids(
     config: &users::UserConfigForUpdater,
     system_id: &String,
-) -> Result<Option<String>> {
-    log::info!("# | get_vrchat_status_name_field_id | {}", config.user_id);
+) -> Result<HashMap<DisplayNamePlatform, String>> {
+    log::info!("# | get_display_name_field_ids | {}", config.user_id);
     let custom_fields_url = format!(
         "{}/customFields/{}",
         &config.simply_plural_base_url, system_id
@@ -232,29 +467,34 @@ async fn get_vrchat_status_name_field_id(
 
     let custom_fields: Vec<CustomField> = serde_json::from_str(&response).inspect_err(|e| {
         log::warn!(
-            "# | get_vrchat_status_name_field_id | {} | {} | input: {}",
+            "# | get_display_name_field_ids | {} | {} | input: {}",
             config.user_id,
             e,
             response.chars().take(500).collect::<String>()
         );
     })?;
 
-    let vrchat_status_name_field = custom_fields
+    let field_ids: HashMap<DisplayNamePlatform, String> = config
+        .display_name_fields
         .iter()
-        .find(|field| field.content.name == SIMPLY_PLURAL_VRCHAT_STATUS_NAME_FIELD_NAME);
-
-    let field_id = vrchat_status_name_field.map(|field| &field.id);
+        .filter_map(|(platform, field_name)| {
+            custom_fields
+                .iter()
+                .find(|field| field.content.name == *field_name)
+                .map(|field| (*platform, field.id.clone()))
+        })
+        .collect();
 
     log::info!(
-        "# | get_vrchat_status_name_field_id | {} | field_id {:?}",
+        "# | get_display_name_field_ids | {} | field_ids {:?}",
         config.user_id,
-        field_id
+        field_ids
     );
 
-    Ok(field_id.cloned())
+    Ok(field_ids)
 }
 
-async fn simply_plural_http_get_members(
+pub async fn simply_plural_http_get_members(
     config: &users::UserConfigForUpdater,
     system_id: &str,
 ) -> Result<Vec<Member>> {
@@ -280,6 +520,122 @@ async fn simply_plural_http_get_members(
     Ok(result)
 }
 
+pub async fn simply_plural_http_get_system_id(
+    config: &users::UserConfigForUpdater,
+) -> Result<String> {
+    log::info!("# | simply_plural_http_get_system_id | {}", config.user_id);
+    let me_url = format!("{}/me", &config.simply_plural_base_url);
+    let result = config
+        .client
+        .get(&me_url)
+        .header("Authorization", &config.simply_plural_token.secret)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let system_user: SystemUser = serde_json::from_str(&result).inspect_err(|e| {
+        log::warn!(
+            "# | simply_plural_http_get_system_id | {} | {} | input: {}",
+            config.user_id,
+            e,
+            result.chars().take(500).collect::<String>()
+        );
+    })?;
+
+    Ok(system_user.system_id)
+}
+
+/// Sets the `PluralKit` ID of the member, such that `PluralSync` can use it when syncing to `PluralKit`.
+pub async fn simply_plural_http_patch_member_pluralkit_id(
+    config: &users::UserConfigForUpdater,
+    member_id: &str,
+    pluralkit_id: &str,
+) -> Result<()> {
+    log::info!(
+        "# | simply_plural_http_patch_member_pluralkit_id | {} | {member_id} -> {pluralkit_id}",
+        config.user_id
+    );
+    let member_url = format!("{}/member/{}", &config.simply_plural_base_url, member_id);
+    config
+        .client
+        .patch(&member_url)
+        .header("Authorization", &config.simply_plural_token.secret)
+        .json(&serde_json::json!({ "pkId": pluralkit_id }))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+/// Starts a new live front of the member at the given time.
+pub async fn simply_plural_http_post_front_history(
+    config: &users::UserConfigForUpdater,
+    member_id: &str,
+    start_time: chrono::DateTime<chrono::Utc>,
+) -> Result<()> {
+    log::info!(
+        "# | simply_plural_http_post_front_history | {} | {member_id}",
+        config.user_id
+    );
+    // Simply Plural expects the client to choose the ID of the new document
+    let front_entry_id: String = rand::random::<[u8; 12]>()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    let front_history_url = format!(
+        "{}/frontHistory/{}",
+        &config.simply_plural_base_url, front_entry_id
+    );
+    config
+        .client
+        .post(&front_history_url)
+        .header("Authorization", &config.simply_plural_token.secret)
+        .json(&serde_json::json!({
+            "member": member_id,
+            "custom": false,
+            "live": true,
+            "startTime": start_time.timestamp_millis(),
+            "customStatus": "",
+        }))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+/// Ends the live front entry at the given time.
+pub async fn simply_plural_http_patch_front_history_end(
+    config: &users::UserConfigForUpdater,
+    front_entry_id: &str,
+    end_time: chrono::DateTime<chrono::Utc>,
+) -> Result<()> {
+    log::info!(
+        "# | simply_plural_http_patch_front_history_end | {} | {front_entry_id}",
+        config.user_id
+    );
+    let front_history_url = format!(
+        "{}/frontHistory/{}",
+        &config.simply_plural_base_url, front_entry_id
+    );
+    config
+        .client
+        .patch(&front_history_url)
+        .header("Authorization", &config.simply_plural_token.secret)
+        .json(&serde_json::json!({
+            "live": false,
+            "endTime": end_time.timestamp_millis(),
+        }))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
 async fn simply_plural_http_get_custom_fronts(
     config: &users::UserConfigForUpdater,
     system_id: &str,
@@ -314,6 +670,34 @@ async fn simply_plural_http_get_custom_fronts(
     Ok(result)
 }
 
+async fn simply_plural_http_get_groups(
+    config: &users::UserConfigForUpdater,
+    system_id: &str,
+) -> Result<Vec<Group>> {
+    log::info!("# | simply_plural_http_get_groups | {}", config.user_id);
+    let groups_url = format!("{}/groups/{}", &config.simply_plural_base_url, system_id);
+    let result = config
+        .client
+        .get(&groups_url)
+        .header("Authorization", &config.simply_plural_token.secret)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let result = serde_json::from_str(&result).inspect_err(|e| {
+        log::warn!(
+            "# | simply_plural_http_get_groups | {} | {} | input: {}",
+            config.user_id,
+            e,
+            result.chars().take(500).collect::<String>()
+        );
+    })?;
+
+    Ok(result)
+}
+
 async fn simply_plural_http_request_get_pluralsync_assigned_buckets(
     config: &users::UserConfigForUpdater,
     system_id: &str,
@@ -372,29 +756,99 @@ mod tests {
             respect_front_notifications_disabled,
             privacy_fine_grained: crate::users::PrivacyFineGrained::NoFineGrained,
             privacy_fine_grained_buckets: None,
+            fronting_source: crate::plurality::FrontingSourceKind::SimplyPlural,
             client: reqwest::Client::new(),
             user_id: crate::users::UserId {
                 inner: uuid::Uuid::new_v4(),
             },
+            allow_private_network_urls: false,
             simply_plural_base_url: "".to_string(),
+            simply_plural_websocket_url: "".to_string(),
+            pluralkit_base_url: "".to_string(),
             discord_base_url: "".to_string(),
             status_prefix: "".to_string(),
             status_no_fronts: "".to_string(),
             status_truncate_names_to: 0,
             show_custom_fronts: false,
+            website_show_custom_status: false,
+            discord_show_custom_status: false,
+            discord_status_message_show_custom_status: false,
+            vrchat_show_custom_status: false,
+            status_group_collapsing: crate::plurality::GroupCollapsing::Never,
+            fronter_order: crate::plurality::FronterOrder::SourceOrder,
+            fronter_order_manual: None,
+            display_name_fields: HashMap::new(),
+            front_history_retention_days: 30,
+            updater_log_retention_days: 7,
+            vrchat_status_templates: vec![],
+            discord_status_templates: vec![],
+            discord_status_message_status_templates: vec![],
+            website_status_templates: vec![],
+            vrchat_unsupported_characters: crate::plurality::UnsupportedCharacters::Drop,
+            vrchat_status_prefix: None,
+            discord_status_prefix: None,
+            discord_status_message_status_prefix: None,
+            website_status_prefix: None,
+            vrchat_status_no_fronts: None,
+            discord_status_no_fronts: None,
+            discord_status_message_status_no_fronts: None,
+            website_status_no_fronts: None,
+            vrchat_status_truncate_names_to: None,
+            discord_status_truncate_names_to: None,
+            discord_status_message_status_truncate_names_to: None,
+            website_status_truncate_names_to: None,
+            vrchat_status_group_collapsing: None,
+            discord_status_group_collapsing: None,
+            discord_status_message_status_group_collapsing: None,
+            website_status_group_collapsing: None,
             enable_website: false,
             enable_discord: false,
             enable_discord_status_message: false,
             enable_vrchat: false,
             enable_to_pluralkit: false,
+            enable_webhook: false,
+            enable_discord_webhook: false,
+            discord_webhook_edit_message: false,
+            enable_matrix: false,
+            matrix_displayname_scope: Default::default(),
+            matrix_displayname_rooms: vec![],
+            matrix_homeserver_url: "".to_string(),
+            enable_mastodon: false,
+            mastodon_instance_url: "".to_string(),
+            mastodon_field_name: "".to_string(),
+            mastodon_display_name: None,
+            enable_bluesky: false,
+            bluesky_pds_url: "".to_string(),
+            bluesky_identifier: "".to_string(),
+            bluesky_display_name: None,
+            enable_mqtt: false,
+            mqtt_broker_url: "".to_string(),
+            mqtt_username: None,
+            mqtt_fronters_topic: "".to_string(),
+            mqtt_status_topic: "".to_string(),
+            mqtt_fronter_count_topic: "".to_string(),
+            mqtt_home_assistant_discovery: false,
+            mqtt_home_assistant_discovery_prefix: "".to_string(),
+            enable_pluralkit_system_sync: false,
+            enable_from_pluralkit: false,
+            pluralkit_switch_order: crate::platforms::PluralKitSwitchOrder::PreservePluralKitOrder,
+            pluralkit_switch_primary_member: None,
             website_url_name: "".to_string(),
             website_system_name: "".to_string(),
+            webhook_url: "".to_string(),
             simply_plural_token: Default::default(),
             discord_status_message_token: Default::default(),
             vrchat_username: Default::default(),
             vrchat_password: Default::default(),
             vrchat_cookie: Default::default(),
             pluralkit_token: Default::default(),
+            webhook_signing_secret: Default::default(),
+            discord_webhook_url: Default::default(),
+            matrix_access_token: Default::default(),
+            mastodon_token: Default::default(),
+            bluesky_app_password: Default::default(),
+            mqtt_password: None,
+            pluralkit_dispatch_signing_token: None,
         }
     }
 
@@ -408,8 +862,11 @@ mod tests {
                 archived,
                 front_notifications_disabled,
                 privacy_buckets: vec![],
-                vrcsn_field_id: None,
+                display_name_field_ids: HashMap::new(),
                 pluralkit_id: None,
+                pronouns: None,
+                color: None,
+                description: None,
             },
         }
     }
@@ -448,4 +905,28 @@ mod tests {
         let member = create_test_member(false, false);
         assert!(!show_member_according_to_privacy_rules(&config, &member));
     }
+
+    #[test]
+    fn test_fronter_from_member_uses_display_name_fields() {
+        let mut member = create_test_member(false, false);
+        member.content.info = serde_json::json!({"f1": "Ali", "f2": " ", "f4": "Unmapped"});
+        member.content.display_name_field_ids = HashMap::from([
+            (DisplayNamePlatform::VRChat, "f1".to_owned()),
+            (DisplayNamePlatform::Discord, "f2".to_owned()),
+            (DisplayNamePlatform::Website, "f3".to_owned()),
+        ]);
+
+        let fronter = Fronter::from(member);
+
+        assert_eq!(fronter.display_name(DisplayNamePlatform::VRChat), "Ali");
+        assert_eq!(
+            fronter.display_name(DisplayNamePlatform::Discord),
+            "Test Member"
+        );
+        assert_eq!(
+            fronter.display_name(DisplayNamePlatform::Website),
+            "Test Member"
+        );
+        assert_eq!(fronter.display_names.len(), 1);
+    }
 }
@@ -0,0 +1,421 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    int_counter_metric,
+    plurality::{
+        CustomFront, DisplayNamePlatform, FrontEntry, Group, Member, SimplyPluralDocumentChange,
+        SimplyPluralWebsocketEvent,
+    },
+    users::UserId,
+};
+
+int_counter_metric!(SIMPLY_PLURAL_CACHE_HITS_TOTAL);
+int_counter_metric!(SIMPLY_PLURAL_CACHE_MISSES_TOTAL);
+
+// Websocket events can get lost (e.g. during reconnects). Hence, we refresh everything regularly.
+const CACHE_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// The Simply Plural collections, which are cached between fetches of the fronters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachedSimplyPluralCollection {
+    FrontHistory,
+    Members,
+    CustomFronts,
+    CustomFields,
+    Groups,
+    Friends,
+}
+
+impl CachedSimplyPluralCollection {
+    pub const ALL: [Self; 6] = [
+        Self::FrontHistory,
+        Self::Members,
+        Self::CustomFronts,
+        Self::CustomFields,
+        Self::Groups,
+        Self::Friends,
+    ];
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheEntry<T> {
+    value: T,
+    fetched_at: Instant,
+}
+
+impl<T: Clone> CacheEntry<T> {
+    fn fresh_value(&self) -> Option<T> {
+        (self.fetched_at.elapsed() < CACHE_MAX_AGE).then(|| self.value.clone())
+    }
+}
+
+/// Per-user in-memory model of the system.
+///
+/// The current front entries and the members are kept up to date by applying the websocket events.
+/// Everything is kept until a websocket event invalidates the collection or the entry is older than `CACHE_MAX_AGE`.
+#[derive(Debug)]
+pub struct SimplyPluralSystemCache {
+    /// Increased by every invalidation and websocket event, such that values fetched meanwhile aren't cached.
+    generation: u64,
+    system_id: Option<String>,
+    front_entries: Option<CacheEntry<Vec<FrontEntry>>>,
+    members: Option<CacheEntry<Vec<Member>>>,
+    custom_fronts: Option<CacheEntry<Vec<CustomFront>>>,
+    display_name_field_ids: Option<CacheEntry<HashMap<DisplayNamePlatform, String>>>,
+    groups: Option<CacheEntry<Vec<Group>>>,
+    friend_buckets: Option<CacheEntry<HashSet<String>>>,
+}
+
+impl Default for SimplyPluralSystemCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimplyPluralSystemCache {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            generation: 0,
+            system_id: None,
+            front_entries: None,
+            members: None,
+            custom_fronts: None,
+            display_name_field_ids: None,
+            groups: None,
+            friend_buckets: None,
+        }
+    }
+
+    pub fn invalidate(&mut self, collection: CachedSimplyPluralCollection) {
+        self.generation += 1;
+        match collection {
+            CachedSimplyPluralCollection::FrontHistory => self.front_entries = None,
+            CachedSimplyPluralCollection::Members => self.members = None,
+            CachedSimplyPluralCollection::CustomFronts => self.custom_fronts = None,
+            CachedSimplyPluralCollection::CustomFields => self.display_name_field_ids = None,
+            CachedSimplyPluralCollection::Groups => self.groups = None,
+            CachedSimplyPluralCollection::Friends => self.friend_buckets = None,
+        }
+    }
+
+    pub fn invalidate_all(&mut self) {
+        *self = Self {
+            generation: self.generation + 1,
+            ..Self::default()
+        };
+    }
+
+    /// Drops everything, if the cached data belongs to a different system.
+    pub fn ensure_system(&mut self, system_id: &str) {
+        match self.system_id.as_deref() {
+            Some(cached_system_id) if cached_system_id == system_id => (),
+            Some(_) => {
+                self.invalidate_all();
+                self.system_id = Some(system_id.to_owned());
+            }
+            None => self.system_id = Some(system_id.to_owned()),
+        }
+    }
+
+    /// Applies the changed documents to the cached model.
+    ///
+    /// Returns the collections which are up to date afterwards and hence must not be invalidated.
+    pub fn apply(
+        &mut self,
+        event: SimplyPluralWebsocketEvent,
+    ) -> Vec<CachedSimplyPluralCollection> {
+        self.generation += 1;
+        match event {
+            // without a known system, we cannot tell whether the entries belong to it
+            SimplyPluralWebsocketEvent::FrontHistory(_) if self.system_id.is_none() => vec![],
+            SimplyPluralWebsocketEvent::FrontHistory(changes) => {
+                if let Some(cached) = self.front_entries.as_mut() {
+                    for change in changes {
+                        match change {
+                            SimplyPluralDocumentChange::Upsert(front_entry) => {
+                                cached
+                                    .value
+                                    .retain(|e| e.front_entry_id != front_entry.front_entry_id);
+                                if front_entry.content.live
+                                    && self.system_id.as_ref()
+                                        == Some(&front_entry.content.system_id)
+                                {
+                                    cached.value.push(front_entry);
+                                }
+                            }
+                            SimplyPluralDocumentChange::Delete { id } => {
+                                cached.value.retain(|e| e.front_entry_id != id);
+                            }
+                        }
+                    }
+                }
+                vec![CachedSimplyPluralCollection::FrontHistory]
+            }
+            SimplyPluralWebsocketEvent::Members(changes) => {
+                if let Some(cached) = self.members.as_mut() {
+                    for change in changes {
+                        match change {
+                            SimplyPluralDocumentChange::Upsert(member) => {
+                                match cached
+                                    .value
+                                    .iter_mut()
+                                    .find(|m| m.member_id == member.member_id)
+                                {
+                                    Some(existing) => *existing = member,
+                                    None => cached.value.push(member),
+                                }
+                            }
+                            SimplyPluralDocumentChange::Delete { id } => {
+                                cached.value.retain(|m| m.member_id != id);
+                            }
+                        }
+                    }
+                }
+                vec![CachedSimplyPluralCollection::Members]
+            }
+            SimplyPluralWebsocketEvent::Other => vec![],
+        }
+    }
+
+    pub const fn front_entries(cache: &mut Self) -> &mut Option<CacheEntry<Vec<FrontEntry>>> {
+        &mut cache.front_entries
+    }
+
+    pub const fn members(cache: &mut Self) -> &mut Option<CacheEntry<Vec<Member>>> {
+        &mut cache.members
+    }
+
+    pub const fn custom_fronts(cache: &mut Self) -> &mut Option<CacheEntry<Vec<CustomFront>>> {
+        &mut cache.custom_fronts
+    }
+
+    pub const fn display_name_field_ids(
+        cache: &mut Self,
+    ) -> &mut Option<CacheEntry<HashMap<DisplayNamePlatform, String>>> {
+        &mut cache.display_name_field_ids
+    }
+
+    pub const fn groups(cache: &mut Self) -> &mut Option<CacheEntry<Vec<Group>>> {
+        &mut cache.groups
+    }
+
+    pub const fn friend_buckets(cache: &mut Self) -> &mut Option<CacheEntry<HashSet<String>>> {
+        &mut cache.friend_buckets
+    }
+}
+
+/// Returns the cached value selected by `entry`, or awaits `fetch` and caches its result.
+///
+/// The cache isn't locked while fetching. If it changed meanwhile, the fetched value may be outdated and is returned without caching it.
+pub async fn get_or_fetch<T: Clone>(
+    cache: &Mutex<SimplyPluralSystemCache>,
+    user_id: &UserId,
+    entry: impl Fn(&mut SimplyPluralSystemCache) -> &mut Option<CacheEntry<T>>,
+    fetch: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let (cached_value, fetch_generation) = {
+        let mut cache = cache.lock().map_err(|e| anyhow!(e.to_string()))?;
+        let cached_value = entry(&mut cache).as_ref().and_then(CacheEntry::fresh_value);
+        (cached_value, cache.generation)
+    };
+
+    if let Some(value) = cached_value {
+        SIMPLY_PLURAL_CACHE_HITS_TOTAL
+            .with_label_values(&[&user_id.to_string()])
+            .inc();
+        return Ok(value);
+    }
+
+    SIMPLY_PLURAL_CACHE_MISSES_TOTAL
+        .with_label_values(&[&user_id.to_string()])
+        .inc();
+
+    let value = fetch.await?;
+
+    let mut cache = cache.lock().map_err(|e| anyhow!(e.to_string()))?;
+    if cache.generation == fetch_generation {
+        *entry(&mut cache) = Some(CacheEntry {
+            value: value.clone(),
+            fetched_at: Instant::now(),
+        });
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::plurality::FrontEntryContent;
+    use sqlx::types::uuid;
+
+    fn user_id() -> UserId {
+        UserId {
+            inner: uuid::Uuid::new_v4(),
+        }
+    }
+
+    fn field_ids(field_id: &str) -> HashMap<DisplayNamePlatform, String> {
+        HashMap::from([(DisplayNamePlatform::VRChat, field_id.to_owned())])
+    }
+
+    #[tokio::test]
+    async fn test_get_or_fetch_uses_cache_until_invalidated() {
+        let cache = Mutex::new(SimplyPluralSystemCache::default());
+        let user_id = user_id();
+
+        let first = get_or_fetch(
+            &cache,
+            &user_id,
+            SimplyPluralSystemCache::display_name_field_ids,
+            async { Ok(field_ids("field-1")) },
+        )
+        .await
+        .unwrap();
+        let second = get_or_fetch(
+            &cache,
+            &user_id,
+            SimplyPluralSystemCache::display_name_field_ids,
+            async { Err(anyhow!("must not be fetched again")) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(first, field_ids("field-1"));
+        assert_eq!(second, first);
+
+        cache
+            .lock()
+            .unwrap()
+            .invalidate(CachedSimplyPluralCollection::Members);
+        let still_cached = get_or_fetch(
+            &cache,
+            &user_id,
+            SimplyPluralSystemCache::display_name_field_ids,
+            async { Err(anyhow!("must not be fetched again")) },
+        )
+        .await;
+        assert!(still_cached.is_ok());
+
+        cache
+            .lock()
+            .unwrap()
+            .invalidate(CachedSimplyPluralCollection::CustomFields);
+        let refetched = get_or_fetch(
+            &cache,
+            &user_id,
+            SimplyPluralSystemCache::display_name_field_ids,
+            async { Ok(HashMap::new()) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(refetched, HashMap::new());
+    }
+
+    #[tokio::test]
+    async fn test_get_or_fetch_does_not_cache_values_outdated_while_fetching() {
+        let cache = Mutex::new(SimplyPluralSystemCache::default());
+        let user_id = user_id();
+
+        let fetched = get_or_fetch(
+            &cache,
+            &user_id,
+            SimplyPluralSystemCache::display_name_field_ids,
+            async {
+                cache
+                    .lock()
+                    .unwrap()
+                    .invalidate(CachedSimplyPluralCollection::CustomFields);
+                Ok(field_ids("field-1"))
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(fetched, field_ids("field-1"));
+
+        let refetched = get_or_fetch(
+            &cache,
+            &user_id,
+            SimplyPluralSystemCache::display_name_field_ids,
+            async { Ok(field_ids("field-2")) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(refetched, field_ids("field-2"));
+    }
+
+    fn front_entry(id: &str, fronter_id: &str, live: bool) -> FrontEntry {
+        FrontEntry {
+            front_entry_id: id.to_owned(),
+            content: FrontEntryContent {
+                fronter_id: fronter_id.to_owned(),
+                system_id: "system-1".to_owned(),
+                start_time: chrono::DateTime::UNIX_EPOCH,
+                custom_status: None,
+                live,
+            },
+        }
+    }
+
+    #[test]
+    fn test_apply_front_history_changes() {
+        let mut cache = SimplyPluralSystemCache::new();
+        cache.ensure_system("system-1");
+        cache.front_entries = Some(CacheEntry {
+            value: vec![
+                front_entry("fh1", "m1", true),
+                front_entry("fh2", "m2", true),
+            ],
+            fetched_at: Instant::now(),
+        });
+
+        let applied = cache.apply(SimplyPluralWebsocketEvent::FrontHistory(vec![
+            SimplyPluralDocumentChange::Upsert(front_entry("fh1", "m1", false)),
+            SimplyPluralDocumentChange::Upsert(front_entry("fh3", "m3", true)),
+            SimplyPluralDocumentChange::Delete {
+                id: "fh2".to_owned(),
+            },
+        ]));
+
+        assert_eq!(applied, vec![CachedSimplyPluralCollection::FrontHistory]);
+        let fronter_ids: Vec<String> = cache
+            .front_entries
+            .unwrap()
+            .value
+            .into_iter()
+            .map(|e| e.content.fronter_id)
+            .collect();
+        assert_eq!(fronter_ids, vec!["m3".to_owned()]);
+    }
+
+    #[tokio::test]
+    async fn test_get_or_fetch_refetches_stale_entries_and_other_systems() {
+        let cache = Mutex::new(SimplyPluralSystemCache::default());
+        let user_id = user_id();
+        cache.lock().unwrap().ensure_system("system-1");
+        cache.lock().unwrap().display_name_field_ids = Some(CacheEntry {
+            value: field_ids("stale"),
+            fetched_at: Instant::now().checked_sub(CACHE_MAX_AGE).unwrap(),
+        });
+
+        let refreshed = get_or_fetch(
+            &cache,
+            &user_id,
+            SimplyPluralSystemCache::display_name_field_ids,
+            async { Ok(field_ids("fresh")) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(refreshed, field_ids("fresh"));
+
+        cache.lock().unwrap().ensure_system("system-1");
+        assert!(cache.lock().unwrap().display_name_field_ids.is_some());
+        cache.lock().unwrap().ensure_system("system-2");
+        assert!(cache.lock().unwrap().display_name_field_ids.is_none());
+    }
+}
@@ -1,18 +1,46 @@
-use std::string::ToString;
+use std::collections::HashMap;
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use serde::Deserialize;
 use serde::Deserializer;
+use serde::de::DeserializeOwned;
 use tokio_tungstenite::tungstenite;
 
+use crate::plurality::CachedSimplyPluralCollection;
+
 pub const GLOBAL_PLURALSYNC_ON_SIMPLY_PLURAL_USER_ID: &str =
     "eb06960e5b7fb576923f0e909947c0ce8ca46dcbe61ee5af2681f8f59404df5d";
 
+pub const SIMPLY_PLURAL_DEFAULT_BASE_URL: &str = "https://api.apparyllis.com/v1";
+
+pub const SIMPLY_PLURAL_DEFAULT_WEBSOCKET_URL: &str = "wss://api.apparyllis.com/v1/socket";
+
+/// The Simply Plural instance this application talks to, e.g. the `mock-simply-plural` for tests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimplyPluralEndpoints {
+    pub base_url: String,
+    pub websocket_url: String,
+}
+
+impl Default for SimplyPluralEndpoints {
+    fn default() -> Self {
+        Self {
+            base_url: String::from(SIMPLY_PLURAL_DEFAULT_BASE_URL),
+            websocket_url: String::from(SIMPLY_PLURAL_DEFAULT_WEBSOCKET_URL),
+        }
+    }
+}
+
+// The custom field which was used for the VRChat status name before the display names became
+// configurable per platform. Hence, it's still the default display name field for VRChat and Discord.
 pub const SIMPLY_PLURAL_VRCHAT_STATUS_NAME_FIELD_NAME: &str = "VRChat Status Name";
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct FrontEntry {
     pub content: FrontEntryContent,
+    #[serde(rename = "id")]
+    #[serde(default)]
+    pub front_entry_id: String,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -27,6 +55,18 @@ pub struct FrontEntryContent {
     #[serde(rename = "startTime")]
     #[serde(deserialize_with = "parse_epoch_millis_to_datetime_utc")]
     pub start_time: chrono::DateTime<chrono::Utc>,
+
+    #[serde(rename = "customStatus")]
+    #[serde(default)]
+    pub custom_status: Option<String>,
+
+    /** false, once the front has ended. The entries from /fronters are always live. */
+    #[serde(default = "live_by_default")]
+    pub live: bool,
+}
+
+const fn live_by_default() -> bool {
+    true
 }
 
 fn parse_epoch_millis_to_datetime_utc<'de, D>(
@@ -56,16 +96,59 @@ pub struct Fronter {
     pub fronter_id: String,
     pub name: String,
     pub avatar_url: String,
-    pub vrchat_status_name: Option<String>,
+    /** Alternative names from the configured custom fields. Platforms without an entry use `name`. */
+    pub display_names: HashMap<DisplayNamePlatform, String>,
     pub pluralkit_id: Option<String>,
+    pub pronouns: Option<String>,
+    /** The custom status of the front entry, e.g. "co-con". Only known for fronters which are currently fronting. */
+    pub custom_status: Option<String>,
     pub start_time: Option<chrono::DateTime<chrono::Utc>>,
     pub privacy_buckets: Vec<String>,
+    pub groups: Vec<FronterGroup>,
+    pub is_custom_front: bool,
+}
+
+/// A group (e.g. "Littles") which the fronter is a member of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FronterGroup {
+    pub group_id: String,
+    pub name: String,
+    /// Number of all members in the group, regardless of whether they are fronting
+    pub members_count: usize,
+}
+
+/// The platforms for which a custom field can be configured as the display name of members.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    Deserialize,
+    strum_macros::Display,
+    specta::Type,
+)]
+pub enum DisplayNamePlatform {
+    VRChat,
+    Discord,
+    DiscordStatusMessage,
+    Website,
+    Webhook,
+    DiscordWebhook,
+    Matrix,
+    Mastodon,
+    Bluesky,
+    Mqtt,
+    /// Formats without a platform, e.g. in tests. Never configured, hence the member names are used.
+    Generic,
 }
 
 impl Fronter {
     #[must_use]
-    pub fn preferred_vrchat_status_name(&self) -> &str {
-        self.vrchat_status_name.as_ref().unwrap_or(&self.name)
+    pub fn display_name(&self, platform: DisplayNamePlatform) -> &str {
+        self.display_names.get(&platform).unwrap_or(&self.name)
     }
 }
 
@@ -95,10 +178,14 @@ impl From<CustomFront> for Fronter {
             fronter_id: cf.custom_front_id,
             name: cf.content.name,
             avatar_url: cf.content.avatar_url,
-            vrchat_status_name: None,
+            display_names: HashMap::new(),
             pluralkit_id: None,
+            pronouns: None,
+            custom_status: None,
             start_time: None,
             privacy_buckets: cf.content.privacy_buckets,
+            groups: vec![],
+            is_custom_front: true,
         }
     }
 }
@@ -119,9 +206,10 @@ pub struct MemberContent {
     pub avatar_url: String,
 
     #[serde(default)]
+    // the values of the custom fields on this member, e.g. the display names
+    // { "<display_name_field_id>": "<display_name>", ...}
     pub info: serde_json::Value,
-    // if the user uses the custom field "VRChat Status Name" on this member, then this will be
-    // { "<vrcsn_field_id>": "<vrcsn>", ...}
+
     #[serde(default)]
     pub archived: bool,
 
@@ -142,29 +230,86 @@ pub struct MemberContent {
     #[serde(deserialize_with = "deserialize_non_empty_string_as_option")]
     pub pluralkit_id: Option<String>,
 
-    // this will be populated later after deserialisation
     #[serde(default)]
-    pub vrcsn_field_id: Option<String>,
+    pub pronouns: Option<String>,
+
+    // hex color, usually with a leading '#'
+    #[serde(default)]
+    pub color: Option<String>,
+
+    #[serde(rename = "desc")]
+    #[serde(default)]
+    pub description: Option<String>,
+
+    // this will be populated later after deserialisation
+    #[serde(skip)]
+    pub display_name_field_ids: HashMap<DisplayNamePlatform, String>,
 }
 
 impl From<Member> for Fronter {
     fn from(m: Member) -> Self {
-        let vrchat_status_name = m.content.vrcsn_field_id.as_ref().and_then(|field_id| {
-            m.content
-                .info
-                .as_object()
-                .and_then(|custom_fields| custom_fields.get(field_id))
-                .and_then(|value| value.as_str())
-                .map(ToString::to_string)
-        });
+        let display_names = m
+            .content
+            .display_name_field_ids
+            .iter()
+            .filter_map(|(platform, field_id)| {
+                m.content
+                    .info
+                    .as_object()
+                    .and_then(|custom_fields| custom_fields.get(field_id))
+                    .and_then(|value| value.as_str())
+                    .filter(|display_name| !display_name.trim().is_empty())
+                    .map(|display_name| (*platform, display_name.to_owned()))
+            })
+            .collect();
         Self {
             fronter_id: m.member_id,
             name: m.content.name,
             avatar_url: m.content.avatar_url,
-            vrchat_status_name,
+            display_names,
             pluralkit_id: m.content.pluralkit_id,
+            pronouns: m.content.pronouns.filter(|p| !p.is_empty()),
+            custom_status: None,
             start_time: None,
             privacy_buckets: m.content.privacy_buckets,
+            groups: vec![],
+            is_custom_front: false,
+        }
+    }
+}
+
+/// The account of the Simply Plural token, i.e. the system itself.
+#[derive(Deserialize, Debug, Clone)]
+pub struct SystemUser {
+    #[serde(rename = "id")]
+    pub system_id: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Group {
+    pub content: GroupContent,
+    #[serde(rename = "id")]
+    pub group_id: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GroupContent {
+    pub name: String,
+
+    #[serde(default)]
+    pub members: Vec<String>,
+
+    #[serde(rename = "buckets")]
+    #[serde(default)]
+    pub privacy_buckets: Vec<String>,
+}
+
+impl From<&Group> for FronterGroup {
+    fn from(g: &Group) -> Self {
+        Self {
+            group_id: g.group_id.clone(),
+            name: g.content.name.clone(),
+            members_count: g.content.members.len(),
         }
     }
 }
@@ -214,6 +359,7 @@ pub fn relevantly_changed_based_on_simply_plural_websocket_event(
       frontStatuses: even if we don't display it currently, but maybe in future we will
       frontHistory: obviously
       members: obviously
+      groups: for collapsing fronters into group names
       private: just to be sure...
       tokens: just to be sure...
       users: just to be sure...
@@ -222,7 +368,8 @@ pub fn relevantly_changed_based_on_simply_plural_websocket_event(
     However, we can also get a notification: {msg: "notification", title: <title>, message: <message>}
     How should we proceed in such a situation? For now, we'll simply take each notification as if it's a system change
 
-    furthermore, we still want to force-reset the cache e.g. once per hour, so any things we've missed would be caught
+    the cached collections are additionally invalidated via invalidated_collections_based_on_simply_plural_websocket_event
+    and they are force-refreshed once per hour (see SimplyPluralSystemCache), so any things we've missed would be caught
     */
 
     let irrelevant_change = matches!(
@@ -235,7 +382,6 @@ pub fn relevantly_changed_based_on_simply_plural_websocket_event(
                     | "channel"
                     | "channelCategories"
                     | "chatMessages"
-                    | "groups"
                     | "notes"
                     | "polls"
                     | "repeatedReminders"
@@ -246,6 +392,111 @@ pub fn relevantly_changed_based_on_simply_plural_websocket_event(
     Ok(!irrelevant_change)
 }
 
+/// The cached collections, which might have changed according to the websocket event.
+///
+/// Unknown messages (e.g. notifications) and changes to the account itself invalidate everything.
+pub fn invalidated_collections_based_on_simply_plural_websocket_event(
+    message: &tungstenite::Utf8Bytes,
+) -> Result<Vec<CachedSimplyPluralCollection>> {
+    let event: Event = serde_json::from_str(message).inspect_err(|e| {
+        log::warn!(
+            "# | invalidated_collections_based_on_simply_plural_websocket_event | {e} | input: {}",
+            message.chars().take(500).collect::<String>()
+        );
+    })?;
+
+    let invalidated = match (event.msg, event.target) {
+        ("update", Some("frontHistory")) => vec![CachedSimplyPluralCollection::FrontHistory],
+        ("update", Some("members")) => vec![CachedSimplyPluralCollection::Members],
+        ("update", Some("customFronts")) => vec![CachedSimplyPluralCollection::CustomFronts],
+        ("update", Some("customFields")) => vec![CachedSimplyPluralCollection::CustomFields],
+        ("update", Some("groups")) => vec![CachedSimplyPluralCollection::Groups],
+        ("update", Some("friends" | "pendingFriendRequests")) => {
+            vec![CachedSimplyPluralCollection::Friends]
+        }
+        ("update", Some(target)) if !["private", "tokens", "users"].contains(&target) => vec![],
+        _ => CachedSimplyPluralCollection::ALL.to_vec(),
+    };
+
+    Ok(invalidated)
+}
+
+/// A change of a single document as sent by Simply Plural in `update` messages on the websocket.
+#[derive(Debug, Clone)]
+pub enum SimplyPluralDocumentChange<T> {
+    Upsert(T),
+    Delete { id: String },
+}
+
+/// The websocket events, which can be applied to the cached system model without any HTTP calls.
+#[derive(Debug, Clone)]
+pub enum SimplyPluralWebsocketEvent {
+    FrontHistory(Vec<SimplyPluralDocumentChange<FrontEntry>>),
+    Members(Vec<SimplyPluralDocumentChange<Member>>),
+    /** Everything else is only handled via cache invalidation */
+    Other,
+}
+
+pub fn parse_simply_plural_websocket_event(
+    message: &tungstenite::Utf8Bytes,
+) -> Result<SimplyPluralWebsocketEvent> {
+    let update: UpdateEvent = serde_json::from_str(message).inspect_err(|e| {
+        log::warn!(
+            "# | parse_simply_plural_websocket_event | {e} | input: {}",
+            message.chars().take(500).collect::<String>()
+        );
+    })?;
+
+    let event = match (update.msg.as_str(), update.target.as_deref()) {
+        ("update", Some("frontHistory")) => {
+            SimplyPluralWebsocketEvent::FrontHistory(document_changes(update.results)?)
+        }
+        ("update", Some("members")) => {
+            SimplyPluralWebsocketEvent::Members(document_changes(update.results)?)
+        }
+        _ => SimplyPluralWebsocketEvent::Other,
+    };
+
+    Ok(event)
+}
+
+// Both, FrontEntry and Member, are deserialized from the same {"id": .., "content": ..} shape as in the REST API.
+fn document_changes<T: DeserializeOwned>(
+    results: Vec<UpdateEventResult>,
+) -> Result<Vec<SimplyPluralDocumentChange<T>>> {
+    results
+        .into_iter()
+        .map(|result| match result.operation_type.as_str() {
+            "delete" => Ok(SimplyPluralDocumentChange::Delete { id: result.id }),
+            "insert" | "update" => {
+                let document = serde_json::json!({"id": result.id, "content": result.content});
+                Ok(SimplyPluralDocumentChange::Upsert(serde_json::from_value(
+                    document,
+                )?))
+            }
+            unknown => Err(anyhow!("Unknown operationType '{unknown}'")),
+        })
+        .collect()
+}
+
+/** The `update` message as sent by Simply Plural on the Websocket including the changed documents. */
+#[derive(Debug, Clone, Deserialize)]
+struct UpdateEvent {
+    msg: String,
+    target: Option<String>,
+    #[serde(default)]
+    results: Vec<UpdateEventResult>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct UpdateEventResult {
+    #[serde(rename = "operationType")]
+    operation_type: String,
+    id: String,
+    #[serde(default)]
+    content: serde_json::Value,
+}
+
 /** The Message as sent by Simply Plural on the Websocket.
  *
  * We use &str to make the code for parsing look better and simpler by being able to match against &str literals.
@@ -276,6 +527,97 @@ mod tests {
         assert!(relevantly_changed_based_on_simply_plural_websocket_event(&utf8_bytes).unwrap());
     }
 
+    #[test]
+    fn test_invalidated_collections_based_on_simply_plural_websocket_event() {
+        let invalidated = |json: &str| {
+            invalidated_collections_based_on_simply_plural_websocket_event(
+                &tungstenite::Utf8Bytes::from(json.to_owned()),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            invalidated("{\"msg\": \"update\", \"target\": \"notes\"}"),
+            vec![]
+        );
+        assert_eq!(
+            invalidated("{\"msg\": \"update\", \"target\": \"frontHistory\"}"),
+            vec![CachedSimplyPluralCollection::FrontHistory]
+        );
+        assert_eq!(
+            invalidated("{\"msg\": \"update\", \"target\": \"members\"}"),
+            vec![CachedSimplyPluralCollection::Members]
+        );
+        assert_eq!(
+            invalidated("{\"msg\": \"update\", \"target\": \"customFields\"}"),
+            vec![CachedSimplyPluralCollection::CustomFields]
+        );
+        assert_eq!(
+            invalidated("{\"msg\": \"update\", \"target\": \"groups\"}"),
+            vec![CachedSimplyPluralCollection::Groups]
+        );
+        assert_eq!(
+            invalidated("{\"msg\": \"update\", \"target\": \"friends\"}"),
+            vec![CachedSimplyPluralCollection::Friends]
+        );
+        assert_eq!(
+            invalidated("{\"msg\": \"update\", \"target\": \"users\"}"),
+            CachedSimplyPluralCollection::ALL.to_vec()
+        );
+        assert_eq!(
+            invalidated("{\"msg\": \"notification\", \"title\": \"Test\"}"),
+            CachedSimplyPluralCollection::ALL.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_parse_simply_plural_websocket_event() {
+        let message = tungstenite::Utf8Bytes::from(
+            r#"{"msg": "update", "target": "frontHistory", "results": [
+                {"operationType": "insert", "id": "fh1", "content": {"member": "m1", "uid": "sys", "startTime": 1700000000000, "live": true}},
+                {"operationType": "update", "id": "fh0", "content": {"member": "m0", "uid": "sys", "startTime": 1600000000000, "endTime": 1700000000000, "live": false}},
+                {"operationType": "delete", "id": "fh9"}
+            ]}"#,
+        );
+        let SimplyPluralWebsocketEvent::FrontHistory(changes) =
+            parse_simply_plural_websocket_event(&message).unwrap()
+        else {
+            panic!("expected front history event");
+        };
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(
+            &changes[0],
+            SimplyPluralDocumentChange::Upsert(FrontEntry { front_entry_id, content })
+                if front_entry_id == "fh1" && content.fronter_id == "m1" && content.live
+        ));
+        assert!(matches!(
+            &changes[1],
+            SimplyPluralDocumentChange::Upsert(FrontEntry { content, .. }) if !content.live
+        ));
+        assert!(matches!(
+            &changes[2],
+            SimplyPluralDocumentChange::Delete { id } if id == "fh9"
+        ));
+
+        let message = tungstenite::Utf8Bytes::from(
+            r#"{"msg": "update", "target": "members", "results": [
+                {"operationType": "update", "id": "m1", "content": {"name": "Renamed"}}
+            ]}"#,
+        );
+        assert!(matches!(
+            parse_simply_plural_websocket_event(&message).unwrap(),
+            SimplyPluralWebsocketEvent::Members(changes)
+                if matches!(&changes[0], SimplyPluralDocumentChange::Upsert(m) if m.content.name == "Renamed")
+        ));
+
+        let message =
+            tungstenite::Utf8Bytes::from("{\"msg\": \"notification\", \"title\": \"Test\"}");
+        assert!(matches!(
+            parse_simply_plural_websocket_event(&message).unwrap(),
+            SimplyPluralWebsocketEvent::Other
+        ));
+    }
+
     #[test]
     fn test_member_json_pluralkid_id_empty_string_as_none() {
         let json_str = r#"
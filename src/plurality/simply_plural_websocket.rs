@@ -17,7 +17,6 @@ int_counter_metric!(SIMPLY_PLURAL_WEBSOCKET_CONNECTION_ENDED_ERROR_AUTH_TOTAL);
 int_counter_metric!(SIMPLY_PLURAL_WEBSOCKET_MESSAGES_RECEIVED_TOTAL);
 int_counter_metric!(SIMPLY_PLURAL_WEBSOCKET_SEMANTIC_MESSAGES_RECEIVED_TOTAL);
 
-const WEBSOCKET_URL: &str = "wss://api.apparyllis.com/v1/socket";
 const RETRY_WAIT_SECONDS: u64 = 60;
 const LONGER_RETRY_AFTER_FAILED_AUTHENTICATION_SECONDS: u64 = 60 * 60;
 const KEEP_ALIVE_INTERVAL: u64 = 30;
@@ -40,6 +39,7 @@ const SIMPLY_PLURAL_AUTH_FAILURE: &str = "Authentication against SP failed.";
 #[allow(clippy::future_not_send)]
 pub async fn auto_reconnecting_websocket_client_to_simply_plural<F1, F2>(
     log_prefix: &str,
+    url: &str,
     token: &str,
     process_event: impl Fn(tungstenite::Utf8Bytes) -> F1,
     on_connect: impl Fn() -> F2,
@@ -54,7 +54,8 @@ where
             .with_label_values(&[log_prefix])
             .inc();
         let wait_seconds = if let Err(e) =
-            run_single_websocket_connection(log_prefix, token, &process_event, &on_connect).await
+            run_single_websocket_connection(log_prefix, url, token, &process_event, &on_connect)
+                .await
         {
             log::error!("WS {log_prefix} client error: {e}.");
 
@@ -84,6 +85,7 @@ where
 
 async fn run_single_websocket_connection<F1, F2>(
     log_prefix: &str,
+    url: &str,
     token: &str,
     process_event: impl Fn(tungstenite::Utf8Bytes) -> F1,
     on_connect: impl Fn() -> F2,
@@ -92,7 +94,7 @@ where
     F1: Future<Output = Result<()>>,
     F2: Future<Output = Result<()>>,
 {
-    let (mut write, mut read) = create_connection(log_prefix, WEBSOCKET_URL).await?;
+    let (mut write, mut read) = create_connection(log_prefix, url).await?;
 
     authenticate(log_prefix, token, &mut write).await?;
 
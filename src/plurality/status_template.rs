@@ -0,0 +1,370 @@
+use anyhow::{Result, anyhow};
+
+/// A user defined shape of the fronting status, e.g. `{prefix} {names|join(", ")}`.
+///
+/// Placeholders are written in braces and consist of a variable followed by filters separated by `|`.
+/// Literal braces are escaped by doubling them, e.g. `{{` and `}}`.
+///
+/// Variables:
+/// * `prefix`: the configured status prefix
+/// * `names`: the names of the fronters (list)
+/// * `pronouns`: the pronouns of the fronters who have pronouns (list)
+/// * `count`: the number of fronters
+///
+/// Filters:
+/// * `join("sep")`: joins a list into a text. Lists without `join` are joined with ", ".
+/// * `first(n)`: only keeps the first n elements of a list
+/// * `truncate(n)`: shortens the text or each element of a list to n characters
+/// * `upper` and `lower`: changes the case
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart {
+    Literal(String),
+    Placeholder(Variable, Vec<Filter>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Variable {
+    Prefix,
+    Names,
+    Pronouns,
+    Count,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Filter {
+    Join(String),
+    First(usize),
+    Truncate(usize),
+    Upper,
+    Lower,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Text,
+    List,
+}
+
+enum Value {
+    Text(String),
+    List(Vec<String>),
+}
+
+/// The values which are available to the placeholders of a template.
+pub struct TemplateContext<'a> {
+    pub prefix: &'a str,
+    pub names: &'a [String],
+    pub pronouns: &'a [String],
+    pub count: usize,
+}
+
+impl StatusTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut parts = vec![];
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut placeholder = String::new();
+                    let mut in_string = false;
+                    let mut closed = false;
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '\\' if in_string => {
+                                placeholder.push(c);
+                                if let Some(escaped) = chars.next() {
+                                    placeholder.push(escaped);
+                                }
+                            }
+                            '"' => {
+                                in_string = !in_string;
+                                placeholder.push(c);
+                            }
+                            '}' if !in_string => {
+                                closed = true;
+                                break;
+                            }
+                            _ => placeholder.push(c),
+                        }
+                    }
+                    if !closed {
+                        return Err(anyhow!("Unclosed placeholder '{{{placeholder}'"));
+                    }
+                    if !literal.is_empty() {
+                        parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(parse_placeholder(&placeholder)?);
+                }
+                '}' => return Err(anyhow!("Unexpected '}}'. Use '}}}}' for a literal '}}'.")),
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+
+    #[must_use]
+    pub fn render(&self, context: &TemplateContext) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Literal(text) => text.clone(),
+                TemplatePart::Placeholder(variable, filters) => {
+                    let value = filters
+                        .iter()
+                        .fold(variable.value(context), |value, filter| filter.apply(value));
+                    match value {
+                        Value::Text(text) => text,
+                        Value::List(list) => list.join(", "),
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parses all templates of a platform, such that invalid templates are rejected when the config is saved.
+pub fn parse_status_templates(
+    field_name: &str,
+    templates: &[String],
+) -> Result<Vec<StatusTemplate>> {
+    templates
+        .iter()
+        .filter(|t| !t.trim().is_empty())
+        .map(|t| {
+            StatusTemplate::parse(t)
+                .map_err(|e| anyhow!("{field_name}: Invalid status template '{t}': {e}"))
+        })
+        .collect()
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<TemplatePart> {
+    let mut segments = split_outside_of_strings(placeholder, '|').into_iter();
+
+    let variable_name = segments.next().unwrap_or_default().trim().to_owned();
+    let variable = match variable_name.as_str() {
+        "prefix" => Variable::Prefix,
+        "names" => Variable::Names,
+        "pronouns" => Variable::Pronouns,
+        "count" => Variable::Count,
+        other => return Err(anyhow!("Unknown variable '{other}'")),
+    };
+
+    let mut kind = variable.kind();
+    let mut filters = vec![];
+    for segment in segments {
+        let filter = parse_filter(segment.trim())?;
+        kind = filter.output_kind(kind)?;
+        filters.push(filter);
+    }
+
+    Ok(TemplatePart::Placeholder(variable, filters))
+}
+
+fn parse_filter(filter: &str) -> Result<Filter> {
+    let (name, args) = match filter.split_once('(') {
+        Some((name, rest)) => {
+            let args = rest
+                .strip_suffix(')')
+                .ok_or_else(|| anyhow!("Missing ')' in filter '{filter}'"))?;
+            (name.trim(), split_outside_of_strings(args, ','))
+        }
+        None => (filter, vec![]),
+    };
+    let args: Vec<&str> = args
+        .iter()
+        .map(|a| a.trim())
+        .filter(|a| !a.is_empty())
+        .collect();
+
+    match (name, args.as_slice()) {
+        ("join", [separator]) => Ok(Filter::Join(parse_string_argument(separator)?)),
+        ("first", [n]) => Ok(Filter::First(parse_number_argument(n)?)),
+        ("truncate", [n]) => Ok(Filter::Truncate(parse_number_argument(n)?)),
+        ("upper", []) => Ok(Filter::Upper),
+        ("lower", []) => Ok(Filter::Lower),
+        ("join" | "first" | "truncate" | "upper" | "lower", _) => {
+            Err(anyhow!("Wrong number of arguments for filter '{name}'"))
+        }
+        _ => Err(anyhow!("Unknown filter '{name}'")),
+    }
+}
+
+fn parse_string_argument(argument: &str) -> Result<String> {
+    let inner = argument
+        .strip_prefix('"')
+        .and_then(|a| a.strip_suffix('"'))
+        .ok_or_else(|| anyhow!("Expected a quoted string, e.g. \", \", but got {argument}"))?;
+
+    let mut unescaped = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unescaped.extend(chars.next());
+        } else {
+            unescaped.push(c);
+        }
+    }
+    Ok(unescaped)
+}
+
+fn parse_number_argument(argument: &str) -> Result<usize> {
+    argument
+        .parse()
+        .map_err(|_| anyhow!("Expected a number, but got {argument}"))
+}
+
+/// Splits at the separator, unless it's within a quoted string.
+fn split_outside_of_strings(s: &str, separator: char) -> Vec<&str> {
+    let mut segments = vec![];
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            c if c == separator && !in_string => {
+                segments.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => (),
+        }
+    }
+    segments.push(&s[start..]);
+    segments
+}
+
+impl Variable {
+    const fn kind(self) -> ValueKind {
+        match self {
+            Self::Prefix | Self::Count => ValueKind::Text,
+            Self::Names | Self::Pronouns => ValueKind::List,
+        }
+    }
+
+    fn value(self, context: &TemplateContext) -> Value {
+        match self {
+            Self::Prefix => Value::Text(context.prefix.to_owned()),
+            Self::Names => Value::List(context.names.to_vec()),
+            Self::Pronouns => Value::List(context.pronouns.to_vec()),
+            Self::Count => Value::Text(context.count.to_string()),
+        }
+    }
+}
+
+impl Filter {
+    fn output_kind(&self, input: ValueKind) -> Result<ValueKind> {
+        match (self, input) {
+            (Self::Join(_), ValueKind::List) => Ok(ValueKind::Text),
+            (Self::First(_), ValueKind::List) => Ok(ValueKind::List),
+            (Self::Join(_) | Self::First(_), ValueKind::Text) => Err(anyhow!(
+                "The filters 'join' and 'first' can only be used on lists"
+            )),
+            (Self::Truncate(_) | Self::Upper | Self::Lower, kind) => Ok(kind),
+        }
+    }
+
+    fn apply(&self, value: Value) -> Value {
+        match (self, value) {
+            (Self::Join(separator), Value::List(list)) => Value::Text(list.join(separator)),
+            (Self::First(n), Value::List(list)) => Value::List(list.into_iter().take(*n).collect()),
+            (Self::Truncate(n), value) => value.map(|s| s.chars().take(*n).collect()),
+            (Self::Upper, value) => value.map(str::to_uppercase),
+            (Self::Lower, value) => value.map(str::to_lowercase),
+            // rejected while parsing
+            (Self::Join(_) | Self::First(_), value @ Value::Text(_)) => value,
+        }
+    }
+}
+
+impl Value {
+    fn map(self, f: impl Fn(&str) -> String) -> Self {
+        match self {
+            Self::Text(text) => Self::Text(f(&text)),
+            Self::List(list) => Self::List(list.iter().map(|s| f(s)).collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str) -> String {
+        let names = vec!["Alice".to_owned(), "Bob".to_owned(), "Claire".to_owned()];
+        let pronouns = vec!["she/her".to_owned(), "he/him".to_owned()];
+        let context = TemplateContext {
+            prefix: "F:",
+            names: &names,
+            pronouns: &pronouns,
+            count: names.len(),
+        };
+        StatusTemplate::parse(template).unwrap().render(&context)
+    }
+
+    #[test]
+    fn test_render_status_templates() {
+        assert_eq!(
+            render("{prefix} {names|join(\", \")}"),
+            "F: Alice, Bob, Claire"
+        );
+        assert_eq!(render("{count} fronting"), "3 fronting");
+        assert_eq!(render("{names|truncate(3)|join(\"/\")}"), "Ali/Bob/Cla");
+        assert_eq!(
+            render("{names} ({pronouns})"),
+            "Alice, Bob, Claire (she/her, he/him)"
+        );
+        assert_eq!(render("{names|first(2)|upper|join(\"|\")}"), "ALICE|BOB");
+        assert_eq!(render("{{literal}} {prefix|lower}"), "{literal} f:");
+        assert_eq!(render("{ names | join( \",\" ) }"), "Alice,Bob,Claire");
+    }
+
+    #[test]
+    fn test_parse_invalid_status_templates() {
+        assert!(StatusTemplate::parse("{unknown}").is_err());
+        assert!(StatusTemplate::parse("{names|unknown}").is_err());
+        assert!(StatusTemplate::parse("{names|join}").is_err());
+        assert!(StatusTemplate::parse("{names|join(, )}").is_err());
+        assert!(StatusTemplate::parse("{names|truncate(x)}").is_err());
+        assert!(StatusTemplate::parse("{prefix|join(\",\")}").is_err());
+        assert!(StatusTemplate::parse("{names|join(\",\")|first(2)}").is_err());
+        assert!(StatusTemplate::parse("{names").is_err());
+        assert!(StatusTemplate::parse("names}").is_err());
+    }
+
+    #[test]
+    fn test_parse_status_templates_ignores_empty_templates() {
+        let templates = vec!["{count}".to_owned(), " ".to_owned()];
+        assert_eq!(
+            parse_status_templates("field", &templates).unwrap().len(),
+            1
+        );
+        let invalid = vec!["{count".to_owned()];
+        assert!(
+            parse_status_templates("field", &invalid)
+                .unwrap_err()
+                .to_string()
+                .starts_with("field: ")
+        );
+    }
+}
@@ -0,0 +1,373 @@
+/// Romanizes Cyrillic, Greek, kana and Hangul and replaces well-known emoji by their shortname, e.g. `:cherry_blossom:`.
+///
+/// All other characters (including Latin and CJK ideographs) are kept as they are.
+/// Scripts without letter case are capitalized at the beginning of a word, e.g. "さくら" becomes "Sakura".
+#[must_use]
+pub fn transliterate_to_latin(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut latin = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let at_word_start = latin.chars().last().is_none_or(char::is_whitespace);
+
+        if let Some(romanized) = romanize_cyrillic(c).or_else(|| romanize_greek(c)) {
+            if c.is_uppercase() {
+                latin.push_str(&capitalize(romanized));
+            } else {
+                latin.push_str(romanized);
+            }
+            i += 1;
+        } else if is_kana(c) {
+            let (romanized, consumed) = romanize_kana(&chars[i..], &latin);
+            push_caseless(&mut latin, &romanized, at_word_start);
+            i += consumed;
+        } else if let Some(romanized) = romanize_hangul(c) {
+            push_caseless(&mut latin, &romanized, at_word_start);
+            i += 1;
+        } else if let Some(shortname) = emoji_shortname(c) {
+            latin.push(':');
+            latin.push_str(shortname);
+            latin.push(':');
+            i += 1;
+        } else {
+            latin.push(c);
+            i += 1;
+        }
+    }
+
+    latin
+}
+
+fn push_caseless(latin: &mut String, romanized: &str, at_word_start: bool) {
+    if at_word_start {
+        latin.push_str(&capitalize(romanized));
+    } else {
+        latin.push_str(romanized);
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+fn romanize_cyrillic(c: char) -> Option<&'static str> {
+    let romanized = match c.to_lowercase().next()? {
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' | 'ґ' | 'ѓ' => "g",
+        'д' => "d",
+        'е' | 'э' => "e",
+        'ё' => "yo",
+        'ж' => "zh",
+        'з' => "z",
+        'и' | 'і' => "i",
+        'й' | 'ы' => "y",
+        'к' | 'ќ' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' | 'ў' => "u",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ю' => "yu",
+        'я' => "ya",
+        'ї' => "yi",
+        'є' => "ye",
+        'ђ' => "dj",
+        'ј' => "j",
+        'љ' => "lj",
+        'њ' => "nj",
+        'ћ' => "c",
+        'џ' | 'ѕ' => "dz",
+        _ => return None,
+    };
+    Some(romanized)
+}
+
+fn romanize_greek(c: char) -> Option<&'static str> {
+    let romanized = match c.to_lowercase().next()? {
+        'α' | 'ά' => "a",
+        'β' => "v",
+        'γ' => "g",
+        'δ' => "d",
+        'ε' | 'έ' => "e",
+        'ζ' => "z",
+        'η' | 'ή' | 'ι' | 'ί' | 'ϊ' | 'ΐ' => "i",
+        'θ' => "th",
+        'κ' => "k",
+        'λ' => "l",
+        'μ' => "m",
+        'ν' => "n",
+        'ξ' => "x",
+        'ο' | 'ό' | 'ω' | 'ώ' => "o",
+        'π' => "p",
+        'ρ' => "r",
+        'σ' | 'ς' => "s",
+        'τ' => "t",
+        'υ' | 'ύ' | 'ϋ' | 'ΰ' => "y",
+        'φ' => "f",
+        'χ' => "ch",
+        'ψ' => "ps",
+        _ => return None,
+    };
+    Some(romanized)
+}
+
+const fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3041}'..='\u{3096}' | '\u{30A1}'..='\u{30FA}' | 'ー')
+}
+
+const SMALL_TSU: char = 'っ';
+
+/// Katakana are romanized like their hiragana counterparts.
+fn to_hiragana(c: char) -> char {
+    match c {
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Romanizes the kana at the beginning of the slice (Hepburn) and returns how many characters were consumed.
+///
+/// Combinations with small ya/yu/yo (e.g. "きょ" -> "kyo") and the small tsu (e.g. "った" -> "tta") span
+/// multiple characters. The long vowel mark repeats the previous vowel.
+fn romanize_kana(chars: &[char], romanized_so_far: &str) -> (String, usize) {
+    let c = to_hiragana(chars[0]);
+
+    if c == 'ー' {
+        let last_vowel = romanized_so_far
+            .chars()
+            .last()
+            .filter(|c| "aeiou".contains(*c))
+            .map(String::from)
+            .unwrap_or_default();
+        return (last_vowel, 1);
+    }
+
+    if c == SMALL_TSU {
+        return match chars.get(1).filter(|next| is_kana(**next)) {
+            Some(_) => {
+                let (next, consumed) = romanize_kana(&chars[1..], romanized_so_far);
+                let doubled = if next.starts_with("ch") {
+                    "t"
+                } else {
+                    next.get(..1)
+                        .filter(|first| !"aeiou".contains(*first))
+                        .unwrap_or_default()
+                };
+                (format!("{doubled}{next}"), consumed + 1)
+            }
+            None => (String::new(), 1),
+        };
+    }
+
+    let base = romanize_single_kana(c);
+    let small_y_vowel = chars
+        .get(1)
+        .map(|next| to_hiragana(*next))
+        .and_then(|next| match next {
+            'ゃ' => Some('a'),
+            'ゅ' => Some('u'),
+            'ょ' => Some('o'),
+            _ => None,
+        });
+
+    match (base.strip_suffix('i'), small_y_vowel) {
+        (Some(consonant), Some(vowel)) if !consonant.is_empty() => {
+            let combined =
+                if consonant.ends_with("sh") || consonant.ends_with("ch") || consonant == "j" {
+                    format!("{consonant}{vowel}")
+                } else {
+                    format!("{consonant}y{vowel}")
+                };
+            (combined, 2)
+        }
+        _ => (base.to_owned(), 1),
+    }
+}
+
+const fn romanize_single_kana(c: char) -> &'static str {
+    match c {
+        'ぁ' | 'あ' => "a",
+        'ぃ' | 'い' | 'ゐ' => "i",
+        'ぅ' | 'う' => "u",
+        'ぇ' | 'え' | 'ゑ' => "e",
+        'ぉ' | 'お' | 'を' => "o",
+        'か' | 'ゕ' => "ka",
+        'が' => "ga",
+        'き' => "ki",
+        'ぎ' => "gi",
+        'く' => "ku",
+        'ぐ' => "gu",
+        'け' | 'ゖ' => "ke",
+        'げ' => "ge",
+        'こ' => "ko",
+        'ご' => "go",
+        'さ' => "sa",
+        'ざ' => "za",
+        'し' => "shi",
+        'じ' | 'ぢ' => "ji",
+        'す' => "su",
+        'ず' | 'づ' => "zu",
+        'せ' => "se",
+        'ぜ' => "ze",
+        'そ' => "so",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'だ' => "da",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'で' => "de",
+        'と' => "to",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ば' => "ba",
+        'ぱ' => "pa",
+        'ひ' => "hi",
+        'び' => "bi",
+        'ぴ' => "pi",
+        'ふ' => "fu",
+        'ぶ' => "bu",
+        'ぷ' => "pu",
+        'へ' => "he",
+        'べ' => "be",
+        'ぺ' => "pe",
+        'ほ' => "ho",
+        'ぼ' => "bo",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'ゃ' | 'や' => "ya",
+        'ゅ' | 'ゆ' => "yu",
+        'ょ' | 'よ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'ゎ' | 'わ' => "wa",
+        'ん' => "n",
+        'ゔ' => "vu",
+        'ヷ' => "va",
+        'ヸ' => "vi",
+        'ヹ' => "ve",
+        'ヺ' => "vo",
+        _ => "",
+    }
+}
+
+const HANGUL_SYLLABLES_START: u32 = 0xAC00;
+const HANGUL_SYLLABLES_END: u32 = 0xD7A3;
+
+const HANGUL_INITIALS: [&str; 19] = [
+    "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p",
+    "h",
+];
+const HANGUL_VOWELS: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we",
+    "wi", "yu", "eu", "ui", "i",
+];
+const HANGUL_FINALS: [&str; 28] = [
+    "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p", "p",
+    "t", "t", "ng", "t", "t", "k", "t", "p", "t",
+];
+
+/// Romanizes a precomposed Hangul syllable (Revised Romanization, without the assimilation rules).
+fn romanize_hangul(c: char) -> Option<String> {
+    let code = c as u32;
+    if !(HANGUL_SYLLABLES_START..=HANGUL_SYLLABLES_END).contains(&code) {
+        return None;
+    }
+    let index = (code - HANGUL_SYLLABLES_START) as usize;
+    let initial = HANGUL_INITIALS[index / (21 * 28)];
+    let vowel = HANGUL_VOWELS[(index % (21 * 28)) / 28];
+    let final_consonant = HANGUL_FINALS[index % 28];
+    Some(format!("{initial}{vowel}{final_consonant}"))
+}
+
+const fn emoji_shortname(c: char) -> Option<&'static str> {
+    let shortname = match c {
+        '😀' => "grinning",
+        '😊' => "blush",
+        '😂' => "joy",
+        '😍' => "heart_eyes",
+        '😎' => "sunglasses",
+        '😢' => "cry",
+        '😴' => "sleeping",
+        '🥺' => "pleading_face",
+        '❤' => "heart",
+        '🧡' => "orange_heart",
+        '💛' => "yellow_heart",
+        '💚' => "green_heart",
+        '💙' => "blue_heart",
+        '💜' => "purple_heart",
+        '🖤' => "black_heart",
+        '🤍' => "white_heart",
+        '💖' => "sparkling_heart",
+        '💭' => "thought_balloon",
+        '✨' => "sparkles",
+        '⭐' => "star",
+        '🌟' => "star2",
+        '🌙' => "crescent_moon",
+        '☀' => "sunny",
+        '❄' => "snowflake",
+        '🔥' => "fire",
+        '🌊' => "ocean",
+        '🌈' => "rainbow",
+        '🌸' => "cherry_blossom",
+        '🌺' => "hibiscus",
+        '🌻' => "sunflower",
+        '🌹' => "rose",
+        '🍀' => "four_leaf_clover",
+        '🦋' => "butterfly",
+        '🐱' => "cat",
+        '🐶' => "dog",
+        '🦊' => "fox_face",
+        '🐺' => "wolf",
+        '🐰' => "rabbit",
+        '🐻' => "bear",
+        '🐼' => "panda_face",
+        '🐉' => "dragon",
+        '🦄' => "unicorn",
+        '🧸' => "teddy_bear",
+        '👻' => "ghost",
+        '💀' => "skull",
+        '👑' => "crown",
+        '🎀' => "ribbon",
+        '🎮' => "video_game",
+        '🎵' => "musical_note",
+        '🛡' => "shield",
+        '⚔' => "crossed_swords",
+        '🗡' => "dagger",
+        '🚀' => "rocket",
+        '🎉' => "tada",
+        '✅' => "white_check_mark",
+        _ => return None,
+    };
+    Some(shortname)
+}
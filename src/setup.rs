@@ -1,4 +1,5 @@
 use crate::database;
+use crate::plurality;
 use crate::updater;
 use crate::users;
 use anyhow::Result;
@@ -8,10 +9,12 @@ use pluralsync_base::meta::PLURALSYNC_VERSION;
 use rocket::http::Method;
 use sqlx::postgres;
 use std::env;
+use std::net::SocketAddr;
 use std::time::Duration;
 
 pub const EVERY_MINUTE: &str = "0 * * * * *";
 pub const EVERY_5_MINUTES: &str = "*/5 * * * * *";
+pub const EVERY_HOUR: &str = "0 0 * * * *";
 
 const REQUEST_TIMEOUT: u64 = 10;
 
@@ -109,6 +112,20 @@ pub fn make_client() -> Result<reqwest::Client> {
     Ok(client)
 }
 
+/// Client for URLs configured by the users. Redirects aren't followed, as they could lead into private networks.
+/// If given, the host is only connected to at the pinned addresses, which were checked before.
+pub fn make_user_url_client(pinned_host: Option<(&str, &[SocketAddr])>) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT));
+
+    if let Some((host, addresses)) = pinned_host {
+        builder = builder.resolve_to_addrs(host, addresses);
+    }
+
+    Ok(builder.build()?)
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ApplicationConfig {
     pub database_url: String,
@@ -119,6 +136,7 @@ pub struct ApplicationConfig {
     pub jwt_application_secret: String,
     pub application_user_secrets: String,
     pub discord_status_message_updater_available: bool,
+    pub simply_plural_endpoints: plurality::SimplyPluralEndpoints,
 }
 
 impl ApplicationConfig {
@@ -140,10 +158,27 @@ impl ApplicationConfig {
             )
             .unwrap_or_else(|_| "false".to_string())
             .parse()?,
+            simply_plural_endpoints: plurality::SimplyPluralEndpoints {
+                base_url: simply_plural_base_url_from_env(),
+                websocket_url: simply_plural_websocket_url_from_env(),
+            },
         })
     }
 }
 
+/// Allows pointing `PluralSync` to a different Simply Plural instance, e.g. the `mock-simply-plural` for tests.
+#[must_use]
+pub fn simply_plural_base_url_from_env() -> String {
+    env::var("SIMPLY_PLURAL_BASE_URL")
+        .unwrap_or_else(|_| plurality::SIMPLY_PLURAL_DEFAULT_BASE_URL.to_string())
+}
+
+#[must_use]
+pub fn simply_plural_websocket_url_from_env() -> String {
+    env::var("SIMPLY_PLURAL_WEBSOCKET_URL")
+        .unwrap_or_else(|_| plurality::SIMPLY_PLURAL_DEFAULT_WEBSOCKET_URL.to_string())
+}
+
 #[derive(Clone)]
 pub struct ApplicationSetup {
     pub db_pool: sqlx::PgPool,
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::database::Decrypted;
+use crate::plurality;
+use crate::users::{self, UserConfigDbEntries, UserId};
+
+pub const MOCK_SIMPLY_PLURAL_TOKEN: &str = "sp_token_123";
+
+#[derive(Clone, Debug)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// E.g. "GET /systems/@me/members"
+    pub fn request_line(&self) -> String {
+        format!("{} {}", self.method, self.path)
+    }
+
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_str(&self.body).unwrap()
+    }
+}
+
+pub type ReceivedRequests = Arc<Mutex<Vec<MockRequest>>>;
+
+/// Minimal HTTP server on a free local port. Records every request and answers it with the status and JSON body of `respond`.
+///
+/// Returns the base URL, e.g. `http://127.0.0.1:41234`, and the received requests.
+pub async fn start_mock_http_server(
+    respond: impl Fn(&MockRequest) -> (&'static str, String) + Send + Sync + 'static,
+) -> (String, ReceivedRequests) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let received = ReceivedRequests::default();
+
+    let respond = Arc::new(respond);
+    let received_by_server = received.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let respond = respond.clone();
+            let received = received_by_server.clone();
+            tokio::spawn(async move {
+                let Some(request) = read_request(&mut stream).await else {
+                    return;
+                };
+                received.lock().unwrap().push(request.clone());
+
+                let (status, body) = respond(&request);
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+
+    (format!("http://{address}"), received)
+}
+
+async fn read_request(stream: &mut tokio::net::TcpStream) -> Option<MockRequest> {
+    let mut data = Vec::new();
+    let mut buffer = vec![0u8; 16384];
+    loop {
+        let n = stream.read(&mut buffer).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        data.extend_from_slice(&buffer[..n]);
+
+        let Some(head_end) = data.windows(4).position(|w| w == b"\r\n\r\n") else {
+            continue;
+        };
+        let head = String::from_utf8_lossy(&data[..head_end]).to_string();
+        let body = &data[head_end + 4..];
+
+        let mut lines = head.lines();
+        let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+        let method = request_line.next().unwrap_or_default().to_owned();
+        let path = request_line.next().unwrap_or_default().to_owned();
+        let headers: Vec<(String, String)> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(n, v)| (n.trim().to_owned(), v.trim().to_owned()))
+            .collect();
+        let content_length: usize = headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case("content-length"))
+            .map_or(0, |(_, v)| v.parse().unwrap());
+
+        if body.len() >= content_length {
+            return Some(MockRequest {
+                method,
+                path,
+                headers,
+                body: String::from_utf8_lossy(body).to_string(),
+            });
+        }
+    }
+}
+
+/// Launches the `mock-simply-plural` with the given system on a free local port. Returns the base URL of its API.
+pub async fn start_mock_simply_plural(system: serde_json::Value) -> String {
+    let (port_sender, port_receiver) = tokio::sync::oneshot::channel();
+    let rocket = plurality::mock_simply_plural::build(
+        MOCK_SIMPLY_PLURAL_TOKEN.to_owned(),
+        serde_json::from_value(system).unwrap(),
+    )
+    .configure(
+        rocket::Config::figment()
+            .merge(("address", "127.0.0.1"))
+            .merge(("port", 0))
+            .merge(("log_level", rocket::config::LogLevel::Off))
+            .merge(("shutdown.ctrlc", false)),
+    )
+    .attach(rocket::fairing::AdHoc::on_liftoff("Port", |rocket| {
+        Box::pin(async move {
+            let _ = port_sender.send(rocket.config().port);
+        })
+    }));
+    tokio::spawn(rocket.launch());
+
+    let port = port_receiver.await.unwrap();
+    format!("http://127.0.0.1:{port}/v1")
+}
+
+/// Database entries which pass the constraints. The tests add the settings of their platform.
+pub fn db_config() -> UserConfigDbEntries<Decrypted> {
+    UserConfigDbEntries {
+        simply_plural_token: Some(Decrypted {
+            secret: MOCK_SIMPLY_PLURAL_TOKEN.to_owned(),
+        }),
+        ..Default::default()
+    }
+}
+
+/// The config of a new user, which may reach the local mock servers.
+pub fn create_test_config(
+    db_config: &UserConfigDbEntries<Decrypted>,
+) -> users::UserConfigForUpdater {
+    create_test_config_with_endpoints(db_config, &plurality::SimplyPluralEndpoints::default())
+}
+
+pub fn create_test_config_with_endpoints(
+    db_config: &UserConfigDbEntries<Decrypted>,
+    simply_plural_endpoints: &plurality::SimplyPluralEndpoints,
+) -> users::UserConfigForUpdater {
+    let user_id = UserId {
+        inner: sqlx::types::uuid::Uuid::new_v4(),
+    };
+    let (mut config, _) = users::create_config_with_strong_constraints(
+        &user_id,
+        &reqwest::Client::new(),
+        simply_plural_endpoints,
+        db_config,
+    )
+    .unwrap();
+    config.allow_private_network_urls = true;
+    config
+}
+
+pub fn fronter(name: &str) -> plurality::Fronter {
+    plurality::Fronter {
+        fronter_id: name.to_lowercase(),
+        name: name.to_owned(),
+        avatar_url: String::new(),
+        display_names: HashMap::new(),
+        pluralkit_id: None,
+        pronouns: None,
+        custom_status: None,
+        start_time: None,
+        privacy_buckets: vec![],
+        groups: vec![],
+        is_custom_front: false,
+    }
+}
@@ -1,5 +1,6 @@
 use crate::database;
 use crate::meta_api::{HttpResult, expose_internal_error};
+use crate::plurality;
 use crate::setup;
 use crate::updater::{change_processor, manager};
 use crate::users;
@@ -7,8 +8,14 @@ use crate::users::UserId;
 use anyhow::Result;
 use futures::future;
 use rocket::{State, serde::json::Json};
+use serde::Serialize;
 use sqlx::PgPool;
 
+const FRONT_HISTORY_DEFAULT_PAGE_SIZE: u32 = 20;
+const FRONT_HISTORY_MAX_PAGE_SIZE: u32 = 100;
+const UPDATER_LOG_DEFAULT_LIMIT: u32 = 50;
+const UPDATER_LOG_MAX_LIMIT: u32 = 200;
+
 #[allow(clippy::needless_pass_by_value)]
 #[get("/api/updaters/status")]
 pub fn get_api_updaters_status(
@@ -28,6 +35,97 @@ pub fn get_api_updaters_status(
     Ok(Json(updaters_state))
 }
 
+#[allow(clippy::needless_pass_by_value)]
+#[get("/api/fronting-source/status")]
+pub fn get_api_fronting_source_status(
+    shared_updaters: &State<manager::UpdaterManager>,
+    jwt: users::Jwt,
+) -> HttpResult<Json<plurality::FrontingSourceStatus>> {
+    let user_id = jwt.user_id().map_err(expose_internal_error)?;
+
+    log::info!("# | GET /api/fronting-source/status | {user_id}");
+
+    let source_status = shared_updaters
+        .get_fronting_source_status(&user_id)
+        .map_err(expose_internal_error)?;
+
+    log::info!("# | GET /api/fronting-source/status | {user_id} | retrieved");
+
+    Ok(Json(source_status))
+}
+
+/// The most recent updates of all platforms, newest first.
+#[get("/api/updaters/log?<limit>")]
+pub async fn get_api_updaters_log(
+    db_pool: &State<PgPool>,
+    jwt: users::Jwt,
+    limit: Option<u32>,
+) -> HttpResult<Json<Vec<database::UpdaterLogEntry>>> {
+    let user_id = jwt.user_id().map_err(expose_internal_error)?;
+    let limit = limit
+        .unwrap_or(UPDATER_LOG_DEFAULT_LIMIT)
+        .clamp(1, UPDATER_LOG_MAX_LIMIT);
+
+    log::info!("# | GET /api/updaters/log | {user_id} | limit {limit}");
+
+    let entries = database::get_updater_log(db_pool, &user_id, i64::from(limit))
+        .await
+        .map_err(expose_internal_error)?;
+
+    log::info!(
+        "# | GET /api/updaters/log | {user_id} | limit {limit} | {} entries",
+        entries.len()
+    );
+
+    Ok(Json(entries))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FrontHistoryPage {
+    pub entries: Vec<database::FrontHistoryEntry>,
+    pub next_page: Option<u32>,
+}
+
+/// The front history of the user, newest first. Pages start at 0.
+#[get("/api/user/front-history?<page>&<page_size>")]
+pub async fn get_api_user_front_history(
+    db_pool: &State<PgPool>,
+    jwt: users::Jwt,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> HttpResult<Json<FrontHistoryPage>> {
+    let user_id = jwt.user_id().map_err(expose_internal_error)?;
+    let page = page.unwrap_or_default();
+    let page_size = page_size
+        .unwrap_or(FRONT_HISTORY_DEFAULT_PAGE_SIZE)
+        .clamp(1, FRONT_HISTORY_MAX_PAGE_SIZE);
+
+    log::info!("# | GET /api/user/front-history | {user_id} | page {page} | size {page_size}");
+
+    // fetch one more entry to know whether there is a next page
+    let mut entries = database::get_front_history(
+        db_pool,
+        &user_id,
+        i64::from(page_size) + 1,
+        i64::from(page) * i64::from(page_size),
+    )
+    .await
+    .map_err(expose_internal_error)?;
+
+    let has_next_page = entries.len() > page_size as usize;
+    entries.truncate(page_size as usize);
+
+    log::info!(
+        "# | GET /api/user/front-history | {user_id} | page {page} | size {page_size} | {} entries",
+        entries.len()
+    );
+
+    Ok(Json(FrontHistoryPage {
+        entries,
+        next_page: has_next_page.then_some(page + 1),
+    }))
+}
+
 pub async fn restart_all_user_updaters_for_app_startups(
     setup: setup::ApplicationSetup,
 ) -> Result<()> {
@@ -72,9 +170,14 @@ pub async fn restart_updater_for_user(
 ) -> Result<()> {
     log::info!("# | restart_updater_for_user | {user_id}");
 
-    let config =
-        database::get_user_config_with_secrets(db_pool, user_id, client, application_user_secrets)
-            .await?;
+    let config = database::get_user_config_with_secrets(
+        db_pool,
+        user_id,
+        client,
+        &shared_updaters.simply_plural_endpoints,
+        application_user_secrets,
+    )
+    .await?;
 
     let () = shared_updaters.restart_updater(
         user_id,
@@ -2,9 +2,10 @@ use pluralsync_base::clock;
 use pluralsync_base::communication::LatestReceiver;
 use pluralsync_base::updater::UpdaterStatus;
 use std::collections::HashMap;
+use std::time::Instant;
 
 use crate::updater::platforms::{Platform, Updater};
-use crate::updater::{manager, platforms};
+use crate::updater::{front_history, manager, platforms, update_log};
 use crate::{database, int_counter_metric, plurality, users};
 use anyhow::Result;
 
@@ -60,9 +61,15 @@ pub async fn run_listener_for_changes(
             .with_label_values(&[&user_id.to_string()])
             .inc();
 
+        log_error_and_continue(
+            "front history",
+            front_history::record_front_history(db_pool, &config, &fronters).await,
+            &config,
+        );
+
         log_error_and_continue(
             "Updater Logic",
-            loop_logic(&config, &mut updaters, &fronters).await,
+            loop_logic(&config, &mut updaters, &fronters, db_pool).await,
             &config,
         );
 
@@ -98,14 +105,29 @@ async fn loop_logic(
     config: &users::UserConfigForUpdater,
     updaters: &mut UserUpdaters,
     fronters: &[plurality::Fronter],
+    db_pool: &sqlx::PgPool,
 ) -> Result<()> {
     for updater in updaters.values_mut() {
         if updater.enabled(config) {
+            let started_at = Instant::now();
+            let result = updater.update_fronting_status(config, fronters).await;
+            let duration = started_at.elapsed();
+
             log_error_and_continue(
-                &updater.platform().to_string(),
-                updater.update_fronting_status(config, fronters).await,
+                "update log",
+                update_log::record_update(
+                    db_pool,
+                    config,
+                    updater.platform(),
+                    updater.last_payload(),
+                    &result,
+                    duration,
+                )
+                .await,
                 config,
             );
+
+            log_error_and_continue(&updater.platform().to_string(), result, config);
         }
     }
 
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use pluralsync_base::clock;
+
+use crate::{database, int_counter_metric, plurality, users};
+
+int_counter_metric!(FRONT_HISTORY_ENTRIES_RECORDED_TOTAL);
+
+/// Records the fronters in the front history, if they differ from the most recently recorded fronters.
+/// Entries older than the retention period of the user are removed periodically by [`prune_expired_entries`](crate::updater::prune_expired_entries).
+pub async fn record_front_history(
+    db_pool: &sqlx::PgPool,
+    config: &users::UserConfigForUpdater,
+    fronters: &[plurality::Fronter],
+) -> Result<()> {
+    let user_id = &config.user_id;
+    let now = clock::now();
+
+    if config.front_history_retention_days == 0 {
+        log::info!("# | record_front_history | {user_id} | disabled");
+        return Ok(());
+    }
+
+    let entry = database::FrontHistoryEntry {
+        changed_at: now,
+        fronter_ids: fronters.iter().map(|f| f.fronter_id.clone()).collect(),
+        fronter_names: fronters.iter().map(|f| f.name.clone()).collect(),
+    };
+
+    let most_recent_entry = database::get_front_history(db_pool, user_id, 1, 0)
+        .await?
+        .into_iter()
+        .next();
+
+    if most_recent_entry.is_some_and(|recorded| is_same_fronter_set(&recorded, &entry)) {
+        log::info!("# | record_front_history | {user_id} | unchanged");
+    } else {
+        database::insert_front_history_entry(db_pool, user_id, &entry).await?;
+        FRONT_HISTORY_ENTRIES_RECORDED_TOTAL
+            .with_label_values(&[&user_id.to_string()])
+            .inc();
+        log::info!(
+            "# | record_front_history | {user_id} | recorded {} fronters",
+            entry.fronter_ids.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// The order of the fronters is irrelevant, as it only depends on the configured fronter order.
+fn is_same_fronter_set(a: &database::FrontHistoryEntry, b: &database::FrontHistoryEntry) -> bool {
+    let mut a_ids: Vec<&String> = a.fronter_ids.iter().collect();
+    let mut b_ids: Vec<&String> = b.fronter_ids.iter().collect();
+    a_ids.sort();
+    b_ids.sort();
+    a_ids == b_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(fronter_ids: &[&str]) -> database::FrontHistoryEntry {
+        database::FrontHistoryEntry {
+            changed_at: chrono::DateTime::UNIX_EPOCH,
+            fronter_ids: fronter_ids.iter().map(|&id| id.to_owned()).collect(),
+            fronter_names: vec![],
+        }
+    }
+
+    #[test]
+    fn test_is_same_fronter_set() {
+        assert!(is_same_fronter_set(&entry(&[]), &entry(&[])));
+        assert!(is_same_fronter_set(
+            &entry(&["a", "b"]),
+            &entry(&["b", "a"])
+        ));
+        assert!(!is_same_fronter_set(&entry(&["a", "b"]), &entry(&["a"])));
+        assert!(!is_same_fronter_set(&entry(&[]), &entry(&["a"])));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::metrics::SHOULDNT_HAPPEN_BUT_IT_DID;
-use crate::plurality::{self};
+use crate::plurality::{self, FrontingSource};
 use crate::updater::{self, change_processor};
 use crate::users::UserId;
 use crate::{database, users};
@@ -11,6 +11,7 @@ use sqlx;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use strum::VariantNames;
+use tokio::sync::Notify;
 use tokio::task::JoinHandle;
 
 type SharedMutable<T> = Arc<Mutex<T>>;
@@ -32,7 +33,6 @@ metric!(
     "updater_platform_status",
     &["user_id", "platform", "status"]
 );
-int_counter_metric!(UPDATER_MANAGER_SIMPLY_PLURAL_WEBSOCKET_RELEVANT_CHANGE_MESSAGE_COUNT);
 
 #[derive(Clone)]
 pub struct UpdaterManager {
@@ -40,7 +40,10 @@ pub struct UpdaterManager {
     pub statuses: ThreadSafePerUser<change_processor::UserUpdatersStatuses>,
     pub fronter_channel: ThreadSafePerUser<FronterChannel>,
     pub foreign_managed_status_channel: ThreadSafePerUser<ForeignStatusChannel>,
+    pub fronting_source_statuses: ThreadSafePerUser<plurality::FrontingSourceStatus>,
+    pub pluralkit_dispatch: ThreadSafePerUser<Arc<Notify>>,
     pub discord_status_message_available: bool,
+    pub simply_plural_endpoints: plurality::SimplyPluralEndpoints,
     pub updater_start_time: ThreadSafePerUser<chrono::DateTime<chrono::Utc>>,
 }
 
@@ -52,7 +55,10 @@ impl UpdaterManager {
             statuses: Arc::new(Mutex::new(HashMap::new())),
             fronter_channel: Arc::new(Mutex::new(HashMap::new())),
             discord_status_message_available: app_config.discord_status_message_updater_available,
+            simply_plural_endpoints: app_config.simply_plural_endpoints.clone(),
             foreign_managed_status_channel: Arc::new(Mutex::new(HashMap::new())),
+            fronting_source_statuses: Arc::new(Mutex::new(HashMap::new())),
+            pluralkit_dispatch: Arc::new(Mutex::new(HashMap::new())),
             updater_start_time: Arc::new(Mutex::new(HashMap::new())),
         }
     }
@@ -133,6 +139,43 @@ impl UpdaterManager {
             .to_owned())
     }
 
+    pub fn get_fronting_source_status(
+        &self,
+        user_id: &UserId,
+    ) -> Result<plurality::FrontingSourceStatus> {
+        Ok(self
+            .fronting_source_statuses
+            .lock()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .get(user_id)
+            .ok_or_else(|| anyhow!("get_fronting_source_status: No fronting source found!"))?
+            .to_owned())
+    }
+
+    fn notify_fronting_source_status(
+        &self,
+        user_id: &UserId,
+        source: &plurality::AnyFrontingSource,
+    ) -> Result<()> {
+        let status = plurality::FrontingSourceStatus {
+            source: source.kind(),
+            status: source.status()?,
+        };
+
+        log::info!(
+            "# | notify_fronting_source_status | {user_id} | {} is {}",
+            status.source,
+            status.status
+        );
+
+        self.fronting_source_statuses
+            .lock()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .insert(user_id.to_owned(), status);
+
+        Ok(())
+    }
+
     #[allow(clippy::significant_drop_tightening)]
     pub fn notify_updater_statuses(
         &self,
@@ -180,12 +223,8 @@ impl UpdaterManager {
         let () = self.recreate_fronter_channel(user_id)?;
         let foreign_status_updater_task = self.recreate_foreign_status_channel(user_id)?;
         let () = self.recreate_updater_statuses(user_id, &config)?;
-        let simply_plural_websocket_listener_task = self
-            .create_simply_plural_websocket_listener_task(
-                &config,
-                &db_pool,
-                application_user_secrets,
-            );
+        let fronting_source_listener_task =
+            self.create_fronting_source_listener_task(&config, &db_pool, application_user_secrets)?;
 
         let owned_self = self.to_owned();
         let application_user_secrets = application_user_secrets.clone();
@@ -206,7 +245,7 @@ impl UpdaterManager {
             vec![
                 work_loop_task,
                 foreign_status_updater_task,
-                simply_plural_websocket_listener_task,
+                fronting_source_listener_task,
             ],
         );
 
@@ -307,65 +346,85 @@ impl UpdaterManager {
             .map_err(|e| anyhow!(e.to_string()))?
             .insert(user_id.to_owned(), initially_disabled_status);
 
+        self.fronting_source_statuses
+            .lock()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .insert(
+                user_id.to_owned(),
+                plurality::FrontingSourceStatus {
+                    source: config.fronting_source,
+                    status: UpdaterStatus::Starting,
+                },
+            );
+
         Ok(())
     }
 
-    fn create_simply_plural_websocket_listener_task(
+    fn create_fronting_source_listener_task(
         &self,
         config: &users::UserConfigForUpdater,
         db_pool: &sqlx::PgPool,
         application_user_secrets: &database::ApplicationUserSecrets,
-    ) -> JoinHandle<()> {
+    ) -> Result<JoinHandle<()>> {
         let user_id = config.user_id.clone();
-        let sp_token = config.simply_plural_token.clone();
+        let source = plurality::AnyFrontingSource::new(config.fronting_source);
+
+        {
+            let mut pluralkit_dispatch = self
+                .pluralkit_dispatch
+                .lock()
+                .map_err(|e| anyhow!(e.to_string()))?;
+            match &source {
+                plurality::AnyFrontingSource::PluralKit(pluralkit) => {
+                    pluralkit_dispatch.insert(user_id.clone(), pluralkit.dispatch_notifier());
+                }
+                plurality::AnyFrontingSource::SimplyPlural(_) => {
+                    pluralkit_dispatch.remove(&user_id);
+                }
+            }
+        }
+
         let self2 = self.clone();
         let client = config.client.clone();
         let db_pool = db_pool.clone();
         let application_user_secrets = application_user_secrets.clone();
+        let listener_config = config.clone();
 
-        tokio::spawn(async move {
-            if sp_token.secret.is_empty() {
-                log::info!("SP WS '{user_id}': Not creating websocket, because token is not set.");
-                return;
-            }
-            let update_fronters_from_simply_plural = || {
+        Ok(tokio::spawn(async move {
+            let update_fronters_from_source = || {
                 self2.fetch_and_update_fronters(
                     &user_id,
+                    &source,
                     &client,
                     &db_pool,
                     &application_user_secrets,
                 )
             };
-            match update_fronters_from_simply_plural().await {
-                Ok(()) => log::info!("Initial SP update after restart OK."),
-                Err(e) => log::info!("Initial SP update after restart Err: {e}"),
+            match update_fronters_from_source().await {
+                Ok(()) => log::info!("Initial {} update after restart OK.", source.kind()),
+                Err(e) => log::info!("Initial {} update after restart Err: {e}", source.kind()),
             }
-            plurality::auto_reconnecting_websocket_client_to_simply_plural(
-                &user_id.to_string(),
-                &sp_token.secret,
-                async |message| {
-                    let changed =
-                        plurality::relevantly_changed_based_on_simply_plural_websocket_event(
-                            &message,
-                        )?;
-                    log::info!("SP WS payload '{user_id}': +{changed}");
-                    UPDATER_MANAGER_SIMPLY_PLURAL_WEBSOCKET_RELEVANT_CHANGE_MESSAGE_COUNT
-                        .with_label_values(&[&user_id.to_string()])
-                        .inc();
-                    if changed {
-                        update_fronters_from_simply_plural().await?;
-                    }
-                    Ok(())
-                },
-                update_fronters_from_simply_plural,
-            )
-            .await;
-        })
+            source
+                .listen_for_changes(&listener_config, update_fronters_from_source)
+                .await;
+        }))
+    }
+
+    /// Lets the `PluralKit` source of the user check for changes right away.
+    pub fn notify_pluralkit_dispatch(&self, user_id: &UserId) -> Result<()> {
+        self.pluralkit_dispatch
+            .lock()
+            .map_err(|e| anyhow!(e.to_string()))?
+            .get(user_id)
+            .ok_or_else(|| anyhow!("notify_pluralkit_dispatch: No PluralKit source for {user_id}"))?
+            .notify_one();
+        Ok(())
     }
 
     async fn fetch_and_update_fronters(
         &self,
         user_id: &UserId,
+        source: &plurality::AnyFrontingSource,
         client: &reqwest::Client,
         db_pool: &sqlx::Pool<sqlx::Postgres>,
         application_user_secrets: &database::ApplicationUserSecrets,
@@ -376,13 +435,24 @@ impl UpdaterManager {
             db_pool,
             user_id,
             client,
+            &self.simply_plural_endpoints,
             application_user_secrets,
         )
         .await?;
 
-        let fronters = plurality::fetch_fronts(&config).await?;
+        let fronters = source.fetch_fronters(&config).await;
+
+        self.notify_fronting_source_status(user_id, source)?;
+
+        let mut fronters = fronters?;
         let fronters_count = fronters.len();
 
+        plurality::sort_fronters(
+            config.fronter_order,
+            config.fronter_order_manual.as_deref().unwrap_or_default(),
+            &mut fronters,
+        );
+
         log::info!("# | fetch_and_update_fronters | {user_id} | {fronters_count} fronters fetched");
 
         self.fronter_channel
@@ -457,6 +527,7 @@ pub async fn restart_first_long_living_updater(
                     &db_pool,
                     &user_id,
                     &setup::make_client()?,
+                    &shared_updaters.simply_plural_endpoints,
                     &application_user_secrets,
                 )
                 .await?;
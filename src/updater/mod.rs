@@ -1,8 +1,12 @@
 pub mod api;
 mod change_processor;
+mod front_history;
 mod manager;
 mod platforms;
+mod update_log;
 
 pub use change_processor::*;
+pub use front_history::*;
 pub use manager::*;
 pub use platforms::*;
+pub use update_log::*;
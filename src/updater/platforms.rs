@@ -6,13 +6,31 @@ use strum_macros;
 
 use crate::{database, platforms, plurality, users};
 
-#[derive(Copy, Clone, Serialize, strum_macros::Display, Eq, Hash, PartialEq, specta::Type)]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Serialize,
+    strum_macros::Display,
+    Eq,
+    Hash,
+    PartialEq,
+    sqlx::Type,
+    specta::Type,
+)]
 #[specta(export)]
+#[sqlx(type_name = "updater_platform_enum")]
 pub enum Platform {
     VRChat,
     Discord,
     DiscordStatusMessage,
     ToPluralKit,
+    Webhook,
+    DiscordWebhook,
+    Matrix,
+    Mastodon,
+    Bluesky,
+    Mqtt,
 }
 
 pub enum Updater {
@@ -20,11 +38,27 @@ pub enum Updater {
     Discord(platforms::DiscordUpdater),
     DiscordStatusMessage(platforms::DiscordStatusMessageUpdater),
     ToPluralKit(platforms::ToPluralKitUpdater),
+    Webhook(platforms::WebhookUpdater),
+    DiscordWebhook(platforms::DiscordWebhookUpdater),
+    Matrix(platforms::MatrixUpdater),
+    Mastodon(platforms::MastodonUpdater),
+    Bluesky(platforms::BlueskyUpdater),
+    Mqtt(platforms::MqttUpdater),
 }
 
 #[must_use]
 pub fn available_updaters(discord_status_message: bool) -> Vec<Platform> {
-    let mut platforms = vec![Platform::VRChat, Platform::Discord, Platform::ToPluralKit];
+    let mut platforms = vec![
+        Platform::VRChat,
+        Platform::Discord,
+        Platform::ToPluralKit,
+        Platform::Webhook,
+        Platform::DiscordWebhook,
+        Platform::Matrix,
+        Platform::Mastodon,
+        Platform::Bluesky,
+        Platform::Mqtt,
+    ];
 
     if discord_status_message {
         platforms.push(Platform::DiscordStatusMessage);
@@ -56,7 +90,15 @@ impl Platform {
     pub const fn foreign_managed(&self) -> bool {
         match self {
             Self::Discord => true,
-            Self::DiscordStatusMessage | Self::VRChat | Self::ToPluralKit => false,
+            Self::DiscordStatusMessage
+            | Self::VRChat
+            | Self::ToPluralKit
+            | Self::Webhook
+            | Self::DiscordWebhook
+            | Self::Matrix
+            | Self::Mastodon
+            | Self::Bluesky
+            | Self::Mqtt => false,
         }
     }
 }
@@ -71,6 +113,12 @@ pub const fn initial_status(
         Platform::VRChat => config.enable_vrchat,
         Platform::DiscordStatusMessage => config.enable_discord_status_message,
         Platform::ToPluralKit => config.enable_to_pluralkit,
+        Platform::Webhook => config.enable_webhook,
+        Platform::DiscordWebhook => config.enable_discord_webhook,
+        Platform::Matrix => config.enable_matrix,
+        Platform::Mastodon => config.enable_mastodon,
+        Platform::Bluesky => config.enable_bluesky,
+        Platform::Mqtt => config.enable_mqtt,
     };
     if enabled {
         UpdaterStatus::Starting
@@ -89,6 +137,14 @@ impl Updater {
                 Self::DiscordStatusMessage(platforms::DiscordStatusMessageUpdater::new())
             }
             Platform::ToPluralKit => Self::ToPluralKit(platforms::ToPluralKitUpdater::new()),
+            Platform::Webhook => Self::Webhook(platforms::WebhookUpdater::new()),
+            Platform::DiscordWebhook => {
+                Self::DiscordWebhook(platforms::DiscordWebhookUpdater::new())
+            }
+            Platform::Matrix => Self::Matrix(platforms::MatrixUpdater::new()),
+            Platform::Mastodon => Self::Mastodon(platforms::MastodonUpdater::new()),
+            Platform::Bluesky => Self::Bluesky(platforms::BlueskyUpdater::new()),
+            Platform::Mqtt => Self::Mqtt(platforms::MqttUpdater::new()),
         }
     }
 
@@ -99,6 +155,12 @@ impl Updater {
             Self::Discord(_) => Platform::Discord,
             Self::DiscordStatusMessage(_) => Platform::DiscordStatusMessage,
             Self::ToPluralKit(_) => Platform::ToPluralKit,
+            Self::Webhook(_) => Platform::Webhook,
+            Self::DiscordWebhook(_) => Platform::DiscordWebhook,
+            Self::Matrix(_) => Platform::Matrix,
+            Self::Mastodon(_) => Platform::Mastodon,
+            Self::Bluesky(_) => Platform::Bluesky,
+            Self::Mqtt(_) => Platform::Mqtt,
         }
     }
 
@@ -118,6 +180,30 @@ impl Updater {
             Self::Discord(updater) => updater.last_operation_error.as_ref(),
             Self::DiscordStatusMessage(updater) => updater.last_operation_error.as_ref(),
             Self::ToPluralKit(updater) => updater.last_operation_error.as_ref(),
+            Self::Webhook(updater) => updater.last_operation_error.as_ref(),
+            Self::DiscordWebhook(updater) => updater.last_operation_error.as_ref(),
+            Self::Matrix(updater) => updater.last_operation_error.as_ref(),
+            Self::Mastodon(updater) => updater.last_operation_error.as_ref(),
+            Self::Bluesky(updater) => updater.last_operation_error.as_ref(),
+            Self::Mqtt(updater) => updater.last_operation_error.as_ref(),
+        }
+    }
+
+    /// The status string or payload which was last pushed (or attempted to be pushed) to the platform.
+    #[must_use]
+    pub const fn last_payload(&self) -> Option<&String> {
+        match self {
+            Self::VRChat(updater) => updater.last_payload.as_ref(),
+            // the rich presence is pushed by the bridge
+            Self::Discord(_) => None,
+            Self::DiscordStatusMessage(updater) => updater.last_payload.as_ref(),
+            Self::ToPluralKit(updater) => updater.last_payload.as_ref(),
+            Self::Webhook(updater) => updater.last_payload.as_ref(),
+            Self::DiscordWebhook(updater) => updater.last_payload.as_ref(),
+            Self::Matrix(updater) => updater.last_payload.as_ref(),
+            Self::Mastodon(updater) => updater.last_payload.as_ref(),
+            Self::Bluesky(updater) => updater.last_payload.as_ref(),
+            Self::Mqtt(updater) => updater.last_payload.as_ref(),
         }
     }
 
@@ -128,6 +214,12 @@ impl Updater {
             Self::Discord(_) => config.enable_discord,
             Self::DiscordStatusMessage(_) => config.enable_discord_status_message,
             Self::ToPluralKit(_) => config.enable_to_pluralkit,
+            Self::Webhook(_) => config.enable_webhook,
+            Self::DiscordWebhook(_) => config.enable_discord_webhook,
+            Self::Matrix(_) => config.enable_matrix,
+            Self::Mastodon(_) => config.enable_mastodon,
+            Self::Bluesky(_) => config.enable_bluesky,
+            Self::Mqtt(_) => config.enable_mqtt,
         }
     }
 
@@ -146,6 +238,12 @@ impl Updater {
             Self::Discord(updater) => updater.setup(config).await,
             Self::DiscordStatusMessage(updater) => updater.setup(config).await,
             Self::ToPluralKit(updater) => updater.setup(config).await,
+            Self::Webhook(updater) => updater.setup(config).await,
+            Self::DiscordWebhook(updater) => updater.setup(config, db_pool).await,
+            Self::Matrix(updater) => updater.setup(config).await,
+            Self::Mastodon(updater) => updater.setup(config).await,
+            Self::Bluesky(updater) => updater.setup(config).await,
+            Self::Mqtt(updater) => updater.setup(config).await,
         }
     }
 
@@ -161,6 +259,12 @@ impl Updater {
                 updater.update_fronting_status(config, fronts).await
             }
             Self::ToPluralKit(updater) => updater.update_fronting_status(config, fronts).await,
+            Self::Webhook(updater) => updater.update_fronting_status(config, fronts).await,
+            Self::DiscordWebhook(updater) => updater.update_fronting_status(config, fronts).await,
+            Self::Matrix(updater) => updater.update_fronting_status(config, fronts).await,
+            Self::Mastodon(updater) => updater.update_fronting_status(config, fronts).await,
+            Self::Bluesky(updater) => updater.update_fronting_status(config, fronts).await,
+            Self::Mqtt(updater) => updater.update_fronting_status(config, fronts).await,
         }
     }
 }
@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use pluralsync_base::clock;
+use serde::Serialize;
+use vrchatapi::apis::users_api::UpdateUserError;
+
+use crate::{database, updater, updater::Platform, users};
+
+/// Coarse classification of failed updates, such that users can tell apart e.g. an expired login from an outage.
+#[derive(
+    Debug, Clone, Copy, Serialize, PartialEq, Eq, sqlx::Type, specta::Type, strum_macros::Display,
+)]
+#[specta(export)]
+#[sqlx(type_name = "update_error_category_enum")]
+pub enum UpdateErrorCategory {
+    /// The credentials or token were rejected
+    Authentication,
+    RateLimited,
+    /// The platform responded with a server error
+    PlatformUnavailable,
+    /// The platform rejected the request for another reason
+    RequestRejected,
+    /// The platform couldn't be reached
+    Network,
+    /// The platform responded with something unexpected
+    InvalidResponse,
+    Other,
+}
+
+#[must_use]
+pub fn categorize_error(err: &anyhow::Error) -> UpdateErrorCategory {
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return categorize_reqwest_error(e);
+        }
+        if let Some(e) = cause.downcast_ref::<vrchatapi::apis::Error<UpdateUserError>>() {
+            return match e {
+                vrchatapi::apis::Error::Reqwest(e) => categorize_reqwest_error(e),
+                vrchatapi::apis::Error::ResponseError(response) => {
+                    categorize_status_code(response.status)
+                }
+                vrchatapi::apis::Error::Serde(_) => UpdateErrorCategory::InvalidResponse,
+                vrchatapi::apis::Error::Io(_) => UpdateErrorCategory::Network,
+            };
+        }
+        if cause.is::<serde_json::Error>() {
+            return UpdateErrorCategory::InvalidResponse;
+        }
+    }
+    UpdateErrorCategory::Other
+}
+
+fn categorize_reqwest_error(err: &reqwest::Error) -> UpdateErrorCategory {
+    match err.status() {
+        Some(status) => categorize_status_code(status),
+        None if err.is_timeout() || err.is_connect() || err.is_request() => {
+            UpdateErrorCategory::Network
+        }
+        None if err.is_decode() || err.is_body() => UpdateErrorCategory::InvalidResponse,
+        None => UpdateErrorCategory::Other,
+    }
+}
+
+fn categorize_status_code(status: reqwest::StatusCode) -> UpdateErrorCategory {
+    match status {
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+            UpdateErrorCategory::Authentication
+        }
+        reqwest::StatusCode::TOO_MANY_REQUESTS => UpdateErrorCategory::RateLimited,
+        s if s.is_server_error() => UpdateErrorCategory::PlatformUnavailable,
+        s if s.is_client_error() => UpdateErrorCategory::RequestRejected,
+        _ => UpdateErrorCategory::Other,
+    }
+}
+
+/// Persists the outcome of an update of a platform. Old entries are removed periodically by [`prune_expired_entries`].
+pub async fn record_update(
+    db_pool: &sqlx::PgPool,
+    config: &users::UserConfigForUpdater,
+    platform: Platform,
+    payload: Option<&String>,
+    result: &Result<()>,
+    duration: Duration,
+) -> Result<()> {
+    let user_id = &config.user_id;
+
+    if config.updater_log_retention_days == 0 {
+        log::info!("# | record_update | {user_id} | {platform} | disabled");
+        return Ok(());
+    }
+
+    let entry = database::UpdaterLogEntry {
+        platform,
+        updated_at: clock::now(),
+        payload: payload.cloned(),
+        success: result.is_ok(),
+        error: result.as_ref().err().map(ToString::to_string),
+        error_category: result.as_ref().err().map(categorize_error),
+        duration_ms: i32::try_from(duration.as_millis()).unwrap_or(i32::MAX),
+    };
+
+    database::insert_updater_log_entry(db_pool, user_id, &entry).await?;
+
+    log::info!(
+        "# | record_update | {user_id} | {platform} | success={}",
+        entry.success
+    );
+
+    Ok(())
+}
+
+/// Removes the front history and updater log entries, which are older than the retention periods of the users.
+/// Runs periodically instead of with every update, as the updates are frequent and the retention is measured in days.
+pub async fn prune_expired_entries(
+    db_pool: sqlx::PgPool,
+    _: updater::UpdaterManager,
+    _: database::ApplicationUserSecrets,
+) -> Result<()> {
+    log::info!("# | prune_expired_entries");
+
+    let now = clock::now();
+    let user_ids = database::get_all_users(&db_pool).await?;
+
+    for user_id in user_ids {
+        let user_config = database::get_user(&db_pool, &user_id)
+            .await?
+            .with_defaults();
+        let retention_start = |retention_days: Option<i32>| {
+            now - chrono::Duration::days(i64::from(retention_days.unwrap_or_default()))
+        };
+
+        let deleted_front_history = database::delete_front_history_before(
+            &db_pool,
+            &user_id,
+            retention_start(user_config.front_history_retention_days),
+        )
+        .await?;
+        let deleted_updater_log = database::delete_updater_log_before(
+            &db_pool,
+            &user_id,
+            retention_start(user_config.updater_log_retention_days),
+        )
+        .await?;
+
+        log::info!(
+            "# | prune_expired_entries | {user_id} | deleted {deleted_front_history} front history and {deleted_updater_log} updater log entries"
+        );
+    }
+
+    log::info!("# | prune_expired_entries | done");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_categorize_status_code() {
+        assert_eq!(
+            categorize_status_code(reqwest::StatusCode::UNAUTHORIZED),
+            UpdateErrorCategory::Authentication
+        );
+        assert_eq!(
+            categorize_status_code(reqwest::StatusCode::TOO_MANY_REQUESTS),
+            UpdateErrorCategory::RateLimited
+        );
+        assert_eq!(
+            categorize_status_code(reqwest::StatusCode::BAD_GATEWAY),
+            UpdateErrorCategory::PlatformUnavailable
+        );
+        assert_eq!(
+            categorize_status_code(reqwest::StatusCode::BAD_REQUEST),
+            UpdateErrorCategory::RequestRejected
+        );
+    }
+
+    #[test]
+    fn test_categorize_error() {
+        let vrchat_error: vrchatapi::apis::Error<UpdateUserError> =
+            vrchatapi::apis::Error::ResponseError(vrchatapi::apis::ResponseContent {
+                status: reqwest::StatusCode::UNAUTHORIZED,
+                content: String::new(),
+                entity: None,
+            });
+        assert_eq!(
+            categorize_error(&anyhow::Error::from(vrchat_error)),
+            UpdateErrorCategory::Authentication
+        );
+
+        let serde_error = serde_json::from_str::<u32>("no json").unwrap_err();
+        assert_eq!(
+            categorize_error(&anyhow::Error::from(serde_error).context("parsing")),
+            UpdateErrorCategory::InvalidResponse
+        );
+
+        assert_eq!(
+            categorize_error(&anyhow!("Updater not initalized!")),
+            UpdateErrorCategory::Other
+        );
+    }
+}
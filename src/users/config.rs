@@ -1,15 +1,22 @@
 use anyhow::{Result, anyhow};
 use sqlx::FromRow;
+use std::collections::HashMap;
 use std::time::Duration;
 
 use crate::{
     config_value, config_value_if,
     database::{self, Encrypted, SecretType},
     int_counter_metric,
+    platforms::{MatrixDisplayNameScope, PluralKitSwitchOrder},
+    plurality::{
+        self, DisplayNamePlatform, FronterOrder, FrontingSourceKind, GroupCollapsing,
+        UnsupportedCharacters,
+    },
     users::model::UserId,
 };
 use serde::{Deserialize, Serialize};
 use specta;
+use sqlx::{Decode, Postgres, error::BoxDynError, postgres};
 
 #[derive(
     Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, sqlx::Type, specta::Type,
@@ -23,6 +30,39 @@ pub enum PrivacyFineGrained {
     ViaPrivacyBuckets,
 }
 
+/// The display name custom field per platform. Platforms without an entry use the default one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, specta::Type)]
+#[serde(transparent)]
+#[specta(transparent)]
+pub struct DisplayNameFields(pub HashMap<DisplayNamePlatform, String>);
+
+impl DisplayNameFields {
+    #[must_use]
+    pub fn with_defaults(&self, defaults: Self) -> Self {
+        let mut fields = defaults.0;
+        fields.extend(self.0.clone());
+        Self(fields)
+    }
+}
+
+// stored as JSON, which the queries read as text
+impl sqlx::Type<Postgres> for DisplayNameFields {
+    fn type_info() -> postgres::PgTypeInfo {
+        <String as sqlx::Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &postgres::PgTypeInfo) -> bool {
+        <String as sqlx::Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for DisplayNameFields {
+    fn decode(value: postgres::PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let json = <&str as Decode<Postgres>>::decode(value)?;
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq, Eq)]
 pub struct UserConfigDbEntries<Secret, Constraints = database::InvalidConstraints>
@@ -47,6 +87,173 @@ where
     pub privacy_fine_grained: PrivacyFineGrained,
     pub privacy_fine_grained_buckets: Option<Vec<String>>,
 
+    // configs posted before the fronting source was configurable don't contain it
+    #[serde(default)]
+    pub fronting_source: FrontingSourceKind,
+
+    // show the custom status of the front next to the fronter, e.g. "Alex (blurry)"
+    #[serde(default)]
+    pub website_show_custom_status: bool,
+    #[serde(default)]
+    pub discord_show_custom_status: bool,
+    #[serde(default)]
+    pub discord_status_message_show_custom_status: bool,
+    #[serde(default)]
+    pub vrchat_show_custom_status: bool,
+
+    #[serde(default)]
+    pub status_group_collapsing: GroupCollapsing,
+
+    #[serde(default)]
+    pub fronter_order: FronterOrder,
+
+    #[serde(default)]
+    pub fronter_order_manual: Option<Vec<String>>,
+
+    // per platform, name of the custom field whose value is shown instead of the member name. empty = disabled
+    #[serde(default)]
+    pub display_name_fields: DisplayNameFields,
+
+    // days for which the front history is kept. 0 = no front history is recorded
+    #[serde(default)]
+    pub front_history_retention_days: Option<i32>,
+
+    // days for which the outcomes of the updates are kept. 0 = no updater log is recorded
+    #[serde(default)]
+    pub updater_log_retention_days: Option<i32>,
+
+    // ordered fallback list of status templates, e.g. "{prefix} {names|join(", ")}". none = built-in shapes
+    #[serde(default)]
+    pub vrchat_status_templates: Option<Vec<String>>,
+    #[serde(default)]
+    pub discord_status_templates: Option<Vec<String>>,
+    #[serde(default)]
+    pub discord_status_message_status_templates: Option<Vec<String>>,
+    #[serde(default)]
+    pub website_status_templates: Option<Vec<String>>,
+
+    // what to do with characters which VRChat can't display, e.g. "さくら"
+    #[serde(default)]
+    pub vrchat_unsupported_characters: UnsupportedCharacters,
+
+    // per-platform overrides of the status_* fields above. none = use the global value
+    #[serde(default)]
+    pub vrchat_status_prefix: Option<String>,
+    #[serde(default)]
+    pub discord_status_prefix: Option<String>,
+    #[serde(default)]
+    pub discord_status_message_status_prefix: Option<String>,
+    #[serde(default)]
+    pub website_status_prefix: Option<String>,
+
+    #[serde(default)]
+    pub vrchat_status_no_fronts: Option<String>,
+    #[serde(default)]
+    pub discord_status_no_fronts: Option<String>,
+    #[serde(default)]
+    pub discord_status_message_status_no_fronts: Option<String>,
+    #[serde(default)]
+    pub website_status_no_fronts: Option<String>,
+
+    #[serde(default)]
+    pub vrchat_status_truncate_names_to: Option<i32>,
+    #[serde(default)]
+    pub discord_status_truncate_names_to: Option<i32>,
+    #[serde(default)]
+    pub discord_status_message_status_truncate_names_to: Option<i32>,
+    #[serde(default)]
+    pub website_status_truncate_names_to: Option<i32>,
+
+    #[serde(default)]
+    pub vrchat_status_group_collapsing: Option<GroupCollapsing>,
+    #[serde(default)]
+    pub discord_status_group_collapsing: Option<GroupCollapsing>,
+    #[serde(default)]
+    pub discord_status_message_status_group_collapsing: Option<GroupCollapsing>,
+    #[serde(default)]
+    pub website_status_group_collapsing: Option<GroupCollapsing>,
+
+    // outbound webhook which receives the fronters on every change. signed with webhook_signing_secret
+    #[serde(default)]
+    pub enable_webhook: bool,
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+
+    // announcements of the fronters as embeds in a discord channel. the webhook url contains a token, hence it's a secret.
+    #[serde(default)]
+    pub enable_discord_webhook: bool,
+    #[serde(default)]
+    pub discord_webhook_edit_message: bool,
+
+    // matrix presence status and optionally the displayname. authenticated with matrix_access_token
+    #[serde(default)]
+    pub enable_matrix: bool,
+    #[serde(default)]
+    pub matrix_homeserver_url: Option<String>,
+    #[serde(default)]
+    pub matrix_displayname_scope: MatrixDisplayNameScope,
+    /// Room IDs (e.g. "!abc:matrix.org") in which the displayname is set, if the scope is `Rooms`.
+    #[serde(default)]
+    pub matrix_displayname_rooms: Option<Vec<String>>,
+
+    // mastodon profile field and optionally a suffix of the display name. authenticated with mastodon_token
+    #[serde(default)]
+    pub enable_mastodon: bool,
+    #[serde(default)]
+    pub mastodon_instance_url: Option<String>,
+    #[serde(default)]
+    pub mastodon_field_name: Option<String>,
+    /// The display name without the fronting status. If set, the fronting status is appended to it.
+    #[serde(default)]
+    pub mastodon_display_name: Option<String>,
+
+    // bluesky profile description section and optionally the display name. authenticated with bluesky_app_password
+    #[serde(default)]
+    pub enable_bluesky: bool,
+    #[serde(default)]
+    pub bluesky_pds_url: Option<String>,
+    /// Handle (e.g. "oursystem.bsky.social") or DID of the account
+    #[serde(default)]
+    pub bluesky_identifier: Option<String>,
+    /// The display name without the fronting status. If set, the fronting status is appended to it.
+    #[serde(default)]
+    pub bluesky_display_name: Option<String>,
+
+    // publishes the fronters, status and fronter count as retained messages. authenticated with mqtt_password
+    #[serde(default)]
+    pub enable_mqtt: bool,
+    /// e.g. "mqtt://homeassistant.local:1883" or "mqtts://broker.example:8883"
+    #[serde(default)]
+    pub mqtt_broker_url: Option<String>,
+    #[serde(default)]
+    pub mqtt_username: Option<String>,
+    #[serde(default)]
+    pub mqtt_fronters_topic: Option<String>,
+    #[serde(default)]
+    pub mqtt_status_topic: Option<String>,
+    #[serde(default)]
+    pub mqtt_fronter_count_topic: Option<String>,
+    #[serde(default)]
+    pub mqtt_home_assistant_discovery: bool,
+    #[serde(default)]
+    pub mqtt_home_assistant_discovery_prefix: Option<String>,
+
+    // allows creating and updating the PluralKit members from the Simply Plural members. uses pluralkit_token
+    #[serde(default)]
+    pub enable_pluralkit_system_sync: bool,
+
+    // writes the switches from PluralKit into the Simply Plural front history. requires enable_to_pluralkit
+    #[serde(default)]
+    pub enable_from_pluralkit: bool,
+
+    // the order of the members in the switches created in PluralKit. drives proxying and autoproxy
+    #[serde(default)]
+    pub pluralkit_switch_order: PluralKitSwitchOrder,
+
+    // Simply Plural or PluralKit member ID. used by PluralKitSwitchOrder::PrimaryMemberFirst
+    #[serde(default)]
+    pub pluralkit_switch_primary_member: Option<String>,
+
     pub enable_website: bool,
     pub enable_discord: bool,
     pub enable_discord_status_message: bool,
@@ -62,10 +269,26 @@ where
     pub vrchat_password: Option<Secret>,
     pub vrchat_cookie: Option<Secret>,
     pub pluralkit_token: Option<Secret>,
+    #[serde(default)]
+    pub webhook_signing_secret: Option<Secret>,
+    #[serde(default)]
+    pub discord_webhook_url: Option<Secret>,
+    #[serde(default)]
+    pub matrix_access_token: Option<Secret>,
+    #[serde(default)]
+    pub mastodon_token: Option<Secret>,
+    #[serde(default)]
+    pub bluesky_app_password: Option<Secret>,
+    #[serde(default)]
+    pub mqtt_password: Option<Secret>,
+    // verifies the events of the PluralKit dispatch webhook, which notifies about new switches
+    #[serde(default)]
+    pub pluralkit_dispatch_signing_token: Option<Secret>,
 }
 
 impl<S: SecretType> UserConfigDbEntries<S> {
     #[must_use]
+    #[allow(clippy::too_many_lines)]
     pub fn with_defaults(&self) -> Self {
         let defaults: Self = Self::default();
         Self {
@@ -88,6 +311,164 @@ impl<S: SecretType> UserConfigDbEntries<S> {
                 .privacy_fine_grained_buckets
                 .clone()
                 .or(defaults.privacy_fine_grained_buckets),
+            fronting_source: self.fronting_source,
+            website_show_custom_status: self.website_show_custom_status,
+            discord_show_custom_status: self.discord_show_custom_status,
+            discord_status_message_show_custom_status: self
+                .discord_status_message_show_custom_status,
+            vrchat_show_custom_status: self.vrchat_show_custom_status,
+            status_group_collapsing: self.status_group_collapsing,
+            fronter_order: self.fronter_order,
+            fronter_order_manual: self
+                .fronter_order_manual
+                .clone()
+                .or(defaults.fronter_order_manual),
+            display_name_fields: self
+                .display_name_fields
+                .with_defaults(defaults.display_name_fields),
+            front_history_retention_days: self
+                .front_history_retention_days
+                .or(defaults.front_history_retention_days),
+            updater_log_retention_days: self
+                .updater_log_retention_days
+                .or(defaults.updater_log_retention_days),
+            vrchat_status_templates: self
+                .vrchat_status_templates
+                .clone()
+                .or(defaults.vrchat_status_templates),
+            discord_status_templates: self
+                .discord_status_templates
+                .clone()
+                .or(defaults.discord_status_templates),
+            discord_status_message_status_templates: self
+                .discord_status_message_status_templates
+                .clone()
+                .or(defaults.discord_status_message_status_templates),
+            website_status_templates: self
+                .website_status_templates
+                .clone()
+                .or(defaults.website_status_templates),
+            vrchat_unsupported_characters: self.vrchat_unsupported_characters,
+            vrchat_status_prefix: self
+                .vrchat_status_prefix
+                .clone()
+                .or(defaults.vrchat_status_prefix),
+            discord_status_prefix: self
+                .discord_status_prefix
+                .clone()
+                .or(defaults.discord_status_prefix),
+            discord_status_message_status_prefix: self
+                .discord_status_message_status_prefix
+                .clone()
+                .or(defaults.discord_status_message_status_prefix),
+            website_status_prefix: self
+                .website_status_prefix
+                .clone()
+                .or(defaults.website_status_prefix),
+            vrchat_status_no_fronts: self
+                .vrchat_status_no_fronts
+                .clone()
+                .or(defaults.vrchat_status_no_fronts),
+            discord_status_no_fronts: self
+                .discord_status_no_fronts
+                .clone()
+                .or(defaults.discord_status_no_fronts),
+            discord_status_message_status_no_fronts: self
+                .discord_status_message_status_no_fronts
+                .clone()
+                .or(defaults.discord_status_message_status_no_fronts),
+            website_status_no_fronts: self
+                .website_status_no_fronts
+                .clone()
+                .or(defaults.website_status_no_fronts),
+            vrchat_status_truncate_names_to: self
+                .vrchat_status_truncate_names_to
+                .or(defaults.vrchat_status_truncate_names_to),
+            discord_status_truncate_names_to: self
+                .discord_status_truncate_names_to
+                .or(defaults.discord_status_truncate_names_to),
+            discord_status_message_status_truncate_names_to: self
+                .discord_status_message_status_truncate_names_to
+                .or(defaults.discord_status_message_status_truncate_names_to),
+            website_status_truncate_names_to: self
+                .website_status_truncate_names_to
+                .or(defaults.website_status_truncate_names_to),
+            vrchat_status_group_collapsing: self
+                .vrchat_status_group_collapsing
+                .or(defaults.vrchat_status_group_collapsing),
+            discord_status_group_collapsing: self
+                .discord_status_group_collapsing
+                .or(defaults.discord_status_group_collapsing),
+            discord_status_message_status_group_collapsing: self
+                .discord_status_message_status_group_collapsing
+                .or(defaults.discord_status_message_status_group_collapsing),
+            website_status_group_collapsing: self
+                .website_status_group_collapsing
+                .or(defaults.website_status_group_collapsing),
+            enable_webhook: self.enable_webhook,
+            webhook_url: self.webhook_url.clone().or(defaults.webhook_url),
+            enable_discord_webhook: self.enable_discord_webhook,
+            discord_webhook_edit_message: self.discord_webhook_edit_message,
+            enable_matrix: self.enable_matrix,
+            matrix_homeserver_url: self
+                .matrix_homeserver_url
+                .clone()
+                .or(defaults.matrix_homeserver_url),
+            matrix_displayname_scope: self.matrix_displayname_scope,
+            matrix_displayname_rooms: self
+                .matrix_displayname_rooms
+                .clone()
+                .or(defaults.matrix_displayname_rooms),
+            enable_mastodon: self.enable_mastodon,
+            mastodon_instance_url: self
+                .mastodon_instance_url
+                .clone()
+                .or(defaults.mastodon_instance_url),
+            mastodon_field_name: self
+                .mastodon_field_name
+                .clone()
+                .or(defaults.mastodon_field_name),
+            mastodon_display_name: self
+                .mastodon_display_name
+                .clone()
+                .or(defaults.mastodon_display_name),
+            enable_bluesky: self.enable_bluesky,
+            bluesky_pds_url: self.bluesky_pds_url.clone().or(defaults.bluesky_pds_url),
+            bluesky_identifier: self
+                .bluesky_identifier
+                .clone()
+                .or(defaults.bluesky_identifier),
+            bluesky_display_name: self
+                .bluesky_display_name
+                .clone()
+                .or(defaults.bluesky_display_name),
+            enable_mqtt: self.enable_mqtt,
+            mqtt_broker_url: self.mqtt_broker_url.clone().or(defaults.mqtt_broker_url),
+            mqtt_username: self.mqtt_username.clone().or(defaults.mqtt_username),
+            mqtt_fronters_topic: self
+                .mqtt_fronters_topic
+                .clone()
+                .or(defaults.mqtt_fronters_topic),
+            mqtt_status_topic: self
+                .mqtt_status_topic
+                .clone()
+                .or(defaults.mqtt_status_topic),
+            mqtt_fronter_count_topic: self
+                .mqtt_fronter_count_topic
+                .clone()
+                .or(defaults.mqtt_fronter_count_topic),
+            mqtt_home_assistant_discovery: self.mqtt_home_assistant_discovery,
+            mqtt_home_assistant_discovery_prefix: self
+                .mqtt_home_assistant_discovery_prefix
+                .clone()
+                .or(defaults.mqtt_home_assistant_discovery_prefix),
+            enable_pluralkit_system_sync: self.enable_pluralkit_system_sync,
+            enable_from_pluralkit: self.enable_from_pluralkit,
+            pluralkit_switch_order: self.pluralkit_switch_order,
+            pluralkit_switch_primary_member: self
+                .pluralkit_switch_primary_member
+                .clone()
+                .or(defaults.pluralkit_switch_primary_member),
             enable_website: self.enable_website,
             enable_discord: self.enable_discord,
             enable_discord_status_message: self.enable_discord_status_message,
@@ -105,6 +486,28 @@ impl<S: SecretType> UserConfigDbEntries<S> {
             vrchat_password: self.vrchat_password.clone().or(defaults.vrchat_password),
             vrchat_cookie: self.vrchat_cookie.clone().or(defaults.vrchat_cookie),
             pluralkit_token: self.pluralkit_token.clone().or(defaults.pluralkit_token),
+            webhook_signing_secret: self
+                .webhook_signing_secret
+                .clone()
+                .or(defaults.webhook_signing_secret),
+            discord_webhook_url: self
+                .discord_webhook_url
+                .clone()
+                .or(defaults.discord_webhook_url),
+            matrix_access_token: self
+                .matrix_access_token
+                .clone()
+                .or(defaults.matrix_access_token),
+            mastodon_token: self.mastodon_token.clone().or(defaults.mastodon_token),
+            bluesky_app_password: self
+                .bluesky_app_password
+                .clone()
+                .or(defaults.bluesky_app_password),
+            mqtt_password: self.mqtt_password.clone().or(defaults.mqtt_password),
+            pluralkit_dispatch_signing_token: self
+                .pluralkit_dispatch_signing_token
+                .clone()
+                .or(defaults.pluralkit_dispatch_signing_token),
             valid_constraints: self.valid_constraints.clone(), // Constraints are not defaulted
         }
     }
@@ -122,11 +525,85 @@ impl<S: SecretType> Default for UserConfigDbEntries<S> {
             respect_front_notifications_disabled: true,
             privacy_fine_grained: PrivacyFineGrained::default(),
             privacy_fine_grained_buckets: None,
+            fronting_source: FrontingSourceKind::default(),
             enable_website: false,
             enable_discord: false,
             enable_discord_status_message: false,
             enable_vrchat: false,
             enable_to_pluralkit: false,
+            website_show_custom_status: false,
+            discord_show_custom_status: false,
+            discord_status_message_show_custom_status: false,
+            vrchat_show_custom_status: false,
+            status_group_collapsing: GroupCollapsing::default(),
+            fronter_order: FronterOrder::default(),
+            fronter_order_manual: None,
+            display_name_fields: DisplayNameFields(
+                [
+                    DisplayNamePlatform::VRChat,
+                    DisplayNamePlatform::Discord,
+                    DisplayNamePlatform::DiscordStatusMessage,
+                ]
+                .into_iter()
+                .map(|platform| {
+                    (
+                        platform,
+                        String::from(plurality::SIMPLY_PLURAL_VRCHAT_STATUS_NAME_FIELD_NAME),
+                    )
+                })
+                .collect(),
+            ),
+            front_history_retention_days: Some(30),
+            updater_log_retention_days: Some(7),
+            vrchat_status_templates: None,
+            discord_status_templates: None,
+            discord_status_message_status_templates: None,
+            website_status_templates: None,
+            vrchat_unsupported_characters: UnsupportedCharacters::default(),
+            vrchat_status_prefix: None,
+            discord_status_prefix: None,
+            discord_status_message_status_prefix: None,
+            website_status_prefix: None,
+            vrchat_status_no_fronts: None,
+            discord_status_no_fronts: None,
+            discord_status_message_status_no_fronts: None,
+            website_status_no_fronts: None,
+            vrchat_status_truncate_names_to: None,
+            discord_status_truncate_names_to: None,
+            discord_status_message_status_truncate_names_to: None,
+            website_status_truncate_names_to: None,
+            vrchat_status_group_collapsing: None,
+            discord_status_group_collapsing: None,
+            discord_status_message_status_group_collapsing: None,
+            website_status_group_collapsing: None,
+            enable_webhook: false,
+            webhook_url: None,
+            enable_discord_webhook: false,
+            discord_webhook_edit_message: false,
+            enable_matrix: false,
+            matrix_homeserver_url: None,
+            matrix_displayname_scope: MatrixDisplayNameScope::default(),
+            matrix_displayname_rooms: None,
+            enable_mastodon: false,
+            mastodon_instance_url: None,
+            mastodon_field_name: Some(String::from("Fronting")),
+            mastodon_display_name: None,
+            enable_bluesky: false,
+            bluesky_pds_url: Some(String::from("https://bsky.social")),
+            bluesky_identifier: None,
+            bluesky_display_name: None,
+            enable_mqtt: false,
+            mqtt_broker_url: None,
+            mqtt_username: None,
+            mqtt_fronters_topic: Some(String::from("pluralsync/fronters")),
+            mqtt_status_topic: Some(String::from("pluralsync/status")),
+            mqtt_fronter_count_topic: Some(String::from("pluralsync/fronter_count")),
+            mqtt_home_assistant_discovery: false,
+            mqtt_home_assistant_discovery_prefix: Some(String::from("homeassistant")),
+            enable_pluralkit_system_sync: false,
+            enable_from_pluralkit: false,
+            pluralkit_switch_order: PluralKitSwitchOrder::default(),
+            pluralkit_switch_primary_member: None,
             valid_constraints: None,
             website_system_name: None,
             website_url_name: None,
@@ -136,11 +613,19 @@ impl<S: SecretType> Default for UserConfigDbEntries<S> {
             vrchat_password: None,
             vrchat_cookie: None,
             pluralkit_token: None,
+            webhook_signing_secret: None,
+            discord_webhook_url: None,
+            matrix_access_token: None,
+            mastodon_token: None,
+            bluesky_app_password: None,
+            mqtt_password: None,
+            pluralkit_dispatch_signing_token: None,
         }
     }
 }
 
 #[must_use]
+#[allow(clippy::too_many_lines)]
 pub fn metrics_config_values(user_config: &UserConfigDbEntries<Encrypted>) -> Vec<(String, bool)> {
     vec![
         ("enable_discord".to_owned(), user_config.enable_discord),
@@ -150,6 +635,23 @@ pub fn metrics_config_values(user_config: &UserConfigDbEntries<Encrypted>) -> Ve
             "enable_discord_status_message".to_owned(),
             user_config.enable_discord_status_message,
         ),
+        ("enable_webhook".to_owned(), user_config.enable_webhook),
+        (
+            "enable_discord_webhook".to_owned(),
+            user_config.enable_discord_webhook,
+        ),
+        ("enable_matrix".to_owned(), user_config.enable_matrix),
+        ("enable_mastodon".to_owned(), user_config.enable_mastodon),
+        ("enable_bluesky".to_owned(), user_config.enable_bluesky),
+        ("enable_mqtt".to_owned(), user_config.enable_mqtt),
+        (
+            "enable_pluralkit_system_sync".to_owned(),
+            user_config.enable_pluralkit_system_sync,
+        ),
+        (
+            "enable_from_pluralkit".to_owned(),
+            user_config.enable_from_pluralkit,
+        ),
         (
             "show_members_non_archived".to_owned(),
             user_config.show_members_non_archived,
@@ -166,6 +668,22 @@ pub fn metrics_config_values(user_config: &UserConfigDbEntries<Encrypted>) -> Ve
             "respect_front_notifications_disabled".to_owned(),
             user_config.respect_front_notifications_disabled,
         ),
+        (
+            "website_show_custom_status".to_owned(),
+            user_config.website_show_custom_status,
+        ),
+        (
+            "discord_show_custom_status".to_owned(),
+            user_config.discord_show_custom_status,
+        ),
+        (
+            "discord_status_message_show_custom_status".to_owned(),
+            user_config.discord_status_message_show_custom_status,
+        ),
+        (
+            "vrchat_show_custom_status".to_owned(),
+            user_config.vrchat_show_custom_status,
+        ),
         (
             format!(
                 "privacy_fine_grained_{:?}",
@@ -177,6 +695,32 @@ pub fn metrics_config_values(user_config: &UserConfigDbEntries<Encrypted>) -> Ve
             "privacy_fine_grained_buckets_set".to_owned(),
             user_config.privacy_fine_grained_buckets.is_some(),
         ),
+        (
+            format!("fronting_source_{}", user_config.fronting_source),
+            true,
+        ),
+        (format!("fronter_order_{}", user_config.fronter_order), true),
+        (
+            format!(
+                "pluralkit_switch_order_{}",
+                user_config.pluralkit_switch_order
+            ),
+            true,
+        ),
+        (
+            format!(
+                "status_group_collapsing_{}",
+                user_config.status_group_collapsing
+            ),
+            true,
+        ),
+        (
+            format!(
+                "vrchat_unsupported_characters_{}",
+                user_config.vrchat_unsupported_characters
+            ),
+            true,
+        ),
         (
             "status_prefix_set".to_owned(),
             user_config.status_prefix.is_some(),
@@ -189,16 +733,121 @@ pub fn metrics_config_values(user_config: &UserConfigDbEntries<Encrypted>) -> Ve
             "status_truncate_names_to_set".to_owned(),
             user_config.status_truncate_names_to.is_some(),
         ),
+        (
+            "display_name_fields_set".to_owned(),
+            !user_config.display_name_fields.0.is_empty(),
+        ),
+        (
+            "vrchat_status_templates_set".to_owned(),
+            user_config.vrchat_status_templates.is_some(),
+        ),
+        (
+            "discord_status_templates_set".to_owned(),
+            user_config.discord_status_templates.is_some(),
+        ),
+        (
+            "discord_status_message_status_templates_set".to_owned(),
+            user_config
+                .discord_status_message_status_templates
+                .is_some(),
+        ),
+        (
+            "website_status_templates_set".to_owned(),
+            user_config.website_status_templates.is_some(),
+        ),
+        (
+            "front_history_retention_days_set".to_owned(),
+            user_config.front_history_retention_days.is_some(),
+        ),
+        (
+            "updater_log_retention_days_set".to_owned(),
+            user_config.updater_log_retention_days.is_some(),
+        ),
+        (
+            "vrchat_status_prefix_set".to_owned(),
+            user_config.vrchat_status_prefix.is_some(),
+        ),
+        (
+            "discord_status_prefix_set".to_owned(),
+            user_config.discord_status_prefix.is_some(),
+        ),
+        (
+            "discord_status_message_status_prefix_set".to_owned(),
+            user_config.discord_status_message_status_prefix.is_some(),
+        ),
+        (
+            "website_status_prefix_set".to_owned(),
+            user_config.website_status_prefix.is_some(),
+        ),
+        (
+            "vrchat_status_no_fronts_set".to_owned(),
+            user_config.vrchat_status_no_fronts.is_some(),
+        ),
+        (
+            "discord_status_no_fronts_set".to_owned(),
+            user_config.discord_status_no_fronts.is_some(),
+        ),
+        (
+            "discord_status_message_status_no_fronts_set".to_owned(),
+            user_config
+                .discord_status_message_status_no_fronts
+                .is_some(),
+        ),
+        (
+            "website_status_no_fronts_set".to_owned(),
+            user_config.website_status_no_fronts.is_some(),
+        ),
+        (
+            "vrchat_status_truncate_names_to_set".to_owned(),
+            user_config.vrchat_status_truncate_names_to.is_some(),
+        ),
+        (
+            "discord_status_truncate_names_to_set".to_owned(),
+            user_config.discord_status_truncate_names_to.is_some(),
+        ),
+        (
+            "discord_status_message_status_truncate_names_to_set".to_owned(),
+            user_config
+                .discord_status_message_status_truncate_names_to
+                .is_some(),
+        ),
+        (
+            "website_status_truncate_names_to_set".to_owned(),
+            user_config.website_status_truncate_names_to.is_some(),
+        ),
+        (
+            "vrchat_status_group_collapsing_set".to_owned(),
+            user_config.vrchat_status_group_collapsing.is_some(),
+        ),
+        (
+            "discord_status_group_collapsing_set".to_owned(),
+            user_config.discord_status_group_collapsing.is_some(),
+        ),
+        (
+            "discord_status_message_status_group_collapsing_set".to_owned(),
+            user_config
+                .discord_status_message_status_group_collapsing
+                .is_some(),
+        ),
+        (
+            "website_status_group_collapsing_set".to_owned(),
+            user_config.website_status_group_collapsing.is_some(),
+        ),
     ]
 }
 
 /// user specific config values in the form needed for the updaters
 /// !! Never convert this back into a DB entry, as it contains defaults which should not be persisted into the DB.
 #[allow(clippy::struct_excessive_bools)]
+#[derive(Clone)]
 pub struct UserConfigForUpdater {
     pub client: reqwest::Client,
     pub user_id: UserId,
+    /// Only tests send to local mock servers. Users may not reach into the network of the server.
+    pub allow_private_network_urls: bool,
     pub simply_plural_base_url: String,
+    pub simply_plural_websocket_url: String,
+    pub pluralkit_base_url: String,
     pub discord_base_url: String,
 
     // Note: v Keep this in sync with UserConfigDbEntries AND the ts-bindings! v
@@ -214,14 +863,89 @@ pub struct UserConfigForUpdater {
     pub privacy_fine_grained: PrivacyFineGrained,
     pub privacy_fine_grained_buckets: Option<Vec<String>>,
 
+    pub fronting_source: FrontingSourceKind,
+
+    pub website_show_custom_status: bool,
+    pub discord_show_custom_status: bool,
+    pub discord_status_message_show_custom_status: bool,
+    pub vrchat_show_custom_status: bool,
+
+    pub status_group_collapsing: GroupCollapsing,
+
+    pub fronter_order: FronterOrder,
+
+    pub fronter_order_manual: Option<Vec<String>>,
+
+    /// custom field name per platform
+    pub display_name_fields: HashMap<DisplayNamePlatform, String>,
+
+    pub front_history_retention_days: u32,
+    pub updater_log_retention_days: u32,
+
+    pub vrchat_status_templates: Vec<plurality::StatusTemplate>,
+    pub discord_status_templates: Vec<plurality::StatusTemplate>,
+    pub discord_status_message_status_templates: Vec<plurality::StatusTemplate>,
+    pub website_status_templates: Vec<plurality::StatusTemplate>,
+
+    pub vrchat_unsupported_characters: UnsupportedCharacters,
+
+    pub vrchat_status_prefix: Option<String>,
+    pub discord_status_prefix: Option<String>,
+    pub discord_status_message_status_prefix: Option<String>,
+    pub website_status_prefix: Option<String>,
+
+    pub vrchat_status_no_fronts: Option<String>,
+    pub discord_status_no_fronts: Option<String>,
+    pub discord_status_message_status_no_fronts: Option<String>,
+    pub website_status_no_fronts: Option<String>,
+
+    pub vrchat_status_truncate_names_to: Option<usize>,
+    pub discord_status_truncate_names_to: Option<usize>,
+    pub discord_status_message_status_truncate_names_to: Option<usize>,
+    pub website_status_truncate_names_to: Option<usize>,
+
+    pub vrchat_status_group_collapsing: Option<GroupCollapsing>,
+    pub discord_status_group_collapsing: Option<GroupCollapsing>,
+    pub discord_status_message_status_group_collapsing: Option<GroupCollapsing>,
+    pub website_status_group_collapsing: Option<GroupCollapsing>,
+
     pub enable_website: bool,
     pub enable_discord: bool,
     pub enable_discord_status_message: bool,
     pub enable_vrchat: bool,
     pub enable_to_pluralkit: bool,
+    pub enable_webhook: bool,
+    pub enable_discord_webhook: bool,
+    pub enable_matrix: bool,
+    pub enable_mastodon: bool,
+    pub enable_bluesky: bool,
+    pub enable_mqtt: bool,
+    pub mqtt_home_assistant_discovery: bool,
+    pub enable_pluralkit_system_sync: bool,
+    pub enable_from_pluralkit: bool,
+    pub pluralkit_switch_order: PluralKitSwitchOrder,
+    pub pluralkit_switch_primary_member: Option<String>,
+
+    pub discord_webhook_edit_message: bool,
+    pub matrix_displayname_scope: MatrixDisplayNameScope,
+    pub matrix_displayname_rooms: Vec<String>,
+    pub mastodon_field_name: String,
+    pub mastodon_display_name: Option<String>,
+    pub bluesky_identifier: String,
+    pub bluesky_display_name: Option<String>,
+    pub mqtt_username: Option<String>,
+    pub mqtt_fronters_topic: String,
+    pub mqtt_status_topic: String,
+    pub mqtt_fronter_count_topic: String,
+    pub mqtt_home_assistant_discovery_prefix: String,
 
     pub website_url_name: String,
     pub website_system_name: String,
+    pub webhook_url: String,
+    pub matrix_homeserver_url: String,
+    pub mastodon_instance_url: String,
+    pub bluesky_pds_url: String,
+    pub mqtt_broker_url: String,
 
     pub simply_plural_token: database::Decrypted,
     pub discord_status_message_token: database::Decrypted,
@@ -229,6 +953,15 @@ pub struct UserConfigForUpdater {
     pub vrchat_password: database::Decrypted,
     pub vrchat_cookie: database::Decrypted,
     pub pluralkit_token: database::Decrypted,
+    pub webhook_signing_secret: database::Decrypted,
+    pub discord_webhook_url: database::Decrypted,
+    pub matrix_access_token: database::Decrypted,
+    pub mastodon_token: database::Decrypted,
+    pub bluesky_app_password: database::Decrypted,
+    /// Brokers may allow anonymous clients
+    pub mqtt_password: Option<database::Decrypted>,
+    /// Without it, changes in PluralKit are only noticed by polling
+    pub pluralkit_dispatch_signing_token: Option<database::Decrypted>,
 }
 
 #[derive(Clone, Deserialize, Serialize, Debug, Default)]
@@ -249,12 +982,26 @@ impl From<i32> for WaitSeconds {
     }
 }
 
+fn collect_display_name_fields<S: SecretType>(
+    config: &UserConfigDbEntries<S>,
+) -> HashMap<DisplayNamePlatform, String> {
+    config
+        .display_name_fields
+        .0
+        .iter()
+        .filter(|(_, field_name)| !field_name.trim().is_empty())
+        .map(|(platform, field_name)| (*platform, field_name.trim().to_owned()))
+        .collect()
+}
+
 int_counter_metric!(CONFIG_CREATE_WITH_STRONG_CONSTRAINTS_TOTAL_COUNT);
 int_counter_metric!(CONFIG_CREATE_WITH_STRONG_CONSTRAINTS_SUCCESS_COUNT);
 
+#[allow(clippy::too_many_lines)]
 pub fn create_config_with_strong_constraints<Constraints>(
     user_id: &UserId,
     client: &reqwest::Client,
+    simply_plural_endpoints: &plurality::SimplyPluralEndpoints,
     db_config: &UserConfigDbEntries<database::Decrypted, Constraints>,
 ) -> Result<(
     UserConfigForUpdater,
@@ -276,12 +1023,33 @@ where
     let enable_vrchat = local_config_with_defaults.enable_vrchat;
     let enable_website = local_config_with_defaults.enable_website;
     let enable_to_pluralkit = local_config_with_defaults.enable_to_pluralkit;
+    let enable_webhook = local_config_with_defaults.enable_webhook;
+    let enable_discord_webhook = local_config_with_defaults.enable_discord_webhook;
+    let enable_matrix = local_config_with_defaults.enable_matrix;
+    let enable_mastodon = local_config_with_defaults.enable_mastodon;
+    let enable_bluesky = local_config_with_defaults.enable_bluesky;
+    let enable_mqtt = local_config_with_defaults.enable_mqtt;
+    let mqtt_home_assistant_discovery =
+        enable_mqtt && local_config_with_defaults.mqtt_home_assistant_discovery;
+    let enable_pluralkit_system_sync = local_config_with_defaults.enable_pluralkit_system_sync;
+    let fronting_source = local_config_with_defaults.fronting_source;
+    let uses_simply_plural = fronting_source == FrontingSourceKind::SimplyPlural;
+    let uses_pluralkit = enable_to_pluralkit
+        || enable_pluralkit_system_sync
+        || fronting_source == FrontingSourceKind::PluralKit;
 
     let config = UserConfigForUpdater {
         user_id: user_id.clone(),
         client: client.clone(),
-        simply_plural_token: config_value!(local_config_with_defaults, simply_plural_token)?,
-        simply_plural_base_url: String::from("https://api.apparyllis.com/v1"),
+        allow_private_network_urls: false,
+        simply_plural_token: config_value_if!(
+            uses_simply_plural,
+            local_config_with_defaults,
+            simply_plural_token
+        )?,
+        simply_plural_base_url: simply_plural_endpoints.base_url.clone(),
+        simply_plural_websocket_url: simply_plural_endpoints.websocket_url.clone(),
+        pluralkit_base_url: String::from(plurality::PLURALKIT_BASE_URL),
         status_prefix: config_value!(local_config_with_defaults, status_prefix)?,
         status_no_fronts: config_value!(local_config_with_defaults, status_no_fronts)?,
         status_truncate_names_to: config_value!(
@@ -298,11 +1066,124 @@ where
         privacy_fine_grained_buckets: local_config_with_defaults
             .privacy_fine_grained_buckets
             .clone(),
+        fronting_source,
+        website_show_custom_status: local_config_with_defaults.website_show_custom_status,
+        discord_show_custom_status: local_config_with_defaults.discord_show_custom_status,
+        discord_status_message_show_custom_status: local_config_with_defaults.discord_status_message_show_custom_status,
+        vrchat_show_custom_status: local_config_with_defaults.vrchat_show_custom_status,
+        status_group_collapsing: local_config_with_defaults.status_group_collapsing,
+        fronter_order: local_config_with_defaults.fronter_order,
+        fronter_order_manual: local_config_with_defaults.fronter_order_manual.clone(),
+        display_name_fields: collect_display_name_fields(&local_config_with_defaults),
+        front_history_retention_days: config_value!(local_config_with_defaults, front_history_retention_days)?.try_into()?,
+        updater_log_retention_days: config_value!(local_config_with_defaults, updater_log_retention_days)?.try_into()?,
+        vrchat_status_templates: plurality::parse_status_templates("vrchat_status_templates", local_config_with_defaults.vrchat_status_templates.as_deref().unwrap_or_default())?,
+        discord_status_templates: plurality::parse_status_templates("discord_status_templates", local_config_with_defaults.discord_status_templates.as_deref().unwrap_or_default())?,
+        discord_status_message_status_templates: plurality::parse_status_templates("discord_status_message_status_templates", local_config_with_defaults.discord_status_message_status_templates.as_deref().unwrap_or_default())?,
+        website_status_templates: plurality::parse_status_templates("website_status_templates", local_config_with_defaults.website_status_templates.as_deref().unwrap_or_default())?,
+        vrchat_unsupported_characters: local_config_with_defaults.vrchat_unsupported_characters,
+        vrchat_status_prefix: local_config_with_defaults.vrchat_status_prefix.clone(),
+        discord_status_prefix: local_config_with_defaults.discord_status_prefix.clone(),
+        discord_status_message_status_prefix: local_config_with_defaults.discord_status_message_status_prefix.clone(),
+        website_status_prefix: local_config_with_defaults.website_status_prefix.clone(),
+        vrchat_status_no_fronts: local_config_with_defaults.vrchat_status_no_fronts.clone(),
+        discord_status_no_fronts: local_config_with_defaults.discord_status_no_fronts.clone(),
+        discord_status_message_status_no_fronts: local_config_with_defaults.discord_status_message_status_no_fronts.clone(),
+        website_status_no_fronts: local_config_with_defaults.website_status_no_fronts.clone(),
+        vrchat_status_truncate_names_to: local_config_with_defaults.vrchat_status_truncate_names_to.map(usize::try_from).transpose()?,
+        discord_status_truncate_names_to: local_config_with_defaults.discord_status_truncate_names_to.map(usize::try_from).transpose()?,
+        discord_status_message_status_truncate_names_to: local_config_with_defaults.discord_status_message_status_truncate_names_to.map(usize::try_from).transpose()?,
+        website_status_truncate_names_to: local_config_with_defaults.website_status_truncate_names_to.map(usize::try_from).transpose()?,
+        vrchat_status_group_collapsing: local_config_with_defaults.vrchat_status_group_collapsing,
+        discord_status_group_collapsing: local_config_with_defaults.discord_status_group_collapsing,
+        discord_status_message_status_group_collapsing: local_config_with_defaults.discord_status_message_status_group_collapsing,
+        website_status_group_collapsing: local_config_with_defaults.website_status_group_collapsing,
         enable_website,
         enable_discord,
         enable_discord_status_message,
         enable_vrchat,
         enable_to_pluralkit,
+        enable_webhook,
+        enable_discord_webhook,
+        discord_webhook_edit_message: local_config_with_defaults.discord_webhook_edit_message,
+        enable_matrix,
+        matrix_displayname_scope: local_config_with_defaults.matrix_displayname_scope,
+        matrix_displayname_rooms: local_config_with_defaults
+            .matrix_displayname_rooms
+            .clone()
+            .unwrap_or_default(),
+        matrix_homeserver_url: config_value_if!(
+            enable_matrix,
+            local_config_with_defaults,
+            matrix_homeserver_url
+        )?,
+        enable_mastodon,
+        mastodon_instance_url: config_value_if!(
+            enable_mastodon,
+            local_config_with_defaults,
+            mastodon_instance_url
+        )?,
+        mastodon_field_name: config_value_if!(
+            enable_mastodon,
+            local_config_with_defaults,
+            mastodon_field_name
+        )?,
+        mastodon_display_name: local_config_with_defaults
+            .mastodon_display_name
+            .clone()
+            .filter(|name| !name.trim().is_empty()),
+        enable_bluesky,
+        bluesky_pds_url: config_value_if!(
+            enable_bluesky,
+            local_config_with_defaults,
+            bluesky_pds_url
+        )?,
+        bluesky_identifier: config_value_if!(
+            enable_bluesky,
+            local_config_with_defaults,
+            bluesky_identifier
+        )?,
+        bluesky_display_name: local_config_with_defaults
+            .bluesky_display_name
+            .clone()
+            .filter(|name| !name.trim().is_empty()),
+        enable_mqtt,
+        mqtt_broker_url: config_value_if!(
+            enable_mqtt,
+            local_config_with_defaults,
+            mqtt_broker_url
+        )?,
+        mqtt_username: local_config_with_defaults
+            .mqtt_username
+            .clone()
+            .filter(|username| !username.is_empty()),
+        mqtt_fronters_topic: config_value_if!(
+            enable_mqtt,
+            local_config_with_defaults,
+            mqtt_fronters_topic
+        )?,
+        mqtt_status_topic: config_value_if!(
+            enable_mqtt,
+            local_config_with_defaults,
+            mqtt_status_topic
+        )?,
+        mqtt_fronter_count_topic: config_value_if!(
+            enable_mqtt,
+            local_config_with_defaults,
+            mqtt_fronter_count_topic
+        )?,
+        mqtt_home_assistant_discovery,
+        mqtt_home_assistant_discovery_prefix: config_value_if!(
+            mqtt_home_assistant_discovery,
+            local_config_with_defaults,
+            mqtt_home_assistant_discovery_prefix
+        )?,
+        enable_pluralkit_system_sync,
+        enable_from_pluralkit: local_config_with_defaults.enable_from_pluralkit,
+        pluralkit_switch_order: local_config_with_defaults.pluralkit_switch_order,
+        pluralkit_switch_primary_member: local_config_with_defaults
+            .pluralkit_switch_primary_member
+            .clone(),
         website_url_name: config_value_if!(
             enable_website,
             local_config_with_defaults,
@@ -313,6 +1194,7 @@ where
             local_config_with_defaults,
             website_system_name
         )?,
+        webhook_url: config_value_if!(enable_webhook, local_config_with_defaults, webhook_url)?,
         discord_base_url: if enable_discord_status_message {
             String::from("https://discord.com")
         } else {
@@ -337,10 +1219,44 @@ where
             .inspect(|_| log::info!("create_config_with_strong_constraints | {user_id} | vrchat cookie found and will be used."))
             .unwrap_or_default(),
         pluralkit_token: config_value_if!(
-            enable_to_pluralkit,
+            uses_pluralkit,
             local_config_with_defaults,
             pluralkit_token
         )?,
+        webhook_signing_secret: config_value_if!(
+            enable_webhook,
+            local_config_with_defaults,
+            webhook_signing_secret
+        )?,
+        discord_webhook_url: config_value_if!(
+            enable_discord_webhook,
+            local_config_with_defaults,
+            discord_webhook_url
+        )?,
+        matrix_access_token: config_value_if!(
+            enable_matrix,
+            local_config_with_defaults,
+            matrix_access_token
+        )?,
+        mastodon_token: config_value_if!(
+            enable_mastodon,
+            local_config_with_defaults,
+            mastodon_token
+        )?,
+        bluesky_app_password: config_value_if!(
+            enable_bluesky,
+            local_config_with_defaults,
+            bluesky_app_password
+        )?,
+        mqtt_password: local_config_with_defaults
+            .mqtt_password
+            .clone()
+            .filter(|_| enable_mqtt),
+        pluralkit_dispatch_signing_token: local_config_with_defaults
+            .pluralkit_dispatch_signing_token
+            .clone()
+            .filter(|token| !token.secret.is_empty())
+            .filter(|_| fronting_source == FrontingSourceKind::PluralKit),
     };
 
     if config.privacy_fine_grained == PrivacyFineGrained::ViaPrivacyBuckets
@@ -352,6 +1268,125 @@ where
         ));
     }
 
+    if config.fronter_order == FronterOrder::Manual && config.fronter_order_manual.is_none() {
+        return Err(anyhow!(
+            "fronter_order_manual must be set, because fronter_order is {:?}",
+            FronterOrder::Manual
+        ));
+    }
+
+    if config.fronting_source == FrontingSourceKind::PluralKit && config.enable_to_pluralkit {
+        return Err(anyhow!(
+            "enable_to_pluralkit cannot be used, because fronting_source is {:?}",
+            FrontingSourceKind::PluralKit
+        ));
+    }
+
+    if config.fronting_source == FrontingSourceKind::PluralKit
+        && config.enable_pluralkit_system_sync
+    {
+        return Err(anyhow!(
+            "enable_pluralkit_system_sync cannot be used, because the members are synced from Simply Plural but fronting_source is {:?}",
+            FrontingSourceKind::PluralKit
+        ));
+    }
+
+    if config.enable_from_pluralkit && !config.enable_to_pluralkit {
+        return Err(anyhow!(
+            "enable_from_pluralkit requires enable_to_pluralkit, because the fronts are synced in both directions"
+        ));
+    }
+
+    if config.pluralkit_switch_order == PluralKitSwitchOrder::PrimaryMemberFirst
+        && config.pluralkit_switch_primary_member.is_none()
+    {
+        return Err(anyhow!(
+            "pluralkit_switch_primary_member must be set, because pluralkit_switch_order is {:?}",
+            PluralKitSwitchOrder::PrimaryMemberFirst
+        ));
+    }
+
+    if config.enable_webhook {
+        let webhook_url = reqwest::Url::parse(&config.webhook_url)
+            .map_err(|e| anyhow!("webhook_url is not a valid URL: {e}"))?;
+        if !["http", "https"].contains(&webhook_url.scheme()) {
+            return Err(anyhow!("webhook_url must be a http or https URL"));
+        }
+        if config.webhook_signing_secret.secret.is_empty() {
+            return Err(anyhow!(
+                "webhook_signing_secret must be set, because enable_webhook is true"
+            ));
+        }
+    }
+
+    if config.enable_discord_webhook {
+        let discord_webhook_url = reqwest::Url::parse(&config.discord_webhook_url.secret)
+            .map_err(|e| anyhow!("discord_webhook_url is not a valid URL: {e}"))?;
+        if !["http", "https"].contains(&discord_webhook_url.scheme()) {
+            return Err(anyhow!("discord_webhook_url must be a http or https URL"));
+        }
+    }
+
+    if config.enable_matrix {
+        let matrix_homeserver_url = reqwest::Url::parse(&config.matrix_homeserver_url)
+            .map_err(|e| anyhow!("matrix_homeserver_url is not a valid URL: {e}"))?;
+        if !["http", "https"].contains(&matrix_homeserver_url.scheme()) {
+            return Err(anyhow!("matrix_homeserver_url must be a http or https URL"));
+        }
+        if config.matrix_displayname_scope == MatrixDisplayNameScope::Rooms
+            && config.matrix_displayname_rooms.is_empty()
+        {
+            return Err(anyhow!(
+                "matrix_displayname_rooms must be set, because matrix_displayname_scope is {:?}",
+                MatrixDisplayNameScope::Rooms
+            ));
+        }
+    }
+
+    if config.enable_mastodon {
+        let mastodon_instance_url = reqwest::Url::parse(&config.mastodon_instance_url)
+            .map_err(|e| anyhow!("mastodon_instance_url is not a valid URL: {e}"))?;
+        if !["http", "https"].contains(&mastodon_instance_url.scheme()) {
+            return Err(anyhow!("mastodon_instance_url must be a http or https URL"));
+        }
+        if config.mastodon_field_name.trim().is_empty() {
+            return Err(anyhow!(
+                "mastodon_field_name must not be empty, because enable_mastodon is true"
+            ));
+        }
+    }
+
+    if config.enable_bluesky {
+        let bluesky_pds_url = reqwest::Url::parse(&config.bluesky_pds_url)
+            .map_err(|e| anyhow!("bluesky_pds_url is not a valid URL: {e}"))?;
+        if !["http", "https"].contains(&bluesky_pds_url.scheme()) {
+            return Err(anyhow!("bluesky_pds_url must be a http or https URL"));
+        }
+    }
+
+    if config.enable_mqtt {
+        let mqtt_broker_url = reqwest::Url::parse(&config.mqtt_broker_url)
+            .map_err(|e| anyhow!("mqtt_broker_url is not a valid URL: {e}"))?;
+        if !["mqtt", "mqtts"].contains(&mqtt_broker_url.scheme())
+            || mqtt_broker_url.host_str().is_none()
+        {
+            return Err(anyhow!(
+                "mqtt_broker_url must be a mqtt or mqtts URL with a host, e.g. mqtt://homeassistant.local:1883"
+            ));
+        }
+        for topic in [
+            &config.mqtt_fronters_topic,
+            &config.mqtt_status_topic,
+            &config.mqtt_fronter_count_topic,
+        ] {
+            if topic.is_empty() || topic.contains(['+', '#']) {
+                return Err(anyhow!(
+                    "MQTT topics must not be empty and must not contain wildcards: '{topic}'"
+                ));
+            }
+        }
+    }
+
     log::info!("# | create_config_with_strong_constraints | {user_id} | created");
 
     let valid_config =
@@ -394,7 +1429,67 @@ mod tests {
             show_custom_fronts: false,
             respect_front_notifications_disabled: true,
             privacy_fine_grained: PrivacyFineGrained::ViaPrivacyBuckets,
+            status_group_collapsing: GroupCollapsing::WhenTooLong,
+            fronter_order: FronterOrder::SourceOrder,
+            fronter_order_manual: None,
+            display_name_fields: DisplayNameFields::default(),
+            front_history_retention_days: None,
+            updater_log_retention_days: None,
+            vrchat_status_templates: None,
+            discord_status_templates: None,
+            discord_status_message_status_templates: None,
+            website_status_templates: None,
+            vrchat_unsupported_characters: UnsupportedCharacters::Drop,
+            vrchat_status_prefix: None,
+            discord_status_prefix: None,
+            discord_status_message_status_prefix: None,
+            website_status_prefix: None,
+            vrchat_status_no_fronts: None,
+            discord_status_no_fronts: None,
+            discord_status_message_status_no_fronts: None,
+            website_status_no_fronts: None,
+            vrchat_status_truncate_names_to: None,
+            discord_status_truncate_names_to: None,
+            discord_status_message_status_truncate_names_to: None,
+            website_status_truncate_names_to: None,
+            vrchat_status_group_collapsing: None,
+            discord_status_group_collapsing: None,
+            discord_status_message_status_group_collapsing: None,
+            website_status_group_collapsing: None,
+            enable_webhook: false,
+            webhook_url: None,
+            enable_discord_webhook: false,
+            discord_webhook_edit_message: false,
+            enable_matrix: false,
+            matrix_homeserver_url: None,
+            matrix_displayname_scope: MatrixDisplayNameScope::Unchanged,
+            matrix_displayname_rooms: None,
+            enable_mastodon: false,
+            mastodon_instance_url: None,
+            mastodon_field_name: None,
+            mastodon_display_name: None,
+            enable_bluesky: false,
+            bluesky_pds_url: None,
+            bluesky_identifier: None,
+            bluesky_display_name: None,
+            enable_mqtt: false,
+            mqtt_broker_url: None,
+            mqtt_username: None,
+            mqtt_fronters_topic: None,
+            mqtt_status_topic: None,
+            mqtt_fronter_count_topic: None,
+            mqtt_home_assistant_discovery: false,
+            mqtt_home_assistant_discovery_prefix: None,
+            enable_pluralkit_system_sync: false,
+            enable_from_pluralkit: false,
+            pluralkit_switch_order: PluralKitSwitchOrder::PreservePluralKitOrder,
+            pluralkit_switch_primary_member: None,
             privacy_fine_grained_buckets: Some(vec!["blabla".to_owned()]),
+            website_show_custom_status: false,
+            discord_show_custom_status: false,
+            discord_status_message_show_custom_status: false,
+            vrchat_show_custom_status: false,
+            fronting_source: FrontingSourceKind::SimplyPlural,
             enable_discord: false,
             enable_discord_status_message: false,
             enable_vrchat: false,
@@ -408,10 +1503,22 @@ mod tests {
             vrchat_cookie: None,
             valid_constraints: None,
             pluralkit_token: None,
+            webhook_signing_secret: None,
+            discord_webhook_url: None,
+            matrix_access_token: None,
+            mastodon_token: None,
+            bluesky_app_password: None,
+            mqtt_password: None,
+            pluralkit_dispatch_signing_token: None,
         };
 
-        let (config_for_updater, _) =
-            create_config_with_strong_constraints(&user_id, &unused_client, &db_config).unwrap();
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
         assert!(!config_for_updater.enable_website);
         assert_eq!(config_for_updater.website_system_name, "");
         assert_eq!(config_for_updater.website_url_name, "");
@@ -420,10 +1527,518 @@ mod tests {
         db_config.enable_website = true;
         db_config.website_system_name = None;
 
-        let result = create_config_with_strong_constraints(&user_id, &unused_client, &db_config);
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_create_config_with_strong_constraints_pluralkit_source() {
+        let user_id = UserId {
+            inner: uuid::Uuid::new_v4(),
+        };
+        let unused_client = reqwest::Client::new();
+
+        // the simply plural token is not needed, if pluralkit is the source
+        let mut db_config = UserConfigDbEntries::<Decrypted> {
+            fronting_source: FrontingSourceKind::PluralKit,
+            simply_plural_token: None,
+            pluralkit_token: Some(Decrypted {
+                secret: "pk_token_123".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
+        assert_eq!(config_for_updater.simply_plural_token.secret, "");
+        assert_eq!(config_for_updater.pluralkit_token.secret, "pk_token_123");
+
+        // syncing back to pluralkit would only echo the source
+        db_config.enable_to_pluralkit = true;
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+
+        // the pluralkit token is mandatory for the pluralkit source
+        db_config.enable_to_pluralkit = false;
+        db_config.pluralkit_token = None;
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_create_config_with_strong_constraints_status_templates() {
+        let user_id = UserId {
+            inner: uuid::Uuid::new_v4(),
+        };
+        let unused_client = reqwest::Client::new();
+
+        let mut db_config = UserConfigDbEntries::<Decrypted> {
+            simply_plural_token: Some(Decrypted {
+                secret: "sp_token_123".to_string(),
+            }),
+            vrchat_status_templates: Some(vec!["{names|join(\"/\")}".to_owned(), String::new()]),
+            ..Default::default()
+        };
+
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
+        assert_eq!(config_for_updater.vrchat_status_templates.len(), 1);
+        assert!(config_for_updater.discord_status_templates.is_empty());
+
+        // invalid templates are rejected when saving the config
+        db_config.discord_status_templates = Some(vec!["{names|join}".to_owned()]);
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_create_config_with_strong_constraints_display_name_fields() {
+        let user_id = UserId {
+            inner: uuid::Uuid::new_v4(),
+        };
+        let unused_client = reqwest::Client::new();
+
+        let db_config = UserConfigDbEntries::<Decrypted> {
+            simply_plural_token: Some(Decrypted {
+                secret: "sp_token_123".to_string(),
+            }),
+            display_name_fields: serde_json::from_str(
+                r#"{ "Discord": "", "Matrix": " Nickname ", "Mqtt": "Nickname" }"#,
+            )
+            .unwrap(),
+            ..Default::default()
+        };
+
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
+
+        // unset platforms keep the default, an empty field disables it
+        let vrchat_status_name = plurality::SIMPLY_PLURAL_VRCHAT_STATUS_NAME_FIELD_NAME.to_owned();
+        assert_eq!(
+            config_for_updater.display_name_fields,
+            HashMap::from([
+                (DisplayNamePlatform::VRChat, vrchat_status_name.clone()),
+                (
+                    DisplayNamePlatform::DiscordStatusMessage,
+                    vrchat_status_name
+                ),
+                (DisplayNamePlatform::Matrix, "Nickname".to_owned()),
+                (DisplayNamePlatform::Mqtt, "Nickname".to_owned()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_create_config_with_strong_constraints_webhook() {
+        let user_id = UserId {
+            inner: uuid::Uuid::new_v4(),
+        };
+        let unused_client = reqwest::Client::new();
+
+        let mut db_config = UserConfigDbEntries::<Decrypted> {
+            simply_plural_token: Some(Decrypted {
+                secret: "sp_token_123".to_string(),
+            }),
+            enable_webhook: true,
+            webhook_url: Some("https://example.com/hook".to_owned()),
+            webhook_signing_secret: Some(Decrypted {
+                secret: "webhook_secret_xyz".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
+        assert_eq!(config_for_updater.webhook_url, "https://example.com/hook");
+        assert_eq!(
+            config_for_updater.webhook_signing_secret.secret,
+            "webhook_secret_xyz"
+        );
+
+        db_config.webhook_url = Some("ftp://example.com/hook".to_owned());
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+
+        // the webhook is only delivered with a signature
+        db_config.webhook_url = Some("https://example.com/hook".to_owned());
+        db_config.webhook_signing_secret = None;
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+
+        db_config.enable_webhook = false;
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_create_config_with_strong_constraints_matrix() {
+        let user_id = UserId {
+            inner: uuid::Uuid::new_v4(),
+        };
+        let unused_client = reqwest::Client::new();
+
+        let mut db_config = UserConfigDbEntries::<Decrypted> {
+            simply_plural_token: Some(Decrypted {
+                secret: "sp_token_123".to_string(),
+            }),
+            enable_matrix: true,
+            matrix_homeserver_url: Some("https://matrix.example.org".to_owned()),
+            matrix_access_token: Some(Decrypted {
+                secret: "syt_matrix_token".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
+        assert_eq!(
+            config_for_updater.matrix_homeserver_url,
+            "https://matrix.example.org"
+        );
+        assert_eq!(
+            config_for_updater.matrix_displayname_scope,
+            MatrixDisplayNameScope::Unchanged
+        );
+
+        // the rooms must be known, if the displayname is set per room
+        db_config.matrix_displayname_scope = MatrixDisplayNameScope::Rooms;
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+
+        db_config.matrix_displayname_rooms = Some(vec!["!room:example.org".to_owned()]);
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_ok());
+
+        db_config.matrix_access_token = None;
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_create_config_with_strong_constraints_mastodon() {
+        let user_id = UserId {
+            inner: uuid::Uuid::new_v4(),
+        };
+        let unused_client = reqwest::Client::new();
+
+        let mut db_config = UserConfigDbEntries::<Decrypted> {
+            simply_plural_token: Some(Decrypted {
+                secret: "sp_token_123".to_string(),
+            }),
+            enable_mastodon: true,
+            mastodon_instance_url: Some("https://mastodon.example".to_owned()),
+            mastodon_display_name: Some("  ".to_owned()),
+            mastodon_token: Some(Decrypted {
+                secret: "mastodon_token_123".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
+        assert_eq!(config_for_updater.mastodon_field_name, "Fronting");
+        assert_eq!(config_for_updater.mastodon_display_name, None);
+
+        db_config.mastodon_field_name = Some(" ".to_owned());
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+
+        db_config.mastodon_field_name = None;
+        db_config.mastodon_instance_url = Some("ftp://mastodon.example".to_owned());
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_create_config_with_strong_constraints_bluesky() {
+        let user_id = UserId {
+            inner: uuid::Uuid::new_v4(),
+        };
+        let unused_client = reqwest::Client::new();
+
+        let mut db_config = UserConfigDbEntries::<Decrypted> {
+            simply_plural_token: Some(Decrypted {
+                secret: "sp_token_123".to_string(),
+            }),
+            enable_bluesky: true,
+            bluesky_identifier: Some("oursystem.bsky.social".to_owned()),
+            bluesky_app_password: Some(Decrypted {
+                secret: "abcd-efgh-ijkl-mnop".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
+        assert_eq!(config_for_updater.bluesky_pds_url, "https://bsky.social");
+        assert_eq!(
+            config_for_updater.bluesky_identifier,
+            "oursystem.bsky.social"
+        );
+
+        db_config.bluesky_pds_url = Some("not a url".to_owned());
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+
+        db_config.bluesky_pds_url = None;
+        db_config.bluesky_app_password = None;
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_create_config_with_strong_constraints_mqtt() {
+        let user_id = UserId {
+            inner: uuid::Uuid::new_v4(),
+        };
+        let unused_client = reqwest::Client::new();
+
+        // anonymous clients are allowed, hence neither username nor password are needed
+        let mut db_config = UserConfigDbEntries::<Decrypted> {
+            simply_plural_token: Some(Decrypted {
+                secret: "sp_token_123".to_string(),
+            }),
+            enable_mqtt: true,
+            mqtt_broker_url: Some("mqtt://homeassistant.local".to_owned()),
+            ..Default::default()
+        };
+
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
+        assert_eq!(config_for_updater.mqtt_status_topic, "pluralsync/status");
+        assert_eq!(config_for_updater.mqtt_username, None);
+        assert!(config_for_updater.mqtt_password.is_none());
+        assert_eq!(config_for_updater.mqtt_home_assistant_discovery_prefix, "");
+
+        db_config.mqtt_home_assistant_discovery = true;
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
+        assert_eq!(
+            config_for_updater.mqtt_home_assistant_discovery_prefix,
+            "homeassistant"
+        );
+
+        db_config.mqtt_status_topic = Some("pluralsync/#".to_owned());
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+
+        db_config.mqtt_status_topic = None;
+        db_config.mqtt_broker_url = Some("https://homeassistant.local".to_owned());
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_create_config_with_strong_constraints_pluralkit_system_sync() {
+        let user_id = UserId {
+            inner: uuid::Uuid::new_v4(),
+        };
+        let unused_client = reqwest::Client::new();
+
+        // the system sync needs the pluralkit token, even if the fronting status isn't synced
+        let mut db_config = UserConfigDbEntries::<Decrypted> {
+            simply_plural_token: Some(Decrypted {
+                secret: "sp_token_123".to_string(),
+            }),
+            enable_pluralkit_system_sync: true,
+            ..Default::default()
+        };
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+
+        db_config.pluralkit_token = Some(Decrypted {
+            secret: "pk_token_123".to_string(),
+        });
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
+        assert!(config_for_updater.enable_pluralkit_system_sync);
+        assert!(!config_for_updater.enable_to_pluralkit);
+        assert_eq!(config_for_updater.pluralkit_token.secret, "pk_token_123");
+
+        // the members are synced from simply plural
+        db_config.fronting_source = FrontingSourceKind::PluralKit;
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_create_config_with_strong_constraints_from_pluralkit() {
+        let user_id = UserId {
+            inner: uuid::Uuid::new_v4(),
+        };
+        let unused_client = reqwest::Client::new();
+
+        // syncing only from pluralkit without syncing back would let both systems drift apart
+        let mut db_config = UserConfigDbEntries::<Decrypted> {
+            simply_plural_token: Some(Decrypted {
+                secret: "sp_token_123".to_string(),
+            }),
+            pluralkit_token: Some(Decrypted {
+                secret: "pk_token_123".to_string(),
+            }),
+            enable_from_pluralkit: true,
+            ..Default::default()
+        };
+        let result = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        );
+        assert!(result.is_err());
+
+        db_config.enable_to_pluralkit = true;
+        let (config_for_updater, _) = create_config_with_strong_constraints(
+            &user_id,
+            &unused_client,
+            &plurality::SimplyPluralEndpoints::default(),
+            &db_config,
+        )
+        .unwrap();
+        assert!(config_for_updater.enable_from_pluralkit);
+        assert_eq!(config_for_updater.pluralkit_token.secret, "pk_token_123");
+    }
+
     #[test]
     fn test_user_config_db_entries_serialization() {
         let config = UserConfigDbEntries::<Decrypted> {
@@ -439,6 +2054,66 @@ mod tests {
             respect_front_notifications_disabled: false,
             privacy_fine_grained: PrivacyFineGrained::ViaFriend,
             privacy_fine_grained_buckets: Some(vec!["bucket1".to_string(), "bucket2".to_string()]),
+            fronting_source: FrontingSourceKind::SimplyPlural,
+            website_show_custom_status: false,
+            discord_show_custom_status: false,
+            discord_status_message_show_custom_status: false,
+            vrchat_show_custom_status: false,
+            status_group_collapsing: GroupCollapsing::WhenTooLong,
+            fronter_order: FronterOrder::SourceOrder,
+            fronter_order_manual: None,
+            display_name_fields: DisplayNameFields::default(),
+            front_history_retention_days: None,
+            updater_log_retention_days: None,
+            vrchat_status_templates: None,
+            discord_status_templates: None,
+            discord_status_message_status_templates: None,
+            website_status_templates: None,
+            vrchat_unsupported_characters: UnsupportedCharacters::Drop,
+            vrchat_status_prefix: None,
+            discord_status_prefix: None,
+            discord_status_message_status_prefix: None,
+            website_status_prefix: None,
+            vrchat_status_no_fronts: None,
+            discord_status_no_fronts: None,
+            discord_status_message_status_no_fronts: None,
+            website_status_no_fronts: None,
+            vrchat_status_truncate_names_to: None,
+            discord_status_truncate_names_to: None,
+            discord_status_message_status_truncate_names_to: None,
+            website_status_truncate_names_to: None,
+            vrchat_status_group_collapsing: None,
+            discord_status_group_collapsing: None,
+            discord_status_message_status_group_collapsing: None,
+            website_status_group_collapsing: None,
+            enable_webhook: true,
+            webhook_url: Some("https://example.com/hook".to_string()),
+            enable_discord_webhook: true,
+            discord_webhook_edit_message: true,
+            enable_matrix: true,
+            matrix_homeserver_url: Some("https://matrix.example.org".to_string()),
+            matrix_displayname_scope: MatrixDisplayNameScope::Rooms,
+            matrix_displayname_rooms: Some(vec!["!room:example.org".to_string()]),
+            enable_mastodon: true,
+            mastodon_instance_url: Some("https://mastodon.example".to_string()),
+            mastodon_field_name: Some("Fronting".to_string()),
+            mastodon_display_name: Some("Our System".to_string()),
+            enable_bluesky: true,
+            bluesky_pds_url: Some("https://bsky.social".to_string()),
+            bluesky_identifier: Some("oursystem.bsky.social".to_string()),
+            bluesky_display_name: Some("Our System".to_string()),
+            enable_mqtt: true,
+            mqtt_broker_url: Some("mqtt://homeassistant.local:1883".to_string()),
+            mqtt_username: Some("pluralsync".to_string()),
+            mqtt_fronters_topic: Some("pluralsync/fronters".to_string()),
+            mqtt_status_topic: Some("pluralsync/status".to_string()),
+            mqtt_fronter_count_topic: Some("pluralsync/fronter_count".to_string()),
+            mqtt_home_assistant_discovery: true,
+            mqtt_home_assistant_discovery_prefix: Some("homeassistant".to_string()),
+            enable_pluralkit_system_sync: true,
+            enable_from_pluralkit: true,
+            pluralkit_switch_order: PluralKitSwitchOrder::PrimaryMemberFirst,
+            pluralkit_switch_primary_member: Some("abcde".to_string()),
             enable_discord: true,
             enable_discord_status_message: true,
             enable_vrchat: false,
@@ -455,6 +2130,27 @@ mod tests {
             pluralkit_token: Some(Decrypted {
                 secret: "pk_token_123".to_string(),
             }),
+            webhook_signing_secret: Some(Decrypted {
+                secret: "webhook_secret_xyz".to_string(),
+            }),
+            discord_webhook_url: Some(Decrypted {
+                secret: "https://discord.com/api/webhooks/1/token".to_string(),
+            }),
+            matrix_access_token: Some(Decrypted {
+                secret: "syt_matrix_token".to_string(),
+            }),
+            mastodon_token: Some(Decrypted {
+                secret: "mastodon_token_123".to_string(),
+            }),
+            bluesky_app_password: Some(Decrypted {
+                secret: "abcd-efgh-ijkl-mnop".to_string(),
+            }),
+            mqtt_password: Some(Decrypted {
+                secret: "mqtt_password_123".to_string(),
+            }),
+            pluralkit_dispatch_signing_token: Some(Decrypted {
+                secret: "pk_dispatch_token_456".to_string(),
+            }),
             valid_constraints: None,
         };
 
@@ -472,6 +2168,68 @@ mod tests {
     "bucket1",
     "bucket2"
   ],
+  "fronting_source": "SimplyPlural",
+  "website_show_custom_status": false,
+  "discord_show_custom_status": false,
+  "discord_status_message_show_custom_status": false,
+  "vrchat_show_custom_status": false,
+  "status_group_collapsing": "WhenTooLong",
+  "fronter_order": "SourceOrder",
+  "fronter_order_manual": null,
+  "display_name_fields": {},
+  "front_history_retention_days": null,
+  "updater_log_retention_days": null,
+  "vrchat_status_templates": null,
+  "discord_status_templates": null,
+  "discord_status_message_status_templates": null,
+  "website_status_templates": null,
+  "vrchat_unsupported_characters": "Drop",
+  "vrchat_status_prefix": null,
+  "discord_status_prefix": null,
+  "discord_status_message_status_prefix": null,
+  "website_status_prefix": null,
+  "vrchat_status_no_fronts": null,
+  "discord_status_no_fronts": null,
+  "discord_status_message_status_no_fronts": null,
+  "website_status_no_fronts": null,
+  "vrchat_status_truncate_names_to": null,
+  "discord_status_truncate_names_to": null,
+  "discord_status_message_status_truncate_names_to": null,
+  "website_status_truncate_names_to": null,
+  "vrchat_status_group_collapsing": null,
+  "discord_status_group_collapsing": null,
+  "discord_status_message_status_group_collapsing": null,
+  "website_status_group_collapsing": null,
+  "enable_webhook": true,
+  "webhook_url": "https://example.com/hook",
+  "enable_discord_webhook": true,
+  "discord_webhook_edit_message": true,
+  "enable_matrix": true,
+  "matrix_homeserver_url": "https://matrix.example.org",
+  "matrix_displayname_scope": "Rooms",
+  "matrix_displayname_rooms": [
+    "!room:example.org"
+  ],
+  "enable_mastodon": true,
+  "mastodon_instance_url": "https://mastodon.example",
+  "mastodon_field_name": "Fronting",
+  "mastodon_display_name": "Our System",
+  "enable_bluesky": true,
+  "bluesky_pds_url": "https://bsky.social",
+  "bluesky_identifier": "oursystem.bsky.social",
+  "bluesky_display_name": "Our System",
+  "enable_mqtt": true,
+  "mqtt_broker_url": "mqtt://homeassistant.local:1883",
+  "mqtt_username": "pluralsync",
+  "mqtt_fronters_topic": "pluralsync/fronters",
+  "mqtt_status_topic": "pluralsync/status",
+  "mqtt_fronter_count_topic": "pluralsync/fronter_count",
+  "mqtt_home_assistant_discovery": true,
+  "mqtt_home_assistant_discovery_prefix": "homeassistant",
+  "enable_pluralkit_system_sync": true,
+  "enable_from_pluralkit": true,
+  "pluralkit_switch_order": "PrimaryMemberFirst",
+  "pluralkit_switch_primary_member": "abcde",
   "enable_website": false,
   "enable_discord": true,
   "enable_discord_status_message": true,
@@ -490,6 +2248,27 @@ mod tests {
   "vrchat_cookie": null,
   "pluralkit_token": {
     "secret": "pk_token_123"
+  },
+  "webhook_signing_secret": {
+    "secret": "webhook_secret_xyz"
+  },
+  "discord_webhook_url": {
+    "secret": "https://discord.com/api/webhooks/1/token"
+  },
+  "matrix_access_token": {
+    "secret": "syt_matrix_token"
+  },
+  "mastodon_token": {
+    "secret": "mastodon_token_123"
+  },
+  "bluesky_app_password": {
+    "secret": "abcd-efgh-ijkl-mnop"
+  },
+  "mqtt_password": {
+    "secret": "mqtt_password_123"
+  },
+  "pluralkit_dispatch_signing_token": {
+    "secret": "pk_dispatch_token_456"
   }
 }"#;
 
@@ -45,9 +45,13 @@ pub async fn post_api_user_config(
     log::info!("# | POST /api/user/config_and_restart | {user_id}");
 
     // check that config satisfies contraints
-    let (_, valid_db_config) =
-        config::create_config_with_strong_constraints(&user_id, client, &config)
-            .map_err(expose_internal_error)?;
+    let (_, valid_db_config) = config::create_config_with_strong_constraints(
+        &user_id,
+        client,
+        &shared_updaters.simply_plural_endpoints,
+        &config,
+    )
+    .map_err(expose_internal_error)?;
 
     log::info!("# | POST /api/user/config_and_restart | {user_id} | config_valid");
 
@@ -22,6 +22,8 @@ export PBUCKET_MEMBER_YES="68e23eb0d3877ebeb6000000"
 export PBUCKET_MEMBER_YES_PK="kapnfj"
 export CUSTOM_FRONT_1_ID="688d41c8aa2e477e53000000"
 
+export SIMPLY_PLURAL_BASE_URL="${SIMPLY_PLURAL_BASE_URL:-https://api.apparyllis.com/v1}"
+
 
 set_system_fronts_set() {
     SET="$1"
@@ -53,7 +55,7 @@ set_to_front() {
     FRONT_ID="$(openssl rand -hex 12)" # produces valid 24 hexdec digits
     UNIX_MILLIS_CURRENT="$(date +%s%3N)"
     UNIX_MILLIS_5_MIN_AGO="$((UNIX_MILLIS_CURRENT - 5*60*1000))"
-    curl --silent --fail-with-body -L "https://api.apparyllis.com/v1/frontHistory/$FRONT_ID" \
+    curl --silent --fail-with-body -L "$SIMPLY_PLURAL_BASE_URL/frontHistory/$FRONT_ID" \
         -H 'Content-Type: application/json' \
         -H "Authorization: $SPS_API_WRITE_TOKEN" \
         -d "{
@@ -73,7 +75,7 @@ clear_all_fronts() {
 
     FRONTER_IDS="$(
         curl --silent \
-            -L 'https://api.apparyllis.com/v1/fronters/' \
+            -L "$SIMPLY_PLURAL_BASE_URL/fronters/" \
             -H "Authorization: $SPS_API_WRITE_TOKEN" |
             jq -r '.[].id'
     )"
@@ -86,7 +88,7 @@ clear_all_fronts() {
         
         echo "Clearing front (id=$fronter_id)"
         
-        curl --silent -L -X PATCH "https://api.apparyllis.com/v1/frontHistory/$fronter_id" \
+        curl --silent -L -X PATCH "$SIMPLY_PLURAL_BASE_URL/frontHistory/$fronter_id" \
             -H 'Content-Type: application/json' \
             -H "Authorization: $SPS_API_WRITE_TOKEN" \
             -d '{
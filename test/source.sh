@@ -15,6 +15,16 @@ export PRIVACY_FINE_GRAINED_BUCKETS="[\"68e23553d3877cbeb6000000\"]"
 
 source docker/source.sh # await
 
+# Use the mock Simply Plural server (src/bin/mock-simply-plural.rs) instead of the real API.
+# It must be started separately, e.g. `MOCK_SIMPLY_PLURAL_TOKEN=mock ROCKET_PORT=8090 cargo run --features mock --bin mock-simply-plural`
+if [[ "${MOCK_SIMPLY_PLURAL-false}" == "true" ]]; then
+    MOCK_SIMPLY_PLURAL_PORT="${MOCK_SIMPLY_PLURAL_PORT-8090}"
+    export SIMPLY_PLURAL_BASE_URL="http://localhost:${MOCK_SIMPLY_PLURAL_PORT}/v1"
+    export SIMPLY_PLURAL_WEBSOCKET_URL="ws://localhost:${MOCK_SIMPLY_PLURAL_PORT}/v1/socket"
+    export SPS_API_TOKEN="${MOCK_SIMPLY_PLURAL_TOKEN-mock}"
+    export SPS_API_WRITE_TOKEN="$SPS_API_TOKEN"
+fi
+
 get_user_config_json() {
 
     if [ -v DISCORD_STATUS_MESSAGE_TOKEN ] ; then 
@@ -42,6 +42,10 @@ main() {
     sleep "$SECONDS_BETWEEN_UPDATES"s
     check_system_fronts_set "D-limited-visibility"
 
+    check_front_history_has_at_least_entries 4
+    check_updaters_log_has_successful_update "DiscordStatusMessage"
+    check_updaters_log_has_successful_update "ToPluralKit"
+
 
     clear_all_fronts
     echo "✅✅✅ Updater Integration Test ✅✅✅"
@@ -101,6 +105,38 @@ check_to_pluralkit_fronters_equals() {
     [[ "$MEMBERS" == "$EXPECTED" ]]
 }
 
+check_front_history_has_at_least_entries() {
+    EXPECTED="$1"
+
+    RESPONSE="$(
+        curl -s --fail-with-body \
+            -H "Authorization: Bearer $JWT" \
+            "$BASE_URL/api/user/front-history?page_size=100"
+    )"
+
+    COUNT="$( echo "$RESPONSE" | jq -r '.entries | length' )"
+
+    echo "Front History Check: '$COUNT' >= '$EXPECTED'"
+
+    [[ "$COUNT" -ge "$EXPECTED" ]]
+}
+
+check_updaters_log_has_successful_update() {
+    PLATFORM="$1"
+
+    RESPONSE="$(
+        curl -s --fail-with-body \
+            -H "Authorization: Bearer $JWT" \
+            "$BASE_URL/api/updaters/log"
+    )"
+
+    COUNT="$( echo "$RESPONSE" | jq -r --arg p "$PLATFORM" '[.[] | select(.platform == $p and .success)] | length' )"
+
+    echo "Updaters Log Check: '$PLATFORM' has '$COUNT' successful updates"
+
+    [[ "$COUNT" -ge 1 ]]
+}
+
 
 export BASE_URL="http://localhost:8080"
 